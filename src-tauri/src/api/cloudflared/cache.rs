@@ -3,9 +3,10 @@
 use chrono::{DateTime, Utc};
 use serde_json;
 use std::path::{Path, PathBuf};
-use tauri::{AppHandle, Manager, Runtime};
+use tauri::{AppHandle, Runtime};
 
 use crate::api::cloudflared::config::CACHE_INFO_FILENAME;
+use crate::api::n8n::base_dir;
 use crate::api::cloudflared::error::{CloudflaredError, CloudflaredResult};
 use crate::api::cloudflared::models::CloudflaredCacheInfo;
 use crate::api::cloudflared::platform::PlatformDetector;
@@ -54,7 +55,7 @@ impl CacheManager {
         &self,
         app: &AppHandle<R>,
     ) -> CloudflaredResult<(bool, Option<i64>)> {
-        let app_data_dir = app.path().app_data_dir().map_err(|error| {
+        let app_data_dir = base_dir(app).map_err(|error| {
             CloudflaredError::filesystem(format!("获取应用数据目录失败: {}", error))
         })?;
 
@@ -88,7 +89,7 @@ impl CacheManager {
         &self,
         app: &AppHandle<R>,
     ) -> CloudflaredResult<Option<PathBuf>> {
-        let app_data_dir = app.path().app_data_dir().map_err(|error| {
+        let app_data_dir = base_dir(app).map_err(|error| {
             CloudflaredError::filesystem(format!("获取应用数据目录失败: {}", error))
         })?;
 
@@ -130,7 +131,7 @@ impl CacheManager {
 
         if let Some(age) = cache_age_days {
             if age > max_age_days {
-                let app_data_dir = app.path().app_data_dir().map_err(|error| {
+                let app_data_dir = base_dir(app).map_err(|error| {
                     CloudflaredError::filesystem(format!("获取应用数据目录失败: {}", error))
                 })?;
 
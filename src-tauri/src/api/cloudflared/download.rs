@@ -7,7 +7,7 @@ use crate::api::cloudflared::config::PlatformDownloadConfig;
 use crate::api::cloudflared::error::{CloudflaredError, CloudflaredResult};
 use crate::api::cloudflared::path_resolver::CloudflaredPathResolver;
 use crate::i18n;
-use crate::services::downloader;
+use crate::services::{archive, downloader};
 
 /// 下载管理器
 #[derive(Debug, Clone)]
@@ -155,7 +155,10 @@ impl DownloadManager {
         })?;
 
         let tar_decoder = GzDecoder::new(tar_gz_file);
-        let mut archive = Archive::new(tar_decoder);
+        let mut archive = Archive::new(archive::SizeCappedReader::new(
+            tar_decoder,
+            archive::MAX_ARCHIVE_UNCOMPRESSED_SIZE,
+        ));
 
         for entry_result in archive.entries().map_err(|error| {
             CloudflaredError::extraction(format!("{}: {}", i18n::t("fs.cannot_read_archive_entry"), error))
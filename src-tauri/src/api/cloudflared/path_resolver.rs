@@ -6,6 +6,7 @@ use tauri::{AppHandle, Manager, Runtime};
 use crate::api::cloudflared::config::CACHE_INFO_FILENAME;
 use crate::api::cloudflared::error::{CloudflaredError, CloudflaredResult};
 use crate::api::cloudflared::platform::PlatformDetector;
+use crate::api::n8n::base_dir;
 use crate::i18n;
 
 /// Cloudflared 路径解析器
@@ -77,7 +78,7 @@ impl CloudflaredPathResolver {
         &self,
         app: &AppHandle<R>,
     ) -> CloudflaredResult<Option<String>> {
-        let app_data_dir = app.path().app_data_dir().map_err(|error| {
+        let app_data_dir = base_dir(app).map_err(|error| {
             CloudflaredError::filesystem(format!("获取应用数据目录失败: {}", error))
         })?;
 
@@ -167,7 +168,7 @@ impl CloudflaredPathResolver {
         &self,
         app: &AppHandle<R>,
     ) -> CloudflaredResult<PathBuf> {
-        let app_data_dir = app.path().app_data_dir().map_err(|error| {
+        let app_data_dir = base_dir(app).map_err(|error| {
             CloudflaredError::filesystem(format!("获取应用数据目录失败: {}", error))
         })?;
 
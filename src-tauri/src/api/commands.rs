@@ -1,14 +1,18 @@
 //! 主命令模块 - 整合所有功能模块
 
+use std::path::PathBuf;
+
 // 导入 Tauri 相关依赖
 use tauri::{AppHandle, Runtime, Window};
 
 // 从父模块导入功能模块
-use crate::api::{cloudflared, n8n, tunnel};
+use crate::api::{cloudflared, n8n, settings, tunnel};
+use crate::services::download_control;
 use crate::i18n;
 
 // 重新导出类型定义，但不重新导出函数（避免宏冲突）
 pub use cloudflared::{CloudflaredCacheInfo, CloudflaredVersionInfo};
+pub use settings::{AppSettings, LogLevel, LogOutput};
 pub use tunnel::{TunnelConfig, TunnelError, TunnelEvent, TunnelHealth, TunnelHealthStatus};
 
 /// 向后兼容的包装函数 - 检查 n8n 是否已安装
@@ -17,28 +21,306 @@ pub async fn is_installed<R: Runtime>(app: AppHandle<R>) -> bool {
     n8n::is_installed(app)
 }
 
+/// 获取未完成的安装状态（安装中途重启后用于恢复安装）
+#[tauri::command]
+pub async fn get_pending_install<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Option<n8n::InstallState>, String> {
+    n8n::get_pending_install(app).map_err(|e| e.to_string())
+}
+
+/// 获取当前安装的完整性校验结果（已校验 / 跳过原因 / 资源包哈希）
+#[tauri::command]
+pub async fn get_verification_status<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Option<n8n::InstallVerification>, String> {
+    n8n::get_verification_status(app).map_err(|e| e.to_string())
+}
+
 /// 向后兼容的包装函数 - 设置 Node 运行环境
 #[tauri::command]
-pub async fn setup_runtime<R: Runtime>(window: Window<R>) -> Result<(), String> {
-    n8n::setup_runtime(window).await.map_err(|e| e.to_string())
+pub async fn setup_runtime<R: Runtime>(
+    window: Window<R>,
+    source: Option<PathBuf>,
+) -> Result<(), String> {
+    n8n::setup_runtime(window, source)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 /// 向后兼容的包装函数 - 安装 n8n 核心包
 #[tauri::command]
-pub async fn setup_n8n<R: tauri::Runtime>(window: tauri::Window<R>) -> Result<(), String> {
-    n8n::setup_n8n(window).await.map_err(|e| e.to_string())
+pub async fn setup_n8n<R: tauri::Runtime>(
+    window: tauri::Window<R>,
+    source: Option<PathBuf>,
+) -> Result<(), String> {
+    n8n::setup_n8n(window, source)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 向后兼容的包装函数 - 启动本地 n8n 进程（`tunnel` 指定是否以隧道模式启动，未指定时按设置）
+#[tauri::command]
+pub async fn launch_n8n<R: Runtime>(app: AppHandle<R>, tunnel: Option<bool>) -> Result<(), String> {
+    n8n::launch_n8n(app, tunnel).map_err(|e| e.to_string())
+}
+
+/// 以安全模式启动 n8n（不加载社区节点与自定义扩展）
+#[tauri::command]
+pub async fn launch_n8n_safe<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    n8n::launch_n8n_safe(app).map_err(|e| e.to_string())
+}
+
+/// 重启本次会话中启动过的 n8n（先正常停止并等待旧进程退出）
+#[tauri::command]
+pub async fn restart_n8n<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    // 停止旧进程时最长等待 `shutdown_timeout_secs`，放到阻塞线程池中执行
+    tokio::task::spawn_blocking(move || n8n::relaunch_n8n(app))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// 启动 n8n 并等待服务就绪（返回健康检查结果）
+#[tauri::command]
+pub async fn launch_n8n_and_wait<R: Runtime>(app: AppHandle<R>) -> Result<String, String> {
+    n8n::launch_n8n_and_wait(app).await.map_err(|e| e.to_string())
+}
+
+/// 以诊断模式启动 n8n，收集启动阶段的完整输出（`window_secs` 默认 30 秒）
+#[tauri::command]
+pub async fn launch_n8n_diagnostic<R: Runtime>(
+    app: AppHandle<R>,
+    window_secs: Option<u64>,
+) -> Result<n8n::BootTrace, String> {
+    n8n::launch_n8n_diagnostic(app, window_secs)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 修复运行时与 n8n 可执行文件的权限（从备份恢复后使用）
+#[tauri::command]
+pub async fn fix_permissions<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<n8n::PermissionFixReport, String> {
+    n8n::fix_permissions(app).map_err(|e| e.to_string())
+}
+
+/// 校验现有安装的完整性（仅报告，不修改文件），报告中列出需要修复的组件
+#[tauri::command]
+pub async fn verify_installation<R: Runtime>(app: AppHandle<R>) -> Result<n8n::VerifyReport, String> {
+    n8n::verify_installation(app).await.map_err(|e| e.to_string())
+}
+
+/// 获取传给 n8n 的实际环境变量（敏感值已脱敏）
+#[tauri::command]
+pub fn get_effective_env<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<std::collections::BTreeMap<String, String>, String> {
+    n8n::get_effective_env(app).map_err(|e| e.to_string())
+}
+
+/// 获取 n8n 二进制数据目录
+#[tauri::command]
+pub fn get_binary_data_dir<R: Runtime>(app: AppHandle<R>) -> Result<std::path::PathBuf, String> {
+    n8n::get_binary_data_dir(app).map_err(|e| e.to_string())
+}
+
+/// 列出已安装的 n8n 核心版本
+#[tauri::command]
+pub fn list_installed_versions<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<n8n::InstalledVersion>, String> {
+    n8n::list_installed_versions(app).map_err(|e| e.to_string())
+}
+
+/// 获取已安装的 n8n 版本及是否有可用更新（未安装时为 None）
+#[tauri::command]
+pub async fn get_installed_n8n_version<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Option<n8n::N8nVersionStatus>, String> {
+    n8n::get_installed_n8n_version(app).await.map_err(|e| e.to_string())
+}
+
+/// 切换到已安装的 n8n 核心版本（会先停止 n8n）
+#[tauri::command]
+pub async fn activate_version<R: Runtime>(app: AppHandle<R>, tag: String) -> Result<(), String> {
+    // 停止 n8n 时最长等待 `shutdown_timeout_secs`，放到阻塞线程池中执行
+    tokio::task::spawn_blocking(move || n8n::activate_version(app, tag))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// 卸载 n8n（会先停止 n8n），`keep_user_data` 为 `false` 时同时删除工作流、凭据与数据库
+#[tauri::command]
+pub async fn uninstall_n8n<R: Runtime>(
+    app: AppHandle<R>,
+    keep_user_data: bool,
+) -> Result<n8n::UninstallSummary, String> {
+    // 停止 n8n 时最长等待 `shutdown_timeout_secs`，放到阻塞线程池中执行
+    tokio::task::spawn_blocking(move || n8n::uninstall_n8n(app, keep_user_data))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
 }
 
-/// 向后兼容的包装函数 - 启动本地 n8n 进程
+/// 取消进行中的安装（下载或解压），operation_id 为 `runtime` 或 `n8n-core`
 #[tauri::command]
-pub async fn launch_n8n<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
-    n8n::launch_n8n(app).map_err(|e| e.to_string())
+pub fn cancel_setup(operation_id: String) -> Result<(), String> {
+    download_control::cancel(&operation_id)
 }
 
-/// 向后兼容的包装函数 - 关闭 n8n 进程
+/// 取消所有进行中的下载，返回被取消的操作标识；已下载的临时文件会被删除
 #[tauri::command]
-pub fn shutdown_n8n() -> Result<(), String> {
-    n8n::shutdown_n8n().map_err(|e| e.to_string())
+pub fn cancel_download() -> Result<Vec<String>, String> {
+    let canceled = download_control::cancel_all();
+    if canceled.is_empty() {
+        return Err(i18n::t("download.none_active"));
+    }
+    Ok(canceled)
+}
+
+/// 获取当前平台信息（系统、架构、是否支持及下载资源）
+#[tauri::command]
+pub fn platform_info() -> n8n::PlatformInfo {
+    n8n::platform_info()
+}
+
+/// 查询 n8n 数据库迁移状态
+#[tauri::command]
+pub fn db_status<R: Runtime>(app: AppHandle<R>) -> Result<n8n::DbStatus, String> {
+    n8n::db_status(app).map_err(|e| e.to_string())
+}
+
+/// 压缩 SQLite 数据库（n8n 运行中时先停止，完成后重新启动）
+#[tauri::command]
+pub async fn vacuum_database<R: Runtime>(app: AppHandle<R>) -> Result<n8n::VacuumReport, String> {
+    // 停止 n8n 与压缩数据库都会阻塞，放到阻塞线程池中执行
+    tokio::task::spawn_blocking(move || n8n::vacuum_database(app))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// 迁移 n8n 数据目录并更新设置（n8n 运行中时先停止，完成后重新启动）
+#[tauri::command]
+pub async fn migrate_data_dir<R: Runtime>(
+    app: AppHandle<R>,
+    new_path: std::path::PathBuf,
+) -> Result<n8n::DataDirMigration, String> {
+    // 停止 n8n 与复制数据都会阻塞，放到阻塞线程池中执行
+    tokio::task::spawn_blocking(move || n8n::migrate_data_dir(app, new_path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// 切换 n8n 数据目录（不移动现有数据，下次启动 n8n 时生效；为空时恢复默认位置）
+#[tauri::command]
+pub fn set_data_dir<R: Runtime>(app: AppHandle<R>, path: String) -> Result<PathBuf, String> {
+    n8n::set_data_dir(app, path).map_err(|e| e.to_string())
+}
+
+/// 获取生效的 n8n 数据目录
+#[tauri::command]
+pub fn get_data_dir<R: Runtime>(app: AppHandle<R>) -> Result<PathBuf, String> {
+    n8n::get_data_dir(app).map_err(|e| e.to_string())
+}
+
+/// 导出全部凭据（`decrypted` 为 true 时导出明文，需先停止 n8n）
+#[tauri::command]
+pub async fn export_credentials<R: Runtime>(
+    app: AppHandle<R>,
+    dest: PathBuf,
+    decrypted: bool,
+) -> Result<String, String> {
+    n8n::export_credentials(app, dest, decrypted).map_err(|e| e.to_string())
+}
+
+/// 导入凭据（需先停止 n8n）
+#[tauri::command]
+pub async fn import_credentials<R: Runtime>(app: AppHandle<R>, src: PathBuf) -> Result<String, String> {
+    n8n::import_credentials(app, src).map_err(|e| e.to_string())
+}
+
+/// 不打开编辑器，在后台执行一次指定 ID 的工作流
+#[tauri::command]
+pub async fn execute_workflow<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+) -> Result<n8n::WorkflowExecution, String> {
+    n8n::execute_workflow(app, id).map_err(|e| e.to_string())
+}
+
+/// 导出诊断包（ZIP）用于问题反馈
+#[tauri::command]
+pub async fn export_diagnostics<R: Runtime>(app: AppHandle<R>, dest: PathBuf) -> Result<(), String> {
+    n8n::export_diagnostics(app, dest).map_err(|e| e.to_string())
+}
+
+/// 获取当前 n8n 日志文件路径（供前端打开日志目录）
+#[tauri::command]
+pub fn get_log_path<R: Runtime>(app: AppHandle<R>) -> Result<PathBuf, String> {
+    n8n::get_log_path(app).map_err(|e| e.to_string())
+}
+
+/// 获取 n8n 日志占用的字节数
+#[tauri::command]
+pub fn get_logs_size<R: Runtime>(app: AppHandle<R>) -> Result<u64, String> {
+    n8n::get_logs_size(app).map_err(|e| e.to_string())
+}
+
+/// 清理 n8n 日志（删除轮转日志并清空当前日志），返回释放的字节数
+#[tauri::command]
+pub fn clear_logs<R: Runtime>(app: AppHandle<R>) -> Result<u64, String> {
+    n8n::clear_logs(app).map_err(|e| e.to_string())
+}
+
+/// 暂停下载（operation_id 为下载类型，如 `runtime`、`n8n-core`）
+#[tauri::command]
+pub fn pause_download(operation_id: String) -> Result<(), String> {
+    download_control::pause(&operation_id)
+}
+
+/// 继续已暂停的下载
+#[tauri::command]
+pub fn resume_download(operation_id: String) -> Result<(), String> {
+    download_control::resume(&operation_id)
+}
+
+/// 读取 n8n 进程的 CPU 与内存占用
+#[tauri::command]
+pub fn process_metrics() -> Result<n8n::ProcessMetrics, String> {
+    n8n::metrics::process_metrics().map_err(|e| e.to_string())
+}
+
+/// 开始定时推送 `n8n-metrics` 事件（监控面板打开时调用）
+#[tauri::command]
+pub fn start_metrics_sampler<R: Runtime>(app: AppHandle<R>, interval_ms: Option<u64>) {
+    n8n::metrics::start_metrics_sampler(app, interval_ms);
+}
+
+/// 停止推送 `n8n-metrics` 事件
+#[tauri::command]
+pub fn stop_metrics_sampler() {
+    n8n::metrics::stop_metrics_sampler();
+}
+
+/// 启动前预检（运行时架构等）
+#[tauri::command]
+pub async fn preflight<R: Runtime>(app: AppHandle<R>) -> Result<n8n::PreflightReport, String> {
+    n8n::preflight(app).map_err(|e| e.to_string())
+}
+
+/// 向后兼容的包装函数 - 关闭 n8n 进程（返回是否终止了正在运行的进程）
+#[tauri::command]
+pub async fn shutdown_n8n() -> Result<bool, String> {
+    // 等待 n8n 正常退出最长需要 `shutdown_timeout_secs`，放到阻塞线程池中执行
+    tokio::task::spawn_blocking(n8n::shutdown_n8n)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
 }
 
 /// 向后兼容的包装函数 - 启动 Cloudflare Tunnel
@@ -127,10 +409,61 @@ pub async fn get_tunnel_errors<R: Runtime>(app: AppHandle<R>) -> Result<Vec<Tunn
     tunnel::get_tunnel_errors(app)
 }
 
-/// 向后兼容的包装函数 - 代理健康检查
+/// 检测 n8n 核心包来源是否可达
+#[tauri::command]
+pub async fn test_connectivity() -> Result<Vec<n8n::ConnectivityCheck>, String> {
+    n8n::test_connectivity().await.map_err(|e| e.to_string())
+}
+
+/// 测速各下载源并按吞吐量排序
 #[tauri::command]
-pub async fn proxy_health_check() -> Result<String, String> {
-    n8n::proxy_health_check().await.map_err(|e| e.to_string())
+pub async fn benchmark_mirrors() -> Result<Vec<n8n::MirrorBenchmark>, String> {
+    n8n::benchmark_mirrors().await.map_err(|e| e.to_string())
+}
+
+/// 向后兼容的包装函数 - 代理健康检查（可指定最多轮数、每轮失败后的等待毫秒数与探测路径）
+#[tauri::command]
+pub async fn proxy_health_check(
+    retries: Option<usize>,
+    interval_ms: Option<u64>,
+    endpoints: Option<Vec<String>>,
+) -> Result<String, String> {
+    n8n::proxy_health_check(retries, interval_ms, endpoints)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 查询 n8n 运行状态（进程是否存活、退出码与健康探测结果）
+#[tauri::command]
+pub async fn get_n8n_status() -> Result<n8n::N8nStatus, String> {
+    n8n::get_n8n_status().await.map_err(|e| e.to_string())
+}
+
+/// 获取 n8n 编辑器地址（包含部署子路径）
+#[tauri::command]
+pub fn get_editor_url() -> Result<String, String> {
+    n8n::get_editor_url().map_err(|e| e.to_string())
+}
+
+/// 在新窗口中打开 n8n 编辑器
+#[tauri::command]
+pub async fn open_editor_window<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+) -> Result<(), String> {
+    n8n::open_editor_window(app, label).await.map_err(|e| e.to_string())
+}
+
+/// 设置 n8n 日志级别（n8n 运行中时会自动重启，返回是否已重启）
+#[tauri::command]
+pub async fn set_log_level<R: Runtime>(
+    app: AppHandle<R>,
+    level: LogLevel,
+    output: Option<LogOutput>,
+) -> Result<bool, String> {
+    n8n::set_log_level(app, level, output)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 /// 设置节点解禁状态
@@ -205,6 +538,84 @@ pub async fn apply_tunnel_config<R: Runtime>(
     )
 }
 
+/// 获取应用设置
+#[tauri::command]
+pub async fn get_settings<R: Runtime>(app: AppHandle<R>) -> Result<AppSettings, String> {
+    settings::get_settings(app)
+}
+
+/// 更新应用设置
+#[tauri::command]
+pub async fn update_settings<R: Runtime>(
+    app: AppHandle<R>,
+    settings: AppSettings,
+) -> Result<(), Vec<settings::FieldError>> {
+    settings::update_settings(app, settings)
+}
+
+/// 修改应用日志级别
+#[tauri::command]
+pub async fn set_app_log_level<R: Runtime>(
+    app: AppHandle<R>,
+    level: crate::services::logging::AppLogLevel,
+) -> Result<(), String> {
+    settings::set_app_log_level(app, level)
+}
+
+/// 修改 Node.js 运行时与 n8n 核心包的下载源
+#[tauri::command]
+pub async fn set_download_mirror<R: Runtime>(
+    app: AppHandle<R>,
+    mirror: settings::DownloadMirror,
+) -> Result<(), String> {
+    settings::set_download_mirror(app, mirror)
+}
+
+/// 替换自定义 n8n 环境变量（如 `WEBHOOK_URL`），下次启动 n8n 时生效
+#[tauri::command]
+pub async fn set_n8n_env<R: Runtime>(
+    app: AppHandle<R>,
+    vars: std::collections::HashMap<String, String>,
+) -> Result<(), String> {
+    settings::set_n8n_env(app, vars)
+}
+
+/// 修改下载使用的 HTTP / HTTPS 代理（为空时使用 HTTP_PROXY / HTTPS_PROXY 环境变量）
+#[tauri::command]
+pub async fn set_http_proxy<R: Runtime>(app: AppHandle<R>, url: Option<String>) -> Result<(), String> {
+    settings::set_http_proxy(app, url)
+}
+
+/// 保存 GitHub 令牌到系统钥匙串（为空时删除）
+#[tauri::command]
+pub async fn set_github_token(token: Option<String>) -> Result<(), String> {
+    settings::set_github_token(token)
+}
+
+/// 是否已配置 GitHub 令牌
+#[tauri::command]
+pub async fn has_github_token() -> bool {
+    settings::has_github_token()
+}
+
+/// 保存 PostgreSQL 密码到系统钥匙串（为空时删除）
+#[tauri::command]
+pub async fn set_postgres_password(password: Option<String>) -> Result<(), String> {
+    settings::set_postgres_password(password)
+}
+
+/// 是否已配置 PostgreSQL 密码
+#[tauri::command]
+pub async fn has_postgres_password() -> bool {
+    settings::has_postgres_password()
+}
+
+/// 将应用设置恢复为默认值（旧设置备份为 config.json.bak；数据目录、数据库与自定义环境变量保持不变）
+#[tauri::command]
+pub async fn reset_settings<R: Runtime>(app: AppHandle<R>) -> Result<settings::SettingsReset, String> {
+    settings::reset_settings(app)
+}
+
 /// 切换侧边栏状态
 #[tauri::command]
 pub async fn toggle_sidebar<R: Runtime>(_window: Window<R>) -> Result<bool, String> {
@@ -4,5 +4,6 @@ pub mod commands;
 // 声明功能模块（这些文件在 src-tauri/src/api/ 目录下）
 pub mod cloudflared;
 pub mod n8n;
+pub mod settings;
 pub mod tunnel;
 pub mod utils;
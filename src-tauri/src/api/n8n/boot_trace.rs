@@ -0,0 +1,91 @@
+//! 启动过程捕获
+//!
+//! 排查偶发的启动失败时，以诊断模式启动 n8n 并在一段时间内（或直到就绪）收集全部输出，
+//! 一次性返回完整的启动记录，便于用户直接发给支持人员。与实时日志推送不同，这里只关心启动阶段。
+
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::error::N8nResult;
+use crate::services::manager::LogLineHandler;
+
+/// 默认的输出收集时长（秒）
+pub const DEFAULT_WINDOW_SECS: u64 = 30;
+
+/// 最多保留的输出行数（超出后丢弃后续输出）
+const MAX_TRACE_LINES: usize = 10_000;
+
+/// 结束收集前等待输出转发线程写完剩余内容的时间
+const OUTPUT_DRAIN_DELAY: Duration = Duration::from_millis(200);
+
+/// 一次诊断启动的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BootTrace {
+    /// 是否在收集时长内启动成功
+    pub ready: bool,
+    /// 启动成功时的就绪信息，失败时的错误信息
+    pub detail: String,
+    /// 收集到的完整输出（stdout 与 stderr 按到达顺序合并）
+    pub output: String,
+    /// 收集的行数
+    pub line_count: usize,
+    /// 输出是否因超过行数上限被截断
+    pub truncated: bool,
+    pub elapsed_ms: u64,
+}
+
+/// 启动输出收集器
+pub(crate) struct BootTraceCollector {
+    lines: Arc<Mutex<Vec<String>>>,
+    truncated: Arc<Mutex<bool>>,
+    started: Instant,
+}
+
+impl BootTraceCollector {
+    pub fn new() -> Self {
+        Self {
+            lines: Arc::new(Mutex::new(Vec::new())),
+            truncated: Arc::new(Mutex::new(false)),
+            started: Instant::now(),
+        }
+    }
+
+    /// 收集输出行的日志处理器
+    pub fn handler(&self) -> LogLineHandler {
+        let lines = self.lines.clone();
+        let truncated = self.truncated.clone();
+        Arc::new(move |_, line: &str| {
+            let mut lines = lines.lock().unwrap_or_else(|p| p.into_inner());
+            if lines.len() < MAX_TRACE_LINES {
+                lines.push(line.to_string());
+            } else {
+                *truncated.lock().unwrap_or_else(|p| p.into_inner()) = true;
+            }
+        })
+    }
+
+    /// 结束收集，结合启动等待的结果生成诊断记录
+    ///
+    /// 收集结束后 n8n 仍会继续输出，但不再记录。
+    pub async fn finish(self, result: N8nResult<String>) -> BootTrace {
+        tokio::time::sleep(OUTPUT_DRAIN_DELAY).await;
+
+        let (ready, detail) = match result {
+            Ok(message) => (true, message),
+            Err(error) => (false, error.to_string()),
+        };
+        let lines = std::mem::take(&mut *self.lines.lock().unwrap_or_else(|p| p.into_inner()));
+        let truncated = *self.truncated.lock().unwrap_or_else(|p| p.into_inner());
+
+        println!("[n8n] 诊断启动结束: ready={ready}, 收集 {} 行输出", lines.len());
+        BootTrace {
+            ready,
+            detail,
+            output: lines.join("\n"),
+            line_count: lines.len(),
+            truncated,
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+        }
+    }
+}
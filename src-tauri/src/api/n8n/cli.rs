@@ -0,0 +1,144 @@
+//! n8n CLI 调用
+//!
+//! 通过内置的 Node.js 与 n8n 入口执行一次性 CLI 命令（如凭据导入导出、执行工作流）。
+//! 环境变量与服务进程一致，保证读取同一个数据库和加密密钥；会写入数据库的命令要求 n8n 已停止，
+//! 避免 SQLite 数据库被锁定。
+
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use super::constants::CREDENTIALS_PLAINTEXT_WARNING_EVENT;
+use super::encryption_key::inject_encryption_key;
+use super::error::{N8nCoreError, N8nResult};
+use super::paths::{base_dir, n8n_data_dir};
+use super::state::construct_n8n_envs;
+use crate::i18n;
+use crate::services::manager::{self, PROCESS_MANAGER};
+
+/// 导出全部凭据到 `dest`（目录时每个凭据一个文件，否则写入单个 JSON 文件）
+///
+/// `decrypted` 为 `true` 时导出明文凭据，会先发出警告事件。
+pub fn export_credentials<R: Runtime>(app: &AppHandle<R>, dest: &Path, decrypted: bool) -> N8nResult<String> {
+    let mut args = vec!["export:credentials".to_string(), "--all".to_string()];
+    if dest.is_dir() {
+        args.push("--separate".to_string());
+    } else if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    args.push(format!("--output={}", dest.display()));
+
+    if decrypted {
+        eprintln!("[n8n] 警告：凭据将以明文导出到 {}", dest.display());
+        let _ = app.emit(CREDENTIALS_PLAINTEXT_WARNING_EVENT, dest.display().to_string());
+        args.push("--decrypted".to_string());
+    }
+
+    run_n8n_cli(app, &args)
+}
+
+/// 从 `src` 导入凭据（目录时导入其中的每个 JSON 文件）
+pub fn import_credentials<R: Runtime>(app: &AppHandle<R>, src: &Path) -> N8nResult<String> {
+    if !src.exists() {
+        return Err(N8nCoreError::Path(format!(
+            "{}: {}",
+            i18n::t("n8n.cli.source_not_found"),
+            src.display()
+        )));
+    }
+
+    let mut args = vec!["import:credentials".to_string()];
+    if src.is_dir() {
+        args.push("--separate".to_string());
+    }
+    args.push(format!("--input={}", src.display()));
+
+    run_n8n_cli(app, &args)
+}
+
+/// SQLite 数据库被 n8n 服务进程锁定时的错误输出
+const DATABASE_LOCKED_MARKERS: [&str; 2] = ["SQLITE_BUSY", "database is locked"];
+
+/// 工作流执行结果
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowExecution {
+    pub success: bool,
+    /// 进程退出码（被信号终止时为 None）
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// 通过 `n8n execute --id <id>` 在后台执行一次工作流
+///
+/// n8n 服务运行中也可以执行；数据库被服务进程锁定时返回明确的错误，提示先停止 n8n。
+pub fn execute_workflow<R: Runtime>(app: &AppHandle<R>, id: &str) -> N8nResult<WorkflowExecution> {
+    let id = id.trim();
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_')) {
+        return Err(N8nCoreError::Config(format!(
+            "{}: {id}",
+            i18n::t("n8n.cli.invalid_workflow_id")
+        )));
+    }
+
+    let args = ["execute".to_string(), format!("--id={id}")];
+    let output = n8n_cli_output(app, &args)?;
+    let execution = WorkflowExecution {
+        success: output.status.success(),
+        exit_code: output.status.code(),
+        stdout: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    };
+
+    let locked = !execution.success
+        && DATABASE_LOCKED_MARKERS
+            .iter()
+            .any(|marker| execution.stderr.contains(marker) || execution.stdout.contains(marker));
+    if locked {
+        return Err(N8nCoreError::Process(i18n::t("n8n.cli.stop_first")));
+    }
+
+    println!("[n8n] 工作流 {id} 执行结束: {}", output.status);
+    Ok(execution)
+}
+
+/// 执行 n8n CLI 命令并返回其输出（要求 n8n 已停止）
+fn run_n8n_cli<R: Runtime>(app: &AppHandle<R>, args: &[String]) -> N8nResult<String> {
+    let running = PROCESS_MANAGER
+        .lock()
+        .map_err(|_| N8nCoreError::Process(i18n::t("n8n.state.process_manager_poisoned")))?
+        .has_child();
+    if running {
+        return Err(N8nCoreError::Process(i18n::t("n8n.cli.stop_first")));
+    }
+
+    let output = n8n_cli_output(app, args)?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() {
+        return Ok(stdout);
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    let detail = if stderr.is_empty() { stdout } else { stderr };
+    Err(N8nCoreError::Process(format!(
+        "{} ({}): {detail}",
+        i18n::t("n8n.cli.failed"),
+        output.status
+    )))
+}
+
+/// 以与服务进程一致的环境执行 n8n CLI 命令，等待其结束并收集输出
+fn n8n_cli_output<R: Runtime>(app: &AppHandle<R>, args: &[String]) -> N8nResult<Output> {
+    let app_path = base_dir(app)?;
+    let (node_path, n8n_bin) = super::resolve_installed_binaries(&app_path)?;
+    let data_dir = n8n_data_dir(&app_path);
+    // 与服务进程使用同一个加密密钥，否则导入导出的凭据无法解密
+    let mut envs = construct_n8n_envs();
+    inject_encryption_key(&app_path, &data_dir, &mut envs)?;
+    manager::build_n8n_cli_command(&node_path, &n8n_bin, &data_dir, &envs, args)
+    .map_err(N8nCoreError::Config)?
+    .output()
+    .map_err(|e| N8nCoreError::Process(format!("{}: {e}", i18n::t("process.spawn_failed"))))
+}
@@ -0,0 +1,211 @@
+//! 下载源连通性检测
+//!
+//! 安装前检测设置中的 n8n 核心包来源是否可达：发布信息 API（用于获取 SHA256）
+//! 与当前平台资源包的下载地址。只发送请求头，不下载资源包内容。
+//!
+//! 另提供下载源测速：并发从各 Node.js 镜像与核心包地址下载前 5MB，按吞吐量排序，
+//! 以及启动前检测 PostgreSQL / Redis 等外部服务端口是否可达。
+
+use futures_util::future::join_all;
+use futures_util::StreamExt;
+use serde::Serialize;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use super::error::{N8nCoreError, N8nResult};
+use super::installer::{
+    core_asset_name, core_download_url, core_platform, github_api_get,
+    github_release_download_url, latest_release_api_url,
+};
+use crate::api::settings::settings_lock;
+use crate::i18n;
+use crate::services::{http, manager};
+
+/// 单个连通性检测的超时时间
+const CONNECTIVITY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 测速时每个下载源读取的最大字节数（5MB）
+const BENCHMARK_BYTES: u64 = 5 * 1024 * 1024;
+
+/// 单个下载源测速的超时时间（超时后按已读取的数据计算吞吐量）
+const BENCHMARK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 单项连通性检测结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectivityCheck {
+    /// 检测项（`core_source_api` / `core_source_download`）
+    pub name: String,
+    pub url: String,
+    pub reachable: bool,
+    /// HTTP 状态或错误信息
+    pub detail: String,
+}
+
+/// 检测 n8n 核心包来源是否可达
+pub async fn test_connectivity() -> N8nResult<Vec<ConnectivityCheck>> {
+    let client = http::client()?;
+    let (source, mirror) = {
+        let settings = settings_lock();
+        (settings.core_source.clone(), settings.download_mirror.clone())
+    };
+
+    let api_url = latest_release_api_url(&source);
+    let api = github_api_get(&client, &api_url)
+        .timeout(CONNECTIVITY_TIMEOUT)
+        .send()
+        .await;
+
+    let download_url = core_download_url(&source, &mirror, &core_asset_name(core_platform()));
+    let download = client
+        .head(&download_url)
+        .timeout(CONNECTIVITY_TIMEOUT)
+        .send()
+        .await;
+
+    Ok(vec![
+        connectivity_check("core_source_api", api_url, api),
+        connectivity_check("core_source_download", download_url, download),
+    ])
+}
+
+/// 将请求结果转换为检测结果（2xx 视为可达，重定向已由客户端跟随）
+fn connectivity_check(
+    name: &str,
+    url: String,
+    result: Result<reqwest::Response, reqwest::Error>,
+) -> ConnectivityCheck {
+    let (reachable, detail) = match result {
+        Ok(response) => (response.status().is_success(), response.status().to_string()),
+        Err(error) => (false, error.to_string()),
+    };
+
+    ConnectivityCheck {
+        name: name.to_string(),
+        url,
+        reachable,
+        detail,
+    }
+}
+
+/// 单个下载源的测速结果
+#[derive(Debug, Clone, Serialize)]
+pub struct MirrorBenchmark {
+    /// 下载源主机名
+    pub name: String,
+    pub url: String,
+    /// 吞吐量（Mbit/s），失败时为 0
+    pub mbps: f64,
+    /// 收到响应头的耗时，失败时为 None
+    pub latency_ms: Option<u64>,
+    /// 失败原因
+    pub error: Option<String>,
+}
+
+/// 并发测速所有下载源，按吞吐量从高到低排序（失败的排在最后）
+pub async fn benchmark_mirrors() -> N8nResult<Vec<MirrorBenchmark>> {
+    let client = http::client()?;
+    let (source, mirror) = {
+        let settings = settings_lock();
+        (settings.core_source.clone(), settings.download_mirror.clone())
+    };
+    let asset = core_asset_name(core_platform());
+
+    // 不支持的平台没有 Node.js 下载地址，仅测速核心包来源
+    let mut urls = manager::get_node_download_urls().unwrap_or_default();
+    urls.push(core_download_url(&source, &mirror, &asset));
+    let proxied = source.release_base_url.as_deref().is_none_or(|url| url.trim().is_empty())
+        && mirror.uses_gh_proxy();
+    if proxied {
+        urls.push(github_release_download_url(&source, &asset));
+    }
+
+    let mut results = join_all(urls.into_iter().map(|url| benchmark_url(&client, url))).await;
+    results.sort_by(|a, b| {
+        a.error
+            .is_some()
+            .cmp(&b.error.is_some())
+            .then(b.mbps.total_cmp(&a.mbps))
+    });
+    Ok(results)
+}
+
+/// 通过 Range 请求下载前 `BENCHMARK_BYTES` 字节并计算吞吐量
+async fn benchmark_url(client: &reqwest::Client, url: String) -> MirrorBenchmark {
+    let name = url::Url::parse(&url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.clone());
+    let mut result = MirrorBenchmark {
+        name,
+        url,
+        mbps: 0.0,
+        latency_ms: None,
+        error: None,
+    };
+
+    let started = Instant::now();
+    let deadline = started + BENCHMARK_TIMEOUT;
+    let response = client
+        .get(&result.url)
+        .header("Range", format!("bytes=0-{}", BENCHMARK_BYTES - 1))
+        .timeout(BENCHMARK_TIMEOUT)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status);
+    let response = match response {
+        Ok(response) => response,
+        Err(error) => {
+            result.error = Some(error.to_string());
+            return result;
+        }
+    };
+    result.latency_ms = Some(started.elapsed().as_millis() as u64);
+
+    // 服务器忽略 Range 时返回完整内容，读满 BENCHMARK_BYTES 后即停止
+    let body_started = Instant::now();
+    let mut received: u64 = 0;
+    let mut stream = response.bytes_stream();
+    while received < BENCHMARK_BYTES {
+        match tokio::time::timeout_at(deadline.into(), stream.next()).await {
+            Ok(Some(Ok(chunk))) => received += chunk.len() as u64,
+            Ok(Some(Err(error))) => {
+                if received == 0 {
+                    result.error = Some(error.to_string());
+                }
+                break;
+            }
+            Ok(None) | Err(_) => break,
+        }
+    }
+
+    let seconds = body_started.elapsed().as_secs_f64();
+    if received > 0 && seconds > 0.0 {
+        result.mbps = received as f64 * 8.0 / 1_000_000.0 / seconds;
+    } else if result.error.is_none() {
+        result.error = Some(i18n::t("download.no_data"));
+    }
+    result
+}
+
+/// 检测 `host:port` 是否可达（仅做 TCP 连接测试，依次尝试解析出的每个地址）
+///
+/// 不可达时返回 `Config` 错误，消息为 `error_key` 对应的文案加上地址与最后一次连接错误。
+pub(super) fn check_tcp_reachable(host: &str, port: u16, timeout: Duration, error_key: &str) -> N8nResult<()> {
+    let address = format!("{}:{port}", host.trim());
+    let unreachable =
+        |detail: String| N8nCoreError::Config(format!("{} ({address}): {detail}", i18n::t(error_key)));
+
+    let socket_addrs = address
+        .to_socket_addrs()
+        .map_err(|e| unreachable(e.to_string()))?;
+
+    let mut last_error = String::new();
+    for socket_addr in socket_addrs {
+        match TcpStream::connect_timeout(&socket_addr, timeout) {
+            Ok(_) => return Ok(()),
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+
+    Err(unreachable(last_error))
+}
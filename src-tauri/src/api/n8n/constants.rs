@@ -8,26 +8,109 @@ use std::time::Duration;
 pub const DEFAULT_BLOCKED_NODES: &str = r#"["n8n-nodes-base.executeCommand"]"#;
 pub const DEFAULT_BLOCKED_NODES_NAMES: &str = "executeCommand";
 
-/// GitHub API 相关常量
-pub const GITHUB_API_URL: &str =
-    "https://api.github.com/repos/tangtao646/n8n-core-builder/releases/latest";
-pub const GITHUB_USER_AGENT: &str = "n8n-desktop";
+/// 安全模式启动时覆盖的环境变量
+///
+/// - `N8N_COMMUNITY_PACKAGES_ENABLED=false`：不加载已安装的社区节点
+/// - `N8N_CUSTOM_EXTENSIONS` 置空：不加载自定义节点目录
+pub const SAFE_MODE_ENVS: [(&str, &str); 2] = [
+    ("N8N_COMMUNITY_PACKAGES_ENABLED", "false"),
+    ("N8N_CUSTOM_EXTENSIONS", ""),
+];
+
+/// 启动模式事件（载荷为是否处于安全模式）
+pub const SAFE_MODE_EVENT: &str = "safe-mode";
+
+/// n8n 二进制数据目录（相对于 n8n 数据目录，filesystem 模式下使用）
+pub const BINARY_DATA_RELATIVE_PATH: &str = ".n8n/binaryData";
+
+/// 锁定工作流（`lock_workflows`）时追加的环境变量
+///
+/// n8n 没有针对工作流的只读开关，这里通过关闭编辑器界面阻止修改，已激活的工作流
+/// （定时、Webhook 触发）照常执行：
+/// - `N8N_DISABLE_UI=true`：不提供编辑器界面
+/// - `N8N_PUBLIC_API_DISABLED=true`：关闭可增删改工作流的 Public API
+/// - `N8N_PUBLIC_API_SWAGGERUI_DISABLED=true`：关闭 Public API 的 Swagger 页面
+/// - `N8N_COMMUNITY_PACKAGES_ENABLED=false`：禁止安装社区节点
+pub const LOCKED_WORKFLOW_ENVS: [(&str, &str); 4] = [
+    ("N8N_DISABLE_UI", "true"),
+    ("N8N_PUBLIC_API_DISABLED", "true"),
+    ("N8N_PUBLIC_API_SWAGGERUI_DISABLED", "true"),
+    ("N8N_COMMUNITY_PACKAGES_ENABLED", "false"),
+];
+
+/// GitHub API 相关常量（仓库可在设置中配置）
 pub const GITHUB_ACCEPT_HEADER: &str = "application/vnd.github.v3+json";
 
+/// GitHub 官方 API 主机（只向该主机发送 GitHub 令牌）
+pub const GITHUB_API_HOST: &str = "api.github.com";
+
 /// 代理下载前缀
 pub const GH_PROXY_PREFIX: &str = "https://gh-proxy.com/";
-pub const N8N_CORE_BASE_URL: &str =
-    "https://github.com/tangtao646/n8n-core-builder/releases/latest/download";
-
-/// 健康检查端点
-pub const HEALTH_CHECK_ENDPOINTS: [&str; 4] = [
-    "http://localhost:5678/healthz",
-    "http://127.0.0.1:5678/healthz",
-    "http://localhost:5678/",
-    "http://127.0.0.1:5678/",
-];
 
-/// 健康检查配置
-pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// GitHub Releases 下载地址
+pub const GITHUB_RELEASES_BASE_URL: &str = "https://github.com";
+
+/// `piped` 模式下 n8n 输出的日志文件（位于数据基础目录下）
+pub const N8N_LOG_DIRNAME: &str = "logs";
+pub const N8N_LOG_FILENAME: &str = "n8n.log";
+
+/// 安装 n8n 核心包所需的磁盘空间（资源包约 100MB，解压后约 300MB）
+pub const CORE_INSTALL_REQUIRED_BYTES: u64 = 400 * 1024 * 1024;
+
+/// 安装 Node 运行时所需的磁盘空间（资源包与解压后的运行时）
+pub const RUNTIME_INSTALL_REQUIRED_BYTES: u64 = 200 * 1024 * 1024;
+
+/// n8n 核心包安装操作标识（下载类型、操作锁与取消均使用此标识）
+pub const N8N_CORE_OPERATION: &str = "n8n-core";
+
+/// 跳过 n8n 核心包 SHA256 校验时的事件
+pub const VERIFICATION_SKIPPED_EVENT: &str = "verification-skipped";
+
+/// n8n 核心包 SHA256 校验进度事件
+pub const VERIFY_PROGRESS_EVENT: &str = "verify-progress";
+
+/// Node 运行时下载成功所用的下载源事件
+pub const RUNTIME_MIRROR_EVENT: &str = "runtime-mirror";
+
+/// 以明文导出凭据时的警告事件
+pub const CREDENTIALS_PLAINTEXT_WARNING_EVENT: &str = "credentials-plaintext-warning";
+
+/// n8n 进程生命周期状态事件（stopped / starting / running / stopping / crashed）
+pub const N8N_STATE_EVENT: &str = "n8n-state";
+
+/// 自动启动被跳过事件（载荷为原因：`not_installed` / `already_running`）
+pub const AUTO_LAUNCH_SKIPPED_EVENT: &str = "auto-launch-skipped";
+
+/// 自动启动失败事件（载荷为错误信息）
+pub const AUTO_LAUNCH_FAILED_EVENT: &str = "auto-launch-failed";
+
+/// 以 root / 管理员身份运行的提醒事件
+pub const ELEVATED_PRIVILEGES_WARNING_EVENT: &str = "elevated-privileges-warning";
+
+/// n8n 启动端口事件（载荷为实际使用的端口；默认端口被占用时为自动选择的端口）
+pub const N8N_PORT_SELECTED_EVENT: &str = "n8n-port-selected";
+
+/// n8n 输出就绪提示事件（载荷为编辑器地址）
+pub const N8N_READY_EVENT: &str = "n8n-ready";
+
+/// n8n 启动后立即退出事件（载荷为 `StartupFailure`，含退出码与最近的错误输出）
+pub const N8N_STARTUP_FAILED_EVENT: &str = "n8n-startup-failed";
+
+/// 安装阶段切换事件（载荷为 `SetupPhase`）
+pub const SETUP_PHASE_EVENT: &str = "setup-phase";
+
+/// n8n 输出日志行事件（载荷为 `N8nLogLine`，区分 stdout / stderr）
+pub const N8N_LOG_EVENT: &str = "n8n-log";
+
+/// 编辑器窗口默认尺寸
+pub const EDITOR_WINDOW_WIDTH: f64 = 1280.0;
+pub const EDITOR_WINDOW_HEIGHT: f64 = 800.0;
+
+/// 健康检查配置（单次请求超时、默认轮数与每轮失败后的等待时间）
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
 pub const HEALTH_CHECK_RETRIES: usize = 3;
 pub const HEALTH_CHECK_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// 前端指定健康检查轮数与间隔时的上限（保证等待时间有界）
+pub const MAX_HEALTH_CHECK_RETRIES: usize = 120;
+pub const MAX_HEALTH_CHECK_RETRY_DELAY: Duration = Duration::from_secs(10);
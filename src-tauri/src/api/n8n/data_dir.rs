@@ -0,0 +1,348 @@
+//! n8n 数据目录迁移
+//!
+//! 系统盘空间不足时，可将 n8n 数据目录（数据库、二进制数据、加密密钥等）迁移到其他位置：
+//! 停止 n8n、校验目标目录、移动数据、更新设置，再按原状态重新启动。
+//! 同一文件系统内直接重命名；跨文件系统时 `rename` 失败，改为复制并校验后再删除源目录。
+//! 任一步骤失败都会回滚，新位置确认完整之前不会删除源目录。
+//!
+//! 也可以只切换数据目录而不移动数据（`set_data_dir`），用于改用另一块磁盘上已有的数据或在多个安装之间共享。
+
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Runtime};
+
+use super::error::{N8nCoreError, N8nResult};
+use super::paths::{
+    base_dir, disk_space, n8n_data_dir, probe_writable, unwritable, N8N_DATA_DIRNAME, N8N_USER_DIRNAME,
+};
+use super::versions::{CORES_DIRNAME, LEGACY_CORE_DIRNAME};
+use crate::api::settings::{save_settings, settings_lock};
+use crate::i18n;
+
+/// 安装目录（运行时与 n8n 核心）：重新安装或卸载时会被删除，不能存放数据
+const INSTALL_DIRNAMES: [&str; 3] = ["runtime", LEGACY_CORE_DIRNAME, CORES_DIRNAME];
+
+/// 数据目录迁移结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DataDirMigration {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    /// 迁移的数据量（字节）
+    pub bytes: u64,
+    /// 是否跨文件系统复制（否则为直接重命名）
+    pub copied: bool,
+    /// 迁移后是否已重新启动 n8n
+    pub restarted: bool,
+}
+
+/// 将 n8n 数据目录迁移到 `new_path` 并更新设置
+///
+/// n8n 正在运行时先停止，迁移结束（无论成功与否）后重新启动；失败时数据保留在原位置。
+pub fn migrate_data_dir<R: Runtime>(app: &AppHandle<R>, new_path: &Path) -> N8nResult<DataDirMigration> {
+    let app_path = base_dir(app)?;
+    let from = n8n_data_dir(&app_path);
+    let to = prepare_target(&app_path, &from, new_path)?;
+    let (_, bytes) = dir_stats(&from)?;
+    ensure_free_space(&from, &to, bytes)?;
+
+    let was_running = super::shutdown_n8n()?;
+    tracing::info!(from = %from.display(), to = %to.display(), "迁移数据目录");
+    let mut result = move_data_dir(&from, &to).map_err(N8nCoreError::from).and_then(|copied| {
+        // 迁回默认位置时清除自定义设置
+        let custom = (to != app_path.join(N8N_DATA_DIRNAME)).then(|| to.clone());
+        let previous = std::mem::replace(&mut settings_lock().n8n_data_dir, custom);
+        if let Err(error) = save_settings(app) {
+            settings_lock().n8n_data_dir = previous;
+            if let Err(rollback) = move_data_dir(&to, &from) {
+                tracing::error!(path = %to.display(), error = %rollback, "回滚数据目录失败，数据仍位于新位置");
+            }
+            return Err(N8nCoreError::Config(error));
+        }
+
+        Ok(DataDirMigration {
+            from: from.clone(),
+            to: to.clone(),
+            bytes,
+            copied,
+            restarted: false,
+        })
+    });
+
+    if was_running {
+        match super::relaunch_n8n(app.clone()) {
+            Ok(()) => {
+                if let Ok(migration) = result.as_mut() {
+                    migration.restarted = true;
+                }
+            }
+            Err(error) => tracing::error!(%error, "迁移数据目录后重新启动 n8n 失败"),
+        }
+    }
+    result
+}
+
+/// 将 n8n 数据目录切换为 `path` 并保存设置（不移动现有数据，下次启动 n8n 时生效），返回生效的路径
+///
+/// `path` 为空时恢复默认位置；自定义目录必须不存在、为空或已包含 n8n 数据（`.n8n`），不存在时创建，并确认可写。
+/// 需要连同数据一起移动时使用 `migrate_data_dir`。
+pub fn set_data_dir<R: Runtime>(app: &AppHandle<R>, path: &str) -> N8nResult<PathBuf> {
+    let app_path = base_dir(app)?;
+    let default_dir = app_path.join(N8N_DATA_DIRNAME);
+    let path = path.trim();
+    let target = if path.is_empty() {
+        default_dir.clone()
+    } else {
+        validate_custom_data_dir(&app_path, Path::new(path))?
+    };
+    fs::create_dir_all(&target).map_err(|e| unwritable(&target, &e))?;
+    probe_writable(&target).map_err(|e| unwritable(&target, &e))?;
+
+    let custom = (target != default_dir).then(|| target.clone());
+    let previous = std::mem::replace(&mut settings_lock().n8n_data_dir, custom);
+    if let Err(error) = save_settings(app) {
+        settings_lock().n8n_data_dir = previous;
+        return Err(N8nCoreError::Config(error));
+    }
+
+    tracing::info!(path = %target.display(), "数据目录已切换（下次启动 n8n 时生效）");
+    Ok(target)
+}
+
+/// 校验要保存为 `n8n_data_dir` 的自定义数据目录（切换数据目录与保存设置共用），返回规范化后的路径
+///
+/// 除 `validate_data_dir` 的要求外，目录还必须不存在、为空或已包含 n8n 数据。
+pub(crate) fn validate_custom_data_dir(app_path: &Path, path: &Path) -> N8nResult<PathBuf> {
+    let path = validate_data_dir(app_path, path)?;
+    ensure_n8n_data_or_empty(&path)?;
+    Ok(path)
+}
+
+/// 校验自定义数据目录：必须是绝对路径，且不能位于运行时或 n8n 核心目录中
+fn validate_data_dir(app_path: &Path, path: &Path) -> N8nResult<PathBuf> {
+    if !path.is_absolute() {
+        return Err(N8nCoreError::Config(format!(
+            "{}: {}",
+            i18n::t("data_dir.not_absolute"),
+            path.display()
+        )));
+    }
+
+    let path = normalize_path(path);
+    if INSTALL_DIRNAMES
+        .iter()
+        .any(|name| path.starts_with(app_path.join(name)))
+    {
+        return Err(N8nCoreError::Config(format!(
+            "{}: {}",
+            i18n::t("data_dir.install_dir"),
+            path.display()
+        )));
+    }
+    Ok(path)
+}
+
+/// 确认目录不存在、为空或已是 n8n 数据目录（包含 `.n8n`）
+///
+/// 卸载时会删除数据目录中的 n8n 数据，不能把存放其他文件的目录（如文档目录）直接设为数据目录。
+fn ensure_n8n_data_or_empty(path: &Path) -> N8nResult<()> {
+    let occupied = path.is_file() || fs::read_dir(path).is_ok_and(|mut entries| entries.next().is_some());
+    if occupied && !path.join(N8N_USER_DIRNAME).is_dir() {
+        return Err(N8nCoreError::Config(format!(
+            "{}: {}",
+            i18n::t("data_dir.not_n8n_data"),
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// 按字面处理路径中的 `.` 与 `..`（目标目录可能尚不存在，无法 canonicalize）
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// 校验目标位置并创建目录：满足 `validate_data_dir` 的要求、与当前目录互不包含、不存在或为空目录，且可写
+fn prepare_target(app_path: &Path, from: &Path, new_path: &Path) -> N8nResult<PathBuf> {
+    let new_path = &validate_data_dir(app_path, new_path)?;
+    if new_path == from {
+        return Err(N8nCoreError::Config(i18n::t("data_dir.same_location")));
+    }
+    if new_path.starts_with(from) || from.starts_with(new_path) {
+        return Err(N8nCoreError::Config(i18n::t("data_dir.nested")));
+    }
+
+    let occupied = new_path.is_file()
+        || fs::read_dir(new_path).is_ok_and(|mut entries| entries.next().is_some());
+    if occupied {
+        return Err(N8nCoreError::Config(format!(
+            "{}: {}",
+            i18n::t("data_dir.target_not_empty"),
+            new_path.display()
+        )));
+    }
+
+    fs::create_dir_all(new_path).map_err(|e| unwritable(new_path, &e))?;
+    probe_writable(new_path).map_err(|e| unwritable(new_path, &e))?;
+    Ok(new_path.to_path_buf())
+}
+
+/// 跨磁盘迁移时确认目标磁盘的剩余空间足够（同一磁盘内重命名不占用额外空间）
+fn ensure_free_space(from: &Path, to: &Path, required: u64) -> N8nResult<()> {
+    let Some((target_mount, available)) = disk_space(to) else {
+        return Ok(());
+    };
+    let same_disk = disk_space(from).is_some_and(|(source_mount, _)| source_mount == target_mount);
+    if same_disk || available >= required {
+        return Ok(());
+    }
+
+    Err(N8nCoreError::Path(format!(
+        "{}: {} MB / {} MB",
+        i18n::t("data_dir.insufficient_space"),
+        available / 1024 / 1024,
+        required / 1024 / 1024
+    )))
+}
+
+/// 将 `from` 移动到 `to`（`to` 不存在或为空目录），返回是否通过复制完成
+///
+/// 源目录不存在时只创建目标目录。复制失败或校验不一致时删除目标目录，源目录保持不变。
+fn move_data_dir(from: &Path, to: &Path) -> io::Result<bool> {
+    if !from.exists() {
+        fs::create_dir_all(to)?;
+        return Ok(false);
+    }
+
+    if to.is_dir() {
+        fs::remove_dir(to)?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(false);
+    }
+
+    // 跨文件系统时 rename 失败（EXDEV），改为复制
+    let copied = copy_dir_all(from, to).and_then(|()| {
+        if dir_stats(from)? == dir_stats(to)? {
+            Ok(())
+        } else {
+            Err(io::Error::other(i18n::t("data_dir.copy_mismatch")))
+        }
+    });
+    if let Err(error) = copied {
+        let _ = fs::remove_dir_all(to);
+        return Err(error);
+    }
+
+    // 新位置已完整，源目录删除失败只留下多余的副本，不影响迁移结果
+    if let Err(error) = fs::remove_dir_all(from) {
+        tracing::warn!(path = %from.display(), %error, "删除旧数据目录失败，请手动删除");
+    }
+    Ok(true)
+}
+
+/// 递归复制目录
+fn copy_dir_all(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_all(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// 统计目录下的文件数与总字节数（目录不存在时为 0）
+pub(crate) fn dir_stats(dir: &Path) -> io::Result<(u64, u64)> {
+    if !dir.exists() {
+        return Ok((0, 0));
+    }
+
+    let mut stats = (0, 0);
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            let (files, bytes) = dir_stats(&entry.path())?;
+            stats.0 += files;
+            stats.1 += bytes;
+        } else {
+            stats.0 += 1;
+            stats.1 += fs::metadata(entry.path())?.len();
+        }
+    }
+    Ok(stats)
+}
+
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+
+    #[test]
+    fn test_move_data_dir_preserves_contents() {
+        let root = temp_dir().join("n8n_data_dir_move_test");
+        let _ = fs::remove_dir_all(&root);
+        let from = root.join("old");
+        let to = root.join("new");
+        fs::create_dir_all(from.join(".n8n")).expect("创建数据目录失败");
+        fs::write(from.join(".n8n/database.sqlite"), "db").expect("写入数据库失败");
+        fs::write(from.join(".n8n/config"), "{}").expect("写入配置失败");
+
+        assert!(prepare_target(&root, &from, &from.join("nested")).is_err());
+        assert!(prepare_target(&root, &from, &root.join("runtime/data")).is_err());
+        prepare_target(&root, &from, &to).expect("目标目录应可用");
+        move_data_dir(&from, &to).expect("迁移数据目录失败");
+
+        assert!(!from.exists());
+        assert_eq!(dir_stats(&to).expect("统计目录失败"), (2, 4));
+        assert_eq!(fs::read_to_string(to.join(".n8n/database.sqlite")).expect("读取数据库失败"), "db");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_validate_data_dir_rejects_install_dirs() {
+        let app_path = temp_dir().join("n8n_set_data_dir_test");
+
+        assert!(validate_data_dir(&app_path, Path::new("relative/n8n-data")).is_err());
+        assert!(validate_data_dir(&app_path, &app_path.join("runtime")).is_err());
+        assert!(validate_data_dir(&app_path, &app_path.join("cores/n8n@1.90.0/data")).is_err());
+        assert!(validate_data_dir(&app_path, &app_path.join("data/../n8n-core")).is_err());
+        assert_eq!(
+            validate_data_dir(&app_path, &app_path.join("shared/./n8n-data")).expect("应接受普通目录"),
+            app_path.join("shared/n8n-data")
+        );
+    }
+
+    #[test]
+    fn test_data_dir_must_be_empty_or_contain_n8n_data() {
+        let root = temp_dir().join("n8n_data_dir_occupied_test");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).expect("创建目录失败");
+
+        assert!(ensure_n8n_data_or_empty(&root.join("missing")).is_ok());
+        assert!(ensure_n8n_data_or_empty(&root).is_ok());
+        fs::write(root.join("notes.txt"), "notes").expect("写入文件失败");
+        assert!(ensure_n8n_data_or_empty(&root).is_err());
+        fs::create_dir_all(root.join(".n8n")).expect("创建数据目录失败");
+        assert!(ensure_n8n_data_or_empty(&root).is_ok());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
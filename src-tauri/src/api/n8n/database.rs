@@ -0,0 +1,73 @@
+//! n8n 数据库配置模块
+//!
+//! 将数据库设置转换为 n8n 环境变量，并在启动前校验 PostgreSQL 配置。
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use super::connectivity::check_tcp_reachable;
+use super::error::{N8nCoreError, N8nResult};
+use crate::api::settings::{DatabaseSettings, DatabaseType};
+use crate::i18n;
+use crate::services::keychain;
+
+/// PostgreSQL 连通性检测超时
+const POSTGRES_CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 将数据库设置转换为 n8n 环境变量
+pub fn database_envs(database: &DatabaseSettings) -> HashMap<String, String> {
+    let mut envs = HashMap::new();
+
+    match database.db_type {
+        DatabaseType::Sqlite => {
+            envs.insert("DB_TYPE".to_string(), "sqlite".to_string());
+        }
+        DatabaseType::Postgres => {
+            envs.insert("DB_TYPE".to_string(), "postgresdb".to_string());
+            envs.insert("DB_POSTGRESDB_HOST".to_string(), database.host.trim().to_string());
+            envs.insert("DB_POSTGRESDB_PORT".to_string(), database.port.to_string());
+            envs.insert("DB_POSTGRESDB_USER".to_string(), database.user.trim().to_string());
+            // 密码保存在钥匙串中；钥匙串不可用时使用保留在设置中的密码
+            let password = keychain::postgres_password().unwrap_or_else(|| database.password.clone());
+            envs.insert("DB_POSTGRESDB_PASSWORD".to_string(), password);
+            envs.insert(
+                "DB_POSTGRESDB_DATABASE".to_string(),
+                database.database.trim().to_string(),
+            );
+        }
+    }
+
+    envs
+}
+
+/// 校验数据库设置（选择 PostgreSQL 时必填字段不能为空）
+pub fn validate_database_settings(database: &DatabaseSettings) -> N8nResult<()> {
+    match database.missing_fields().first() {
+        Some(field) => Err(N8nCoreError::Config(format!(
+            "{}: {field}",
+            i18n::t("db.postgres.missing_field")
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// 检测 PostgreSQL 服务器是否可达（仅做 TCP 连接测试）
+pub fn check_postgres_reachable(database: &DatabaseSettings) -> N8nResult<()> {
+    check_tcp_reachable(
+        &database.host,
+        database.port,
+        POSTGRES_CONNECT_TIMEOUT,
+        "db.postgres.unreachable",
+    )
+}
+
+/// 启动前确认数据库配置可用
+pub fn ensure_database_ready(database: &DatabaseSettings) -> N8nResult<()> {
+    validate_database_settings(database)?;
+
+    if database.db_type == DatabaseType::Postgres {
+        check_postgres_reachable(database)?;
+    }
+
+    Ok(())
+}
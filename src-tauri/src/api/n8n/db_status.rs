@@ -0,0 +1,182 @@
+//! n8n 数据库迁移状态
+//!
+//! n8n 升级后首次启动会先执行数据库迁移，耗时可能较长；迁移失败时前端只能看到启动超时。
+//! 这里直接读取 SQLite 的 `migrations` 表，并与 n8n 包内附带的迁移文件按时间戳对比，
+//! 报告已应用与待执行的迁移。PostgreSQL 不做探测。
+
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Runtime};
+
+use super::error::{N8nCoreError, N8nResult};
+use super::paths::{base_dir, n8n_data_dir};
+use super::versions;
+use crate::api::settings::{settings_lock, DatabaseType};
+use crate::i18n;
+
+/// SQLite 数据库文件（相对于 n8n 数据目录）
+pub const SQLITE_DB_RELATIVE_PATH: &str = ".n8n/database.sqlite";
+
+/// n8n 包内 SQLite 迁移文件目录（相对核心目录；新版本位于 `@n8n/db`，旧版本位于 n8n 包内）
+const SQLITE_MIGRATION_DIRS: [&str; 2] = [
+    "node_modules/@n8n/db/dist/migrations/sqlite",
+    "node_modules/n8n/dist/databases/migrations/sqlite",
+];
+
+/// 数据库迁移状态
+#[derive(Debug, Clone, Serialize)]
+pub struct DbStatus {
+    /// 数据库类型（`sqlite` / `postgresdb`）
+    pub db_type: String,
+    /// 数据库文件是否存在（首次启动前不存在）
+    pub database_exists: bool,
+    /// 已应用的迁移数量
+    pub applied: usize,
+    /// 最近一次应用的迁移名称
+    pub last_applied: Option<String>,
+    /// 待执行的迁移名称（无法获取 n8n 附带的迁移列表时为 `None`）
+    pub pending: Option<Vec<String>>,
+    /// 面向用户的状态说明
+    pub message: String,
+}
+
+/// 查询 n8n 数据库迁移状态
+pub fn db_status<R: Runtime>(app: &AppHandle<R>) -> N8nResult<DbStatus> {
+    let db_type = settings_lock().database.db_type;
+    if db_type == DatabaseType::Postgres {
+        return Ok(DbStatus {
+            db_type: "postgresdb".to_string(),
+            database_exists: true,
+            applied: 0,
+            last_applied: None,
+            pending: None,
+            message: i18n::t("db.status.postgres_unsupported"),
+        });
+    }
+
+    let app_path = base_dir(app)?;
+    sqlite_status(&n8n_data_dir(&app_path).join(SQLITE_DB_RELATIVE_PATH), &app_path)
+}
+
+/// 读取 SQLite 数据库的迁移状态
+fn sqlite_status(db_path: &Path, app_path: &Path) -> N8nResult<DbStatus> {
+    let core_dir = versions::core_dir(app_path);
+    let available = SQLITE_MIGRATION_DIRS
+        .iter()
+        .find_map(|dir| available_migrations(&core_dir.join(dir)));
+
+    if !db_path.exists() {
+        return Ok(DbStatus {
+            db_type: "sqlite".to_string(),
+            database_exists: false,
+            applied: 0,
+            last_applied: None,
+            pending: available.map(|migrations| migrations.into_iter().map(|(_, name)| name).collect()),
+            message: i18n::t("db.status.not_initialized"),
+        });
+    }
+
+    let applied = applied_migrations(db_path)?;
+    let applied_timestamps: HashSet<i64> = applied.iter().map(|(timestamp, _)| *timestamp).collect();
+    let pending = available.map(|migrations| {
+        migrations
+            .into_iter()
+            .filter(|(timestamp, _)| !applied_timestamps.contains(timestamp))
+            .map(|(_, name)| name)
+            .collect::<Vec<_>>()
+    });
+
+    let message = match &pending {
+        Some(pending) if !pending.is_empty() => {
+            format!("{}: {}", i18n::t("db.status.pending"), pending.len())
+        }
+        Some(_) => i18n::t("db.status.up_to_date"),
+        None => i18n::t("db.status.unknown_pending"),
+    };
+
+    Ok(DbStatus {
+        db_type: "sqlite".to_string(),
+        database_exists: true,
+        applied: applied.len(),
+        last_applied: applied.last().map(|(_, name)| name.clone()),
+        pending,
+        message,
+    })
+}
+
+/// 以只读方式读取 `migrations` 表（按时间戳升序）
+fn applied_migrations(db_path: &Path) -> N8nResult<Vec<(i64, String)>> {
+    let query_failed =
+        |e: rusqlite::Error| N8nCoreError::Config(format!("{}: {e}", i18n::t("db.status.read_failed")));
+
+    let connection = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(query_failed)?;
+    let mut statement = connection
+        .prepare("SELECT timestamp, name FROM migrations ORDER BY timestamp")
+        .map_err(query_failed)?;
+    let rows = statement
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))
+        .map_err(query_failed)?;
+
+    rows.collect::<Result<Vec<_>, _>>().map_err(query_failed)
+}
+
+/// 列出迁移目录中的迁移（文件名形如 `1588102412422-InitialMigration.js`）
+fn available_migrations(dir: &Path) -> Option<Vec<(i64, String)>> {
+    let mut migrations: Vec<(i64, String)> = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "js"))
+        .filter_map(|path| parse_migration_file(&path))
+        .collect();
+
+    migrations.sort();
+    Some(migrations)
+}
+
+/// 从迁移文件名解析时间戳与迁移名称（名称与 `migrations` 表一致：`<名称><时间戳>`）
+fn parse_migration_file(path: &Path) -> Option<(i64, String)> {
+    let stem = path.file_stem()?.to_str()?;
+    let (timestamp, name) = stem.split_once('-')?;
+    let timestamp = timestamp.parse::<i64>().ok()?;
+    Some((timestamp, format!("{name}{timestamp}")))
+}
+
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+
+    #[test]
+    fn test_sqlite_status_reports_pending_migrations() {
+        let root = temp_dir().join("n8n_db_status_test");
+        let _ = fs::remove_dir_all(&root);
+        let migration_dir = root.join(versions::LEGACY_CORE_DIRNAME).join(SQLITE_MIGRATION_DIRS[0]);
+        fs::create_dir_all(&migration_dir).expect("创建迁移目录失败");
+        for file in ["1588102412422-InitialMigration.js", "1690000000000-AddFoo.js", "index.js"] {
+            fs::write(migration_dir.join(file), "").expect("写入迁移文件失败");
+        }
+
+        let db_path = root.join(SQLITE_DB_RELATIVE_PATH);
+        fs::create_dir_all(db_path.parent().expect("缺少父目录")).expect("创建数据目录失败");
+        let connection = Connection::open(&db_path).expect("创建数据库失败");
+        connection
+            .execute_batch(
+                "CREATE TABLE migrations (id INTEGER PRIMARY KEY, timestamp INTEGER, name TEXT);
+                 INSERT INTO migrations (timestamp, name) VALUES (1588102412422, 'InitialMigration1588102412422');",
+            )
+            .expect("初始化迁移表失败");
+        drop(connection);
+
+        let status = sqlite_status(&db_path, &root).expect("读取迁移状态失败");
+        assert_eq!(status.applied, 1);
+        assert_eq!(status.pending, Some(vec!["AddFoo1690000000000".to_string()]));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
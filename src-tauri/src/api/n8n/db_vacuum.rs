@@ -0,0 +1,106 @@
+//! SQLite 数据库压缩
+//!
+//! n8n 删除执行记录后 SQLite 只会把页面标记为空闲，数据库文件不会变小。
+//! 这里对 `database.sqlite` 执行 `VACUUM` 回收空闲页面，并报告压缩前后的文件大小。
+//! 执行期间 n8n 必须已停止，否则数据库被锁定。
+
+use rusqlite::Connection;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::error::{N8nCoreError, N8nResult};
+use crate::i18n;
+
+/// 等待数据库锁释放的最长时间
+const VACUUM_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 数据库压缩结果
+#[derive(Debug, Clone, Serialize)]
+pub struct VacuumReport {
+    /// 压缩前的大小（字节，包含 WAL 文件）
+    pub before_bytes: u64,
+    /// 压缩后的大小（字节，包含 WAL 文件）
+    pub after_bytes: u64,
+    /// 回收的空间（字节）
+    pub freed_bytes: u64,
+    /// 压缩后是否重新启动了 n8n（压缩前 n8n 正在运行时才会重启）
+    pub restarted: bool,
+}
+
+/// 对 SQLite 数据库执行 `VACUUM`（调用方需保证 n8n 已停止）
+pub fn vacuum_sqlite(db_path: &Path) -> N8nResult<VacuumReport> {
+    if !db_path.exists() {
+        return Err(N8nCoreError::Path(format!(
+            "{}: {}",
+            i18n::t("db.vacuum.not_found"),
+            db_path.display()
+        )));
+    }
+
+    let vacuum_failed =
+        |e: rusqlite::Error| N8nCoreError::Config(format!("{}: {e}", i18n::t("db.vacuum.failed")));
+
+    let before_bytes = database_size(db_path);
+    let connection = Connection::open(db_path).map_err(vacuum_failed)?;
+    connection.busy_timeout(VACUUM_BUSY_TIMEOUT).map_err(vacuum_failed)?;
+    connection.execute_batch("VACUUM").map_err(vacuum_failed)?;
+    // 关闭连接时 SQLite 会将 WAL 合并回主文件
+    connection.close().map_err(|(_, e)| vacuum_failed(e))?;
+    let after_bytes = database_size(db_path);
+
+    let freed_bytes = before_bytes.saturating_sub(after_bytes);
+    println!("[n8n] 数据库压缩完成: {before_bytes} -> {after_bytes} 字节");
+    Ok(VacuumReport {
+        before_bytes,
+        after_bytes,
+        freed_bytes,
+        restarted: false,
+    })
+}
+
+/// 数据库文件与 WAL 文件的总大小
+fn database_size(db_path: &Path) -> u64 {
+    let mut wal_path = PathBuf::from(db_path).into_os_string();
+    wal_path.push("-wal");
+
+    [db_path.to_path_buf(), PathBuf::from(wal_path)]
+        .iter()
+        .filter_map(|path| fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+
+    #[test]
+    fn test_vacuum_sqlite_reclaims_free_pages() {
+        let dir = temp_dir().join("n8n_db_vacuum_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("创建目录失败");
+        let db_path = dir.join("database.sqlite");
+
+        let connection = Connection::open(&db_path).expect("创建数据库失败");
+        connection
+            .execute_batch(
+                "CREATE TABLE execution_data (data TEXT);
+                 WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 2000)
+                 INSERT INTO execution_data SELECT printf('%.1000c', 'x') FROM n;
+                 DELETE FROM execution_data;",
+            )
+            .expect("初始化数据库失败");
+        drop(connection);
+
+        let report = vacuum_sqlite(&db_path).expect("压缩数据库失败");
+        assert!(report.freed_bytes > 0);
+        assert_eq!(report.before_bytes - report.after_bytes, report.freed_bytes);
+        assert!(vacuum_sqlite(&dir.join("missing.sqlite")).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,137 @@
+//! 诊断包导出
+//!
+//! 将排查问题所需的信息打包为一个 ZIP：n8n 日志末尾、n8n 与 Node.js 版本、平台信息、
+//! 脱敏后的设置与环境变量、启动前预检结果以及最近一次进程退出信息。
+//! 密码、令牌、加密密钥等敏感字段统一替换为占位符，凭据本身不会被导出。
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use tauri::{AppHandle, Runtime};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use super::error::N8nResult;
+use super::paths::base_dir;
+use super::state::{is_secret_key, REDACTED_VALUE};
+use super::{get_effective_env, logs, platform, preflight, versions};
+use crate::api::settings::settings_lock;
+use crate::services::manager;
+
+/// 导出日志末尾的最大字节数（512KB）
+const LOG_TAIL_BYTES: u64 = 512 * 1024;
+
+/// 导出 n8n 诊断包到 `dest`（ZIP 文件）
+pub fn export_diagnostics<R: Runtime>(app: &AppHandle<R>, dest: &Path) -> N8nResult<()> {
+    let app_path = base_dir(app)?;
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut zip = ZipWriter::new(File::create(dest)?);
+    let options = FileOptions::default();
+
+    let versions = json!({
+        "app": env!("CARGO_PKG_VERSION"),
+        "n8n": versions::current_version(&app_path),
+        "node": manager::get_runtime_version(&app_path.join("runtime")),
+        "installed_cores": versions::installed_versions(&app_path),
+    });
+    write_json(&mut zip, options, "versions.json", &versions)?;
+    write_json(&mut zip, options, "platform.json", &platform::platform_info())?;
+
+    let mut settings = serde_json::to_value(settings_lock().clone())?;
+    redact_json(&mut settings);
+    write_json(&mut zip, options, "settings.json", &settings)?;
+
+    let env = get_effective_env(app.clone())
+        .map(|env| json!(env))
+        .unwrap_or_else(|error| json!({ "error": error.to_string() }));
+    write_json(&mut zip, options, "env.json", &env)?;
+
+    let preflight = preflight::run_preflight(app)
+        .map(|report| json!(report))
+        .unwrap_or_else(|error| json!({ "error": error.to_string() }));
+    write_json(&mut zip, options, "preflight.json", &preflight)?;
+
+    let process = {
+        let manager = manager::PROCESS_MANAGER.lock().unwrap_or_else(|p| p.into_inner());
+        json!({
+            "state": manager.state(),
+            "pid": manager.pid(),
+            "port": manager.port(),
+            "last_exit": manager.last_exit(),
+        })
+    };
+    write_json(&mut zip, options, "process.json", &process)?;
+
+    if let Ok(tail) = read_tail(&logs::active_log_path(&app_path), LOG_TAIL_BYTES) {
+        zip.start_file("n8n.log", options)?;
+        zip.write_all(&tail)?;
+    }
+
+    zip.finish()?;
+    println!("[n8n] 诊断包已导出: {}", dest.display());
+    Ok(())
+}
+
+/// 以格式化 JSON 写入一个 ZIP 条目
+fn write_json<W: Write + Seek, T: Serialize>(
+    zip: &mut ZipWriter<W>,
+    options: FileOptions,
+    name: &str,
+    value: &T,
+) -> N8nResult<()> {
+    zip.start_file(name, options)?;
+    zip.write_all(&serde_json::to_vec_pretty(value)?)?;
+    Ok(())
+}
+
+/// 将 JSON 中敏感字段的字符串值替换为占位符
+fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_secret_key(key) && value.is_string() {
+                    *value = Value::String(REDACTED_VALUE.to_string());
+                } else {
+                    redact_json(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// 读取文件末尾最多 `max_bytes` 字节
+fn read_tail(path: &Path, max_bytes: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(max_bytes)))?;
+
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail)?;
+    Ok(tail)
+}
+
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_json_masks_nested_secrets() {
+        let mut settings = json!({
+            "database": { "user": "n8n", "password": "hunter2" },
+            "user_agent": null,
+        });
+        redact_json(&mut settings);
+
+        assert_eq!(settings["database"]["password"], REDACTED_VALUE);
+        assert_eq!(settings["database"]["user"], "n8n");
+        assert!(settings["user_agent"].is_null());
+    }
+}
@@ -0,0 +1,200 @@
+//! n8n 凭据加密密钥
+//!
+//! n8n 使用 `N8N_ENCRYPTION_KEY` 加密保存的凭据；未设置时会在 `n8n-data/.n8n/config` 中生成随机密钥，
+//! 数据目录被重建后旧凭据便无法解密。这里将密钥保存在数据基础目录的 `config/` 下
+//! （不在 `n8n-data` 内，卸载与重新安装核心都不会删除），每次启动 n8n 或执行 CLI 时注入同一个密钥。
+//!
+//! 密钥按数据目录分别保存：切换到另一个数据目录（如其他安装共享的目录）时不会注入不匹配的密钥。
+//! 数据目录中已有 n8n 生成的密钥时总是以其为准（n8n 拒绝以不一致的密钥启动），保证已保存的凭据仍可解密。
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::error::N8nResult;
+use super::paths::{N8N_DATA_DIRNAME, N8N_USER_DIRNAME};
+use crate::api::utils::generate_random_string;
+
+/// n8n 读取加密密钥的环境变量
+pub const ENCRYPTION_KEY_ENV: &str = "N8N_ENCRYPTION_KEY";
+
+/// 默认数据目录的密钥文件（相对于数据基础目录）
+const ENCRYPTION_KEY_RELATIVE_PATH: &str = "config/encryption.key";
+
+/// 自定义数据目录的密钥文件所在目录（相对于数据基础目录，文件名为数据目录路径的哈希）
+const CUSTOM_KEYS_RELATIVE_DIR: &str = "config/encryption-keys";
+
+/// n8n 自动生成的配置文件（相对于 n8n 数据目录，内含 `encryptionKey`）
+const N8N_CONFIG_RELATIVE_PATH: &str = ".n8n/config";
+
+/// 新生成密钥的长度（字母数字字符）
+const GENERATED_KEY_LENGTH: usize = 48;
+
+/// 数据目录对应的密钥文件路径
+///
+/// 默认数据目录使用 `config/encryption.key`，自定义数据目录使用 `config/encryption-keys/<路径哈希>.key`。
+pub fn encryption_key_path(base_dir: &Path, n8n_data_dir: &Path) -> PathBuf {
+    if n8n_data_dir == base_dir.join(N8N_DATA_DIRNAME) {
+        return base_dir.join(ENCRYPTION_KEY_RELATIVE_PATH);
+    }
+    let digest = Sha256::digest(n8n_data_dir.to_string_lossy().as_bytes());
+    base_dir.join(CUSTOM_KEYS_RELATIVE_DIR).join(format!("{digest:x}.key"))
+}
+
+/// 读取数据目录对应的密钥
+///
+/// 数据目录中已有 n8n 生成的密钥时以其为准并保存；否则使用保存的密钥，都没有时生成新密钥并保存。
+pub fn ensure_encryption_key(base_dir: &Path, n8n_data_dir: &Path) -> N8nResult<String> {
+    let path = encryption_key_path(base_dir, n8n_data_dir);
+    let saved = read_key(&path);
+
+    if let Some(key) = read_n8n_config_key(n8n_data_dir) {
+        if saved.as_deref() != Some(key.as_str()) {
+            tracing::info!(path = %path.display(), "沿用数据目录中 n8n 已生成的加密密钥");
+            write_key(&path, &key)?;
+        }
+        return Ok(key);
+    }
+    if let Some(key) = saved {
+        return Ok(key);
+    }
+
+    let key = match legacy_key(base_dir, n8n_data_dir) {
+        Some(key) => {
+            tracing::info!(path = %path.display(), "沿用此前共用的加密密钥");
+            key
+        }
+        None => {
+            tracing::info!(path = %path.display(), "生成新的加密密钥");
+            generate_random_string(GENERATED_KEY_LENGTH)
+        }
+    };
+    write_key(&path, &key)?;
+    Ok(key)
+}
+
+/// 查找数据目录对应的已有密钥（查找顺序与 `ensure_encryption_key` 相同），不生成也不保存密钥
+///
+/// 用于只读的查询（如环境变量预览）；还没有密钥时返回 None，首次启动 n8n 时才会生成。
+pub fn existing_encryption_key(base_dir: &Path, n8n_data_dir: &Path) -> Option<String> {
+    read_n8n_config_key(n8n_data_dir)
+        .or_else(|| read_key(&encryption_key_path(base_dir, n8n_data_dir)))
+        .or_else(|| legacy_key(base_dir, n8n_data_dir))
+}
+
+/// 此前所有数据目录共用默认数据目录的密钥：已有 n8n 数据的自定义目录沿用该密钥
+fn legacy_key(base_dir: &Path, n8n_data_dir: &Path) -> Option<String> {
+    read_key(&base_dir.join(ENCRYPTION_KEY_RELATIVE_PATH)).filter(|_| n8n_data_dir.join(N8N_USER_DIRNAME).is_dir())
+}
+
+/// 将数据目录对应的密钥注入 n8n 环境变量（用户在自定义环境变量中指定时以用户的为准）
+pub fn inject_encryption_key(
+    base_dir: &Path,
+    n8n_data_dir: &Path,
+    envs: &mut HashMap<String, String>,
+) -> N8nResult<()> {
+    if !envs.contains_key(ENCRYPTION_KEY_ENV) {
+        envs.insert(
+            ENCRYPTION_KEY_ENV.to_string(),
+            ensure_encryption_key(base_dir, n8n_data_dir)?,
+        );
+    }
+    Ok(())
+}
+
+fn read_key(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    let key = content.trim();
+    (!key.is_empty()).then(|| key.to_string())
+}
+
+/// 读取 n8n 配置文件中的 `encryptionKey`
+fn read_n8n_config_key(n8n_data_dir: &Path) -> Option<String> {
+    let content = fs::read_to_string(n8n_data_dir.join(N8N_CONFIG_RELATIVE_PATH)).ok()?;
+    let config: Value = serde_json::from_str(&content).ok()?;
+    config["encryptionKey"]
+        .as_str()
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(ToString::to_string)
+}
+
+/// 先写临时文件再重命名，避免中断后留下空的密钥文件；Unix 上仅允许当前用户读写
+fn write_key(path: &Path, key: &str) -> N8nResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp = path.with_extension("key.tmp");
+    fs::write(&temp, key)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&temp, fs::Permissions::from_mode(0o600))?;
+    }
+    fs::rename(&temp, path)?;
+    Ok(())
+}
+
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+
+    #[test]
+    fn test_encryption_key_adopts_existing_and_persists() {
+        let base = temp_dir().join("n8n_encryption_key_test");
+        let _ = fs::remove_dir_all(&base);
+        let data_dir = base.join("n8n-data");
+        fs::create_dir_all(data_dir.join(".n8n")).expect("创建数据目录失败");
+        fs::write(data_dir.join(".n8n/config"), r#"{"encryptionKey":"existing-key"}"#)
+            .expect("写入 n8n 配置失败");
+
+        assert_eq!(ensure_encryption_key(&base, &data_dir).expect("读取密钥失败"), "existing-key");
+
+        // 数据目录被删除后仍使用保存的密钥
+        fs::remove_dir_all(&data_dir).expect("删除数据目录失败");
+        let mut envs = HashMap::new();
+        inject_encryption_key(&base, &data_dir, &mut envs).expect("注入密钥失败");
+        assert_eq!(envs.get(ENCRYPTION_KEY_ENV).map(String::as_str), Some("existing-key"));
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_existing_encryption_key_does_not_generate() {
+        let base = temp_dir().join("n8n_encryption_key_lookup_test");
+        let _ = fs::remove_dir_all(&base);
+        let data_dir = base.join("n8n-data");
+
+        assert_eq!(existing_encryption_key(&base, &data_dir), None);
+        assert!(!encryption_key_path(&base, &data_dir).exists());
+
+        let key = ensure_encryption_key(&base, &data_dir).expect("生成密钥失败");
+        assert_eq!(existing_encryption_key(&base, &data_dir), Some(key));
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_encryption_key_follows_data_dir() {
+        let base = temp_dir().join("n8n_encryption_key_switch_test");
+        let _ = fs::remove_dir_all(&base);
+        let default_key = ensure_encryption_key(&base, &base.join("n8n-data")).expect("生成密钥失败");
+
+        // 切换到其他安装共享的数据目录时使用该目录中的密钥，不影响默认数据目录的密钥
+        let shared = base.join("shared");
+        fs::create_dir_all(shared.join(".n8n")).expect("创建数据目录失败");
+        fs::write(shared.join(".n8n/config"), r#"{"encryptionKey":"shared-key"}"#)
+            .expect("写入 n8n 配置失败");
+        assert_eq!(ensure_encryption_key(&base, &shared).expect("读取密钥失败"), "shared-key");
+        assert_eq!(
+            ensure_encryption_key(&base, &base.join("n8n-data")).expect("读取密钥失败"),
+            default_key
+        );
+
+        let _ = fs::remove_dir_all(&base);
+    }
+}
@@ -0,0 +1,86 @@
+//! n8n 入口文件解析模块
+//!
+//! n8n 通过 `node <入口> start` 启动。不同打包方式下入口位置可能不同：
+//! `bin/n8n` 可能是指向 `dist` 的符号链接，也可能缺失而只保留 `dist/index.js`。
+//! 这里按顺序尝试多个候选位置，避免构建产物的目录调整导致无法启动。
+//! 核心目录由 `versions` 模块按当前启用的版本解析。
+
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::error::{N8nCoreError, N8nResult};
+use super::versions;
+use crate::i18n;
+
+/// 默认入口（相对 n8n 包目录）
+const DEFAULT_BIN: &str = "bin/n8n";
+
+/// 备用入口（相对 n8n 包目录）
+const FALLBACK_DIST_ENTRY: &str = "dist/index.js";
+
+/// 当前启用的 n8n 包目录
+pub fn n8n_package_dir(app_data_dir: &Path) -> PathBuf {
+    versions::package_dir(&versions::core_dir(app_data_dir))
+}
+
+/// 解析当前启用版本的 n8n 入口文件
+pub fn resolve_n8n_entrypoint(app_data_dir: &Path) -> N8nResult<PathBuf> {
+    resolve_entrypoint_in(&versions::core_dir(app_data_dir))
+}
+
+/// 解析指定核心目录中的 n8n 入口文件
+///
+/// 依次尝试 `bin/n8n`（符号链接会被解析为真实路径）、`package.json` 的 `bin` 字段、`dist/index.js`，
+/// 全部失败时返回列出已搜索路径的错误。
+pub fn resolve_entrypoint_in(core_dir: &Path) -> N8nResult<PathBuf> {
+    let package_dir = versions::package_dir(core_dir);
+
+    let mut candidates = vec![package_dir.join(DEFAULT_BIN)];
+    if let Some(bin) = read_package_bin(&package_dir) {
+        candidates.push(package_dir.join(bin));
+    }
+    candidates.push(package_dir.join(FALLBACK_DIST_ENTRY));
+
+    for candidate in &candidates {
+        // canonicalize 会解析符号链接，并在目标不存在（如悬空链接）时失败
+        if let Ok(resolved) = fs::canonicalize(candidate) {
+            if resolved.is_file() {
+                return Ok(resolved);
+            }
+        }
+    }
+
+    let searched = candidates
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(N8nCoreError::Installation(format!(
+        "{} ({searched})",
+        i18n::t("n8n.entrypoint_not_found")
+    )))
+}
+
+/// n8n 入口文件是否存在
+pub fn has_n8n_entrypoint(app_data_dir: &Path) -> bool {
+    resolve_n8n_entrypoint(app_data_dir).is_ok()
+}
+
+/// 读取 `package.json` 中 `bin` 字段声明的 n8n 入口
+///
+/// `bin` 可以是字符串，也可以是 `{ "n8n": "./bin/n8n" }` 形式的映射。
+fn read_package_bin(package_dir: &Path) -> Option<String> {
+    let content = fs::read_to_string(package_dir.join("package.json")).ok()?;
+    let package: Value = serde_json::from_str(&content).ok()?;
+
+    match &package["bin"] {
+        Value::String(bin) => Some(bin.clone()),
+        Value::Object(bins) => bins
+            .get("n8n")
+            .or_else(|| bins.values().next())
+            .and_then(Value::as_str)
+            .map(ToString::to_string),
+        _ => None,
+    }
+}
@@ -43,10 +43,34 @@ pub enum N8nCoreError {
     #[error("路径操作失败: {0}")]
     Path(String),
 
+    /// 应用数据目录无法创建或不可写
+    #[error("[DATA_DIR_UNWRITABLE] 应用数据目录不可写: {path} ({reason})")]
+    DataDirUnwritable { path: String, reason: String },
+
+    /// 安装所在磁盘的可用空间不足
+    #[error("[INSUFFICIENT_DISK_SPACE] 磁盘空间不足: {path} 需要 {required} 字节，可用 {available} 字节")]
+    InsufficientDiskSpace {
+        path: String,
+        required: u64,
+        available: u64,
+    },
+
+    /// 同一操作已在进行中
+    #[error("[ALREADY_IN_PROGRESS] 操作正在进行中: {0}")]
+    AlreadyInProgress(String),
+
     /// 配置错误
     #[error("配置错误: {0}")]
     Config(String),
 
+    /// n8n 在限定时间内未就绪
+    #[error("[STARTUP_TIMEOUT] n8n 启动超时: {0}")]
+    StartupTimeout(String),
+
+    /// n8n 启动后立即退出
+    #[error("[STARTUP_FAILED] n8n 启动失败: {0}")]
+    StartupFailed(String),
+
     /// 服务未响应
     #[error("服务未响应: {0}")]
     ServiceUnavailable(String),
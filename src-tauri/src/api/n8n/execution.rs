@@ -0,0 +1,65 @@
+//! n8n 执行模式配置模块
+//!
+//! 将执行模式设置转换为 n8n 环境变量，并在启动前校验队列模式的依赖
+//! （队列模式不支持 SQLite，且需要可达的 Redis）。
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use super::connectivity::check_tcp_reachable;
+use super::error::{N8nCoreError, N8nResult};
+use crate::api::settings::{DatabaseSettings, DatabaseType, ExecutionMode, ExecutionSettings};
+use crate::i18n;
+
+/// Redis 连通性检测超时
+const REDIS_CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 将执行模式设置转换为 n8n 环境变量
+pub fn execution_envs(execution: &ExecutionSettings) -> HashMap<String, String> {
+    let mut envs = HashMap::new();
+    envs.insert("EXECUTIONS_MODE".to_string(), execution.mode.as_str().to_string());
+
+    if execution.mode == ExecutionMode::Queue {
+        envs.insert(
+            "QUEUE_BULL_REDIS_HOST".to_string(),
+            execution.redis_host.trim().to_string(),
+        );
+        envs.insert("QUEUE_BULL_REDIS_PORT".to_string(), execution.redis_port.to_string());
+    }
+
+    envs
+}
+
+/// 需要启动的 worker 进程数量（非队列模式为 0，队列模式至少 1 个）
+pub fn worker_count(execution: &ExecutionSettings) -> usize {
+    match execution.mode {
+        ExecutionMode::Regular => 0,
+        ExecutionMode::Queue => execution.workers.max(1),
+    }
+}
+
+/// 启动前确认队列模式的依赖可用
+pub fn ensure_execution_ready(
+    execution: &ExecutionSettings,
+    database: &DatabaseSettings,
+) -> N8nResult<()> {
+    if execution.mode == ExecutionMode::Regular {
+        return Ok(());
+    }
+
+    if database.db_type == DatabaseType::Sqlite {
+        return Err(N8nCoreError::Config(i18n::t("queue.requires_postgres")));
+    }
+
+    check_redis_reachable(execution)
+}
+
+/// 检测 Redis 服务器是否可达（仅做 TCP 连接测试）
+fn check_redis_reachable(execution: &ExecutionSettings) -> N8nResult<()> {
+    check_tcp_reachable(
+        &execution.redis_host,
+        execution.redis_port,
+        REDIS_CONNECT_TIMEOUT,
+        "queue.redis_unreachable",
+    )
+}
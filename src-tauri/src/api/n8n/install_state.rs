@@ -0,0 +1,168 @@
+//! n8n 安装状态持久化模块
+//!
+//! 在 `setup_n8n` 过程中记录当前阶段，机器中途重启后可据此恢复安装，
+//! 而不是让用户回到空白的引导页。安装完成后状态文件保留，记录资源包的完整性校验结果，
+//! 供界面展示当前安装是否经过 SHA256 校验。
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::error::N8nResult;
+use super::installer::{VerificationSkipReason, VerificationSkipped};
+
+/// 安装状态文件名
+pub const INSTALL_STATE_FILENAME: &str = "install-state.json";
+
+/// 安装阶段
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallStage {
+    /// 正在下载资源包
+    Downloading,
+    /// 资源包已就绪，正在解压
+    Extracting,
+    /// 安装已完成
+    Completed,
+}
+
+/// 资源包完整性校验结果
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct InstallVerification {
+    /// 是否已与发布摘要比对通过
+    pub verified: bool,
+    /// 实际安装的资源包 SHA256
+    pub sha256: String,
+    /// 跳过校验的原因（`verified` 为 false 时）
+    pub skip_reason: Option<VerificationSkipReason>,
+    /// 跳过校验的详细说明
+    pub detail: Option<String>,
+}
+
+impl InstallVerification {
+    /// 已通过校验
+    pub fn verified(sha256: String) -> Self {
+        Self {
+            verified: true,
+            sha256,
+            skip_reason: None,
+            detail: None,
+        }
+    }
+
+    /// 未能校验（记录跳过原因）
+    pub fn skipped(sha256: String, skipped: Option<VerificationSkipped>) -> Self {
+        Self {
+            verified: false,
+            sha256,
+            skip_reason: skipped.as_ref().map(|skipped| skipped.reason),
+            detail: skipped.map(|skipped| skipped.detail),
+        }
+    }
+}
+
+/// 持久化的安装状态
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct InstallState {
+    /// 当前阶段
+    pub stage: InstallStage,
+    /// 目标资源包文件名（如 `n8n-core-macos.zip`）
+    pub asset: String,
+    /// 目标版本的 SHA256（远程摘要，可能无法获取）
+    pub expected_sha256: Option<String>,
+    /// 资源包校验结果（安装完成时写入）
+    #[serde(default)]
+    pub verification: Option<InstallVerification>,
+    /// 安装完成的核心版本（安装完成时写入）
+    #[serde(default)]
+    pub version: Option<String>,
+    /// 最后更新时间（RFC3339 格式）
+    pub updated_at: String,
+}
+
+impl InstallState {
+    /// 创建新的安装状态
+    pub fn new(stage: InstallStage, asset: String, expected_sha256: Option<String>) -> Self {
+        Self {
+            stage,
+            asset,
+            expected_sha256,
+            verification: None,
+            version: None,
+            updated_at: chrono::Local::now().to_rfc3339(),
+        }
+    }
+
+    /// 安装完成时的状态（记录安装的版本与校验结果）
+    pub fn completed(asset: String, version: String, verification: InstallVerification) -> Self {
+        Self {
+            expected_sha256: Some(verification.sha256.clone()),
+            verification: Some(verification),
+            version: Some(version),
+            ..Self::new(InstallStage::Completed, asset, None)
+        }
+    }
+
+    /// 获取状态文件路径
+    pub fn path(app_data_dir: &Path) -> PathBuf {
+        app_data_dir.join(INSTALL_STATE_FILENAME)
+    }
+
+    /// 读取状态文件（文件不存在或损坏时返回 None）
+    pub fn load(app_data_dir: &Path) -> Option<Self> {
+        let content = fs::read_to_string(Self::path(app_data_dir)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// 读取未完成的安装状态
+    pub fn load_pending(app_data_dir: &Path) -> Option<Self> {
+        Self::load(app_data_dir).filter(|state| state.stage != InstallStage::Completed)
+    }
+
+    /// 保存安装状态
+    pub fn save(&self, app_data_dir: &Path) -> N8nResult<()> {
+        fs::create_dir_all(app_data_dir)?;
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(Self::path(app_data_dir), content)?;
+        Ok(())
+    }
+
+    /// 是否存在未完成的安装（状态文件损坏时同样视为未完成）
+    pub fn is_pending(app_data_dir: &Path) -> bool {
+        match Self::load(app_data_dir) {
+            Some(state) => state.stage != InstallStage::Completed,
+            None => Self::path(app_data_dir).exists(),
+        }
+    }
+}
+
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+
+    #[test]
+    fn test_completed_state_is_not_pending() {
+        let dir = temp_dir().join("n8n_install_state_completed_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        InstallState::new(InstallStage::Extracting, "core.zip".to_string(), None)
+            .save(&dir)
+            .expect("保存安装状态失败");
+        assert!(InstallState::is_pending(&dir));
+
+        let verification = InstallVerification::verified("abc".to_string());
+        InstallState::completed("core.zip".to_string(), "1.90.0".to_string(), verification.clone())
+            .save(&dir)
+            .expect("保存安装状态失败");
+        assert!(!InstallState::is_pending(&dir));
+        assert!(InstallState::load_pending(&dir).is_none());
+        assert_eq!(
+            InstallState::load(&dir).and_then(|state| state.verification),
+            Some(verification)
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -2,19 +2,66 @@
 //!
 //! 提供 n8n 核心包的下载、验证和安装功能。
 
-use crate::services::{downloader, manager};
-use reqwest;
+use crate::api::settings::{settings_lock, CoreSourceSettings, DownloadMirror};
+use crate::i18n;
+use crate::services::{archive, download_control, downloader, http, keychain, manager};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
 use std::env;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
-use tauri::{AppHandle, Manager, Runtime, Window};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use tauri::{AppHandle, Emitter, Runtime, Window};
 use zip::ZipArchive;
 
 use super::constants::*;
+use super::entrypoint::{has_n8n_entrypoint, resolve_entrypoint_in};
 use super::error::{N8nCoreError, N8nResult};
+use super::install_state::{InstallStage, InstallState, InstallVerification};
+use super::offline;
+use super::paths::base_dir;
+use super::setup_phase::{self, SetupPhase};
+use super::versions;
+
+/// 解压进度回调（已解压文件数，文件总数）
+type ExtractionProgress<'a> = &'a (dyn Fn(usize, usize) + Sync);
+
+/// 哈希计算进度回调（已读取字节数，文件总字节数）
+type HashProgress<'a> = &'a dyn Fn(u64, u64);
+
+/// 安装各阶段在整体进度中的区间：下载、校验资源包、解压
+///
+/// 本地已有资源包时跳过下载，整体进度直接从校验阶段开始；
+/// 本地资源包校验不通过而重新下载时，整体进度会回到下载阶段。
+const DOWNLOAD_PHASE_RANGE: downloader::OverallRange = (0.0, 60.0);
+const VERIFY_PHASE_RANGE: downloader::OverallRange = (60.0, 75.0);
+const EXTRACT_PHASE_RANGE: downloader::OverallRange = (75.0, 100.0);
+
+/// `verify-progress` 事件负载
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyProgressEvent {
+    pub download_type: String,
+    pub verified_bytes: u64,
+    pub total_bytes: u64,
+    /// 百分比（0-100）
+    pub progress: f64,
+}
+
+/// 本地资源包检查结果
+enum CachedArchive {
+    /// 不存在或已损坏，需要下载
+    Missing,
+    /// 可以直接使用；已与远程摘要比对过时附带计算出的哈希
+    Usable(Option<String>),
+}
+
+/// 将阶段内的百分比映射为整体进度
+fn phase_progress((start, end): downloader::OverallRange, percent: f64) -> f64 {
+    start + percent.clamp(0.0, 100.0) * (end - start) / 100.0
+}
 
 /// n8n 安装管理器
 pub struct N8nInstaller {
@@ -25,108 +72,267 @@ pub struct N8nInstaller {
 impl N8nInstaller {
     /// 创建新的安装管理器
     pub fn new<R: Runtime>(app: &AppHandle<R>) -> N8nResult<Self> {
-        let platform = match env::consts::OS {
-            "windows" => "windows",
-            "macos" => "macos",
-            "linux" => "linux",
-            _ => "unknown",
-        };
-
-        let app_data_dir = app
-            .path()
-            .app_data_dir()
-            .map_err(|e| N8nCoreError::Path(e.to_string()))?;
+        let app_data_dir = base_dir(app)?;
 
         Ok(Self {
-            platform: platform.to_string(),
+            platform: core_platform().to_string(),
             app_data_dir,
         })
     }
 
-    /// 检查是否已安装
+    /// 检查是否已安装（存在未完成的安装状态时视为未安装）
     pub fn is_installed(&self) -> bool {
-        let bin_path = self.app_data_dir.join("n8n-core/node_modules/n8n/bin/n8n");
-        bin_path.exists()
+        self.has_core_binary() && !InstallState::is_pending(&self.app_data_dir)
+    }
+
+    /// 检查当前启用版本的 n8n 可执行入口是否存在
+    pub fn has_core_binary(&self) -> bool {
+        has_n8n_entrypoint(&self.app_data_dir)
+    }
+
+    /// 获取资源包文件名
+    pub fn asset_name(&self) -> String {
+        core_asset_name(&self.platform)
+    }
+
+    /// 获取平台标识
+    pub fn platform(&self) -> &str {
+        &self.platform
+    }
+
+    /// 获取数据基础目录
+    pub fn app_data_dir(&self) -> &Path {
+        &self.app_data_dir
     }
 
     /// 获取下载 URL
     pub fn download_url(&self) -> String {
-        let file_name = format!("n8n-core-{}.zip", self.platform);
-        format!("{}{}/{}", GH_PROXY_PREFIX, N8N_CORE_BASE_URL, file_name)
+        let settings = settings_lock();
+        core_download_url(&settings.core_source, &settings.download_mirror, &self.asset_name())
     }
 
     /// 获取目标文件路径
     pub fn zip_path(&self) -> PathBuf {
-        let file_name = format!("n8n-core-{}.zip", self.platform);
-        self.app_data_dir.join(file_name)
+        self.app_data_dir.join(self.asset_name())
     }
 
     /// 获取解压目录
     pub fn extract_dir(&self) -> PathBuf {
-        self.app_data_dir.join("n8n-core")
+        self.app_data_dir.join(versions::LEGACY_CORE_DIRNAME)
     }
 
     /// 执行安装
-    pub async fn install<R: Runtime>(&self, window: Window<R>) -> N8nResult<()> {
+    ///
+    /// `source` 指定本地资源包；未指定时若 `offline/` 中预置了同名资源包也会直接使用。
+    pub async fn install<R: Runtime>(
+        &self,
+        window: Window<R>,
+        source: Option<PathBuf>,
+    ) -> N8nResult<()> {
         println!("开始处理 n8n 资源包: {}", self.platform);
 
-        // 1. 获取远程 SHA256 哈希值
+        let offline_archive = source
+            .or_else(|| offline::find_offline_archive(&self.app_data_dir, &self.asset_name()));
+
+        // 1. 获取远程 SHA256 哈希值（离线安装时获取失败不影响继续）
         println!("正在获取远程 SHA256 哈希值...");
-        let remote_sha256_opt = fetch_latest_sha256(&self.platform).await?;
-
-        let need_download = self.should_download(remote_sha256_opt)?;
-
-        // 2. 如果需要下载，则下载文件
-        if need_download {
-            println!("开始下载资源包: {}", self.download_url());
-            downloader::download_file(
-                window.clone(),
-                self.download_url(),
-                self.zip_path(),
-                "n8n-core".to_string(),
-            )
-            .await
-            .map_err(N8nCoreError::Installation)?;
-            println!("下载完成");
+        let remote_digest = match &offline_archive {
+            Some(_) => fetch_latest_digest(&self.platform).await.unwrap_or_else(|e| {
+                println!("获取远程 SHA256 失败，使用本地摘要校验离线资源包: {}", e);
+                Err(VerificationSkipped {
+                    asset: self.asset_name(),
+                    reason: VerificationSkipReason::Unreachable,
+                    detail: e.to_string(),
+                })
+            }),
+            None => fetch_latest_digest(&self.platform).await?,
+        };
+        let (remote_sha256_opt, skipped) = match remote_digest {
+            Ok(sha256) => (Some(sha256), None),
+            Err(skipped) => {
+                // 打包后的 GUI 看不到控制台输出，通过事件告知前端完整性校验被跳过
+                let _ = window.emit(VERIFICATION_SKIPPED_EVENT, &skipped);
+                (None, Some(skipped))
+            }
+        };
+
+        // 上次安装未完成（如中途重启）：远程摘要不可用时沿用记录的摘要重新校验
+        let pending_state = InstallState::load_pending(&self.app_data_dir);
+        if let Some(state) = &pending_state {
+            println!("检测到未完成的安装 (阶段: {:?})，继续安装", state.stage);
+        }
+        let expected_sha256 =
+            remote_sha256_opt.or_else(|| pending_state.and_then(|state| state.expected_sha256));
+
+        let on_verify = |verified: u64, total: u64| {
+            let progress = verified as f64 * 100.0 / total.max(1) as f64;
+            let _ = window.emit(
+                VERIFY_PROGRESS_EVENT,
+                VerifyProgressEvent {
+                    download_type: N8N_CORE_OPERATION.to_string(),
+                    verified_bytes: verified,
+                    total_bytes: total,
+                    progress,
+                },
+            );
+            downloader::emit_overall_progress(
+                &window,
+                N8N_CORE_OPERATION,
+                phase_progress(VERIFY_PHASE_RANGE, progress),
+            );
+        };
+
+        // 2. 离线资源包校验后放到下载位置；否则按需下载
+        let mut known_sha256 = None;
+        if let Some(archive_path) = offline_archive {
+            println!("使用离线资源包: {}", archive_path.display());
+            setup_phase::emit(&window, SetupPhase::VerifyingHash);
+            offline::verify_offline_archive(&archive_path, expected_sha256.as_deref())?;
+            if archive_path != self.zip_path() {
+                fs::copy(&archive_path, self.zip_path())?;
+                downloader::sync_file(&self.zip_path())?;
+            }
+        } else {
+            match self.check_cached_archive(expected_sha256.as_deref(), &window, &on_verify) {
+                CachedArchive::Usable(sha256) => known_sha256 = sha256,
+                CachedArchive::Missing => {
+                    self.save_install_state(InstallStage::Downloading, &expected_sha256)?;
+                    setup_phase::emit(&window, SetupPhase::DownloadingCore);
+                    tracing::info!(url = %self.download_url(), "开始下载资源包");
+                    downloader::download_file_in_range(
+                        window.clone(),
+                        self.download_url(),
+                        self.zip_path(),
+                        N8N_CORE_OPERATION.to_string(),
+                        DOWNLOAD_PHASE_RANGE,
+                    )
+                    .await
+                    .map_err(N8nCoreError::Installation)?;
+                    tracing::info!("资源包下载完成");
+                }
+            }
         }
 
-        // 3. 清理旧的目录并解压
-        self.clean_and_extract()?;
+        // 校验最终使用的资源包（本地资源包刚校验过时复用其哈希），结果在安装完成后写入安装状态
+        downloader::notify_setup_stage(&window, "verifying", N8N_CORE_OPERATION);
+        setup_phase::emit(&window, SetupPhase::VerifyingHash);
+        let sha256 = match known_sha256 {
+            Some(sha256) => sha256,
+            None => calculate_file_sha256_with_progress(&self.zip_path(), &on_verify)?,
+        };
+        let verification = self.verify_archive(sha256, expected_sha256.as_deref(), skipped)?;
+
+        // 3. 清理旧的目录并解压；之后任何一步失败或被取消，守卫都会删除暂存目录，
+        //    已启用的 cores/<版本>/ 不受影响
+        self.save_install_state(InstallStage::Extracting, &expected_sha256)?;
+        downloader::notify_setup_stage(&window, "extracting", N8N_CORE_OPERATION);
+        setup_phase::emit(&window, SetupPhase::ExtractingCore);
+        let staging = archive::ExtractionDirGuard::new(self.extract_dir());
+        let on_progress = |extracted: usize, total: usize| {
+            let progress =
+                downloader::emit_extraction_progress(&window, N8N_CORE_OPERATION, extracted, total);
+            downloader::emit_overall_progress(
+                &window,
+                N8N_CORE_OPERATION,
+                phase_progress(EXTRACT_PHASE_RANGE, progress),
+            );
+        };
+        self.clean_and_extract(&on_progress)?;
 
-        println!("n8n-core 安装完成");
+        // 4. 确认解压结果完整后移入 cores/<版本>/ 并设为启用版本，最后才标记安装完成
+        if resolve_entrypoint_in(&self.extract_dir()).is_err() {
+            return Err(N8nCoreError::Installation(i18n::t("n8n.binary_not_found")));
+        }
+        let version = versions::store_installed_core(&self.app_data_dir, &self.extract_dir())?;
+        staging.commit();
+        InstallState::completed(self.asset_name(), version.clone(), verification).save(&self.app_data_dir)?;
+        downloader::notify_setup_stage(&window, "done", N8N_CORE_OPERATION);
+
+        println!("n8n-core {version} 安装完成");
         Ok(())
     }
 
-    /// 判断是否需要下载
-    fn should_download(&self, remote_sha: Option<String>) -> N8nResult<bool> {
+    /// 记录当前安装阶段
+    fn save_install_state(
+        &self,
+        stage: InstallStage,
+        expected_sha256: &Option<String>,
+    ) -> N8nResult<()> {
+        InstallState::new(stage, self.asset_name(), expected_sha256.clone())
+            .save(&self.app_data_dir)
+    }
+
+    /// 读取未完成的安装状态
+    pub fn pending_install(&self) -> Option<InstallState> {
+        InstallState::load_pending(&self.app_data_dir)
+    }
+
+    /// 将资源包哈希与预期摘要比对（无预期摘要时记录跳过原因）
+    ///
+    /// 哈希不匹配时删除资源包，下次安装重新下载。
+    fn verify_archive(
+        &self,
+        sha256: String,
+        expected_sha256: Option<&str>,
+        skipped: Option<VerificationSkipped>,
+    ) -> N8nResult<InstallVerification> {
+        let path = self.zip_path();
+
+        match expected_sha256 {
+            Some(expected) if expected != sha256 => {
+                let _ = fs::remove_file(&path);
+                Err(N8nCoreError::HashMismatch {
+                    expected: expected.to_string(),
+                    actual: sha256,
+                })
+            }
+            Some(_) => {
+                println!("资源包 SHA256 校验通过: {sha256}");
+                Ok(InstallVerification::verified(sha256))
+            }
+            None => Ok(InstallVerification::skipped(sha256, skipped)),
+        }
+    }
+
+    /// 检查本地已有的资源包，判断是否需要下载
+    ///
+    /// 校验本地文件时发送 `verifying` 阶段与校验进度。
+    fn check_cached_archive<R: Runtime>(
+        &self,
+        remote_sha: Option<&str>,
+        window: &Window<R>,
+        on_verify: HashProgress,
+    ) -> CachedArchive {
         let path = self.zip_path();
 
         // 场景 A：本地文件根本不存在 -> 直接下载
         if !path.exists() {
             println!("本地文件不存在，需要下载");
-            return Ok(true);
+            return CachedArchive::Missing;
         }
 
         // 场景 B：无法获取远程哈希 -> 信任本地现有文件
         let Some(remote_hash) = remote_sha else {
             println!("无法获取远程 SHA256，跳过验证直接使用本地文件");
-            return Ok(false);
+            return CachedArchive::Usable(None);
         };
 
         // 场景 C：本地存在且有远程哈希 -> 验证完整性
         println!("成功获取远程 SHA256: {}，正在验证完整性...", remote_hash);
+        downloader::notify_setup_stage(window, "verifying", N8N_CORE_OPERATION);
+        setup_phase::emit(window, SetupPhase::VerifyingHash);
 
-        let local_hash = match calculate_file_sha256(&path) {
+        let local_hash = match calculate_file_sha256_with_progress(&path, on_verify) {
             Ok(h) => h,
             Err(e) => {
                 println!("计算本地文件哈希失败: {}，准备重新下载", e);
-                return Ok(true);
+                return CachedArchive::Missing;
             }
         };
 
         if local_hash == remote_hash {
             println!("文件完整性验证通过，跳过下载");
-            Ok(false)
+            CachedArchive::Usable(Some(local_hash))
         } else {
             println!(
                 "文件哈希不匹配 (本地: {}, 远程: {})",
@@ -136,12 +342,12 @@ impl N8nInstaller {
             let _ = fs::remove_file(&path).map_err(|e| {
                 eprintln!("警告：清理损坏文件失败: {}", e);
             });
-            Ok(true)
+            CachedArchive::Missing
         }
     }
 
     /// 清理旧的目录并解压
-    fn clean_and_extract(&self) -> N8nResult<()> {
+    fn clean_and_extract(&self, on_progress: ExtractionProgress) -> N8nResult<()> {
         let final_dir = self.extract_dir();
 
         // 清理旧的目录（如果存在），防止解压冲突
@@ -150,62 +356,251 @@ impl N8nInstaller {
         }
         fs::create_dir_all(&final_dir)?;
 
-        // 解压到最终目录
         println!("开始解压到: {:?}", final_dir);
-        self.extract_zip_file(&self.zip_path(), &final_dir)?;
+        self.extract_zip_file(&self.zip_path(), &final_dir, on_progress)?;
         println!("解压完成");
 
         Ok(())
     }
 
     /// 解压 ZIP 文件
-    fn extract_zip_file(&self, archive_path: &Path, target_dir: &Path) -> N8nResult<()> {
+    ///
+    /// 先按顺序创建全部目录，再由多个线程并发解压文件：每个线程各自打开压缩包，
+    /// 按序号领取条目。线程数由设置决定（0 表示按 CPU 核数自动选择），机械硬盘可设为 1。
+    fn extract_zip_file(
+        &self,
+        archive_path: &Path,
+        target_dir: &Path,
+        on_progress: ExtractionProgress,
+    ) -> N8nResult<()> {
         let file = fs::File::open(archive_path)?;
         let mut archive = ZipArchive::new(file)?;
+        archive::ensure_zip_within_limit(&mut archive, archive::MAX_ARCHIVE_UNCOMPRESSED_SIZE)
+            .map_err(N8nCoreError::Installation)?;
+        archive::ensure_memory_headroom(archive::MIN_AVAILABLE_MEMORY)
+            .map_err(N8nCoreError::Installation)?;
 
+        // 使用扩展长度路径，避免深层 node_modules 超出 Windows MAX_PATH
+        let target_root = archive::extended_length_path(target_dir);
+        let io_failed = |path: &Path, e: io::Error| match archive::path_too_long_message(path, &e) {
+            Some(message) => N8nCoreError::Installation(message),
+            None => N8nCoreError::Io(e),
+        };
+
+        // 1. 创建目录，并收集需要解压的文件条目
+        let mut files = Vec::new();
         for i in 0..archive.len() {
-            let mut file = archive.by_index(i)?;
-            let outpath = match file.enclosed_name() {
-                Some(path) => target_dir.join(path),
-                None => continue,
-            };
-
-            if (*file.name()).ends_with('/') {
-                fs::create_dir_all(&outpath)?;
+            let entry = archive.by_index_raw(i)?;
+            let outpath = archive::zip_entry_outpath(&target_root, &entry)
+                .map_err(N8nCoreError::Installation)?;
+
+            if entry.name().ends_with('/') {
+                fs::create_dir_all(&outpath).map_err(|e| io_failed(&outpath, e))?;
             } else {
                 if let Some(p) = outpath.parent() {
                     if !p.exists() {
-                        fs::create_dir_all(p)?;
+                        fs::create_dir_all(p).map_err(|e| io_failed(p, e))?;
                     }
                 }
-                let mut outfile = fs::File::create(&outpath)?;
-                io::copy(&mut file, &mut outfile)?;
+                files.push((i, outpath));
             }
         }
+        drop(archive);
+
+        // 2. 并发解压文件；任一线程失败或安装被取消时其余线程尽快停止
+        let total = files.len();
+        let threads =
+            archive::extraction_threads(settings_lock().extraction_threads).min(total.max(1));
+        let progress_step = downloader::extraction_progress_step(total);
+        let next = AtomicUsize::new(0);
+        let extracted = AtomicUsize::new(0);
+        let aborted = AtomicBool::new(false);
+        println!("使用 {threads} 个线程解压 {total} 个文件");
+
+        let extract_files = || -> N8nResult<()> {
+            let mut archive = ZipArchive::new(fs::File::open(archive_path)?)?;
+            loop {
+                if aborted.load(Ordering::Relaxed) {
+                    return Ok(());
+                }
+                if download_control::is_canceled(N8N_CORE_OPERATION) {
+                    return Err(N8nCoreError::Installation(download_control::canceled_error()));
+                }
+                let Some((index, outpath)) = files.get(next.fetch_add(1, Ordering::Relaxed)) else {
+                    return Ok(());
+                };
+
+                let mut entry = archive.by_index(*index)?;
+                let mut outfile = fs::File::create(outpath).map_err(|e| io_failed(outpath, e))?;
+                archive::copy_bounded(&mut entry, &mut outfile)?;
+
+                let done = extracted.fetch_add(1, Ordering::Relaxed) + 1;
+                if done.is_multiple_of(progress_step) || done == total {
+                    on_progress(done, total);
+                }
+            }
+        };
+
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let result = extract_files();
+                        if result.is_err() {
+                            aborted.store(true, Ordering::Relaxed);
+                        }
+                        result
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .map(|worker| {
+                    worker.join().unwrap_or_else(|_| {
+                        Err(N8nCoreError::Installation(i18n::t("archive.worker_panicked")))
+                    })
+                })
+                .collect::<N8nResult<Vec<()>>>()
+        })?;
         Ok(())
     }
 }
 
+/// 当前系统对应的 n8n 核心包平台标识（不支持的系统为 `unknown`）
+pub fn core_platform() -> &'static str {
+    match env::consts::OS {
+        "windows" => "windows",
+        "macos" => "macos",
+        "linux" => "linux",
+        _ => "unknown",
+    }
+}
+
+/// n8n 核心包资源文件名
+pub fn core_asset_name(platform: &str) -> String {
+    format!("n8n-core-{}.zip", platform)
+}
+
+/// 核心包来源最新发布的 GitHub API 地址
+pub fn latest_release_api_url(source: &CoreSourceSettings) -> String {
+    format!(
+        "{}/repos/{}/{}/releases/latest",
+        source.api_base_url.trim_end_matches('/'),
+        source.owner,
+        source.repo
+    )
+}
+
+/// 核心包资源下载地址（未配置发布地址时按下载源设置经 gh-proxy 或直连 GitHub Releases 下载）
+pub fn core_download_url(source: &CoreSourceSettings, mirror: &DownloadMirror, asset: &str) -> String {
+    match source.release_base_url.as_deref().map(str::trim).filter(|url| !url.is_empty()) {
+        Some(base) => format!("{}/{asset}", base.trim_end_matches('/')),
+        None if mirror.uses_gh_proxy() => {
+            format!("{GH_PROXY_PREFIX}{}", github_release_download_url(source, asset))
+        }
+        None => github_release_download_url(source, asset),
+    }
+}
+
+/// GitHub Releases 上最新版本资源的直连地址（不经代理）
+pub fn github_release_download_url(source: &CoreSourceSettings, asset: &str) -> String {
+    format!(
+        "{GITHUB_RELEASES_BASE_URL}/{}/{}/releases/latest/download/{asset}",
+        source.owner, source.repo
+    )
+}
+
+/// 跳过 SHA256 校验的原因
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationSkipReason {
+    /// GitHub API 限流（403 / 429）
+    RateLimited,
+    /// GitHub API 返回其他错误状态
+    ApiError,
+    /// 发布中没有对应平台的资源包
+    MissingAsset,
+    /// digest 不是 `sha256:<hex>` 格式
+    BadDigestFormat,
+    /// 无法访问 GitHub API（仅离线安装时容忍）
+    Unreachable,
+    /// Node.js 镜像未提供 `SHASUMS256.txt`
+    MissingChecksums,
+}
+
+/// `verification-skipped` 事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct VerificationSkipped {
+    pub asset: String,
+    pub reason: VerificationSkipReason,
+    pub detail: String,
+}
+
+/// 构造 GitHub API 请求：配置了 GitHub 令牌时以 `Authorization: Bearer` 认证（限额 5000 次/小时），
+/// 否则匿名访问（60 次/小时）
+///
+/// 核心包来源的 API 地址可以指向任意服务器，令牌只通过 HTTPS 发送给 GitHub 官方 API（`api.github.com`）。
+pub fn github_api_get(client: &reqwest::Client, url: &str) -> reqwest::RequestBuilder {
+    let request = client.get(url).header("Accept", GITHUB_ACCEPT_HEADER);
+    match keychain::github_token().filter(|_| is_github_api_url(url)) {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    }
+}
+
+/// 是否为通过 HTTPS 访问的 GitHub 官方 API 地址
+fn is_github_api_url(url: &str) -> bool {
+    reqwest::Url::parse(url)
+        .is_ok_and(|url| url.scheme() == "https" && url.host_str() == Some(GITHUB_API_HOST))
+}
+
+/// 从 GitHub API 获取核心包来源最新发布的标签名
+pub async fn fetch_latest_release_tag() -> N8nResult<String> {
+    let client = http::client()?;
+    let api_url = latest_release_api_url(&settings_lock().core_source);
+    let response = github_api_get(&client, &api_url).send().await?.error_for_status()?;
+    let json: Value = serde_json::from_str(&response.text().await?)?;
+
+    json["tag_name"]
+        .as_str()
+        .map(ToString::to_string)
+        .ok_or_else(|| N8nCoreError::Config("GitHub 响应中缺少 tag_name 字段".to_string()))
+}
+
 /// 从 GitHub API 获取最新发布的 SHA256 哈希值
 pub async fn fetch_latest_sha256(platform: &str) -> N8nResult<Option<String>> {
-    let client = reqwest::Client::new();
-    let file_name = format!("n8n-core-{}.zip", platform);
+    Ok(fetch_latest_digest(platform).await?.ok())
+}
+
+/// 从 GitHub API 获取最新发布的 SHA256 哈希值，无法校验时返回跳过原因
+pub async fn fetch_latest_digest(
+    platform: &str,
+) -> N8nResult<Result<String, VerificationSkipped>> {
+    let client = http::client()?;
+    let file_name = core_asset_name(platform);
+    let api_url = latest_release_api_url(&settings_lock().core_source);
 
     // 发送 API 请求
-    let response = client
-        .get(GITHUB_API_URL)
-        .header("User-Agent", GITHUB_USER_AGENT)
-        .header("Accept", GITHUB_ACCEPT_HEADER)
-        .send()
-        .await?;
+    let response = github_api_get(&client, &api_url).send().await?;
+
+    let skipped = |reason, detail: String| {
+        Err(VerificationSkipped {
+            asset: file_name.clone(),
+            reason,
+            detail,
+        })
+    };
 
     // 检查响应状态
-    if !response.status().is_success() {
-        println!(
-            "GitHub API 返回错误 {}，跳过 SHA256 验证",
-            response.status()
-        );
-        return Ok(None);
+    let status = response.status();
+    if !status.is_success() {
+        println!("GitHub API 返回错误 {}，跳过 SHA256 验证", status);
+        let reason = match status.as_u16() {
+            403 | 429 => VerificationSkipReason::RateLimited,
+            _ => VerificationSkipReason::ApiError,
+        };
+        return Ok(skipped(reason, status.to_string()));
     }
 
     // 解析响应
@@ -225,33 +620,80 @@ pub async fn fetch_latest_sha256(platform: &str) -> N8nResult<Option<String>> {
 
             // digest 格式: "sha256:xxxxxxxx..."
             match digest.strip_prefix("sha256:") {
-                Some(sha256) => return Ok(Some(sha256.to_string())),
+                Some(sha256) => return Ok(Ok(sha256.to_string())),
                 None => {
                     println!("无效的 digest 格式: {}，跳过 SHA256 验证", digest);
-                    return Ok(None);
+                    return Ok(skipped(
+                        VerificationSkipReason::BadDigestFormat,
+                        digest.to_string(),
+                    ));
                 }
             }
         }
     }
 
     println!("未找到 {} 的发布资源，跳过 SHA256 验证", file_name);
-    Ok(None)
+    Ok(skipped(VerificationSkipReason::MissingAsset, file_name.clone()))
+}
+
+/// 从 Node.js 资源包同目录下的 `SHASUMS256.txt` 获取其 SHA256，镜像未提供时返回跳过原因
+pub async fn fetch_node_sha256(archive_url: &str) -> Result<String, VerificationSkipped> {
+    let (base_url, file_name) = archive_url.rsplit_once('/').unwrap_or(("", archive_url));
+    let shasums_url = format!("{base_url}/{}", offline::SHASUMS_FILENAME);
+    let skipped = |reason, detail: String| VerificationSkipped {
+        asset: file_name.to_string(),
+        reason,
+        detail,
+    };
+
+    let response = http::client()
+        .map_err(|e| skipped(VerificationSkipReason::MissingChecksums, e.to_string()))?
+        .get(&shasums_url)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| skipped(VerificationSkipReason::MissingChecksums, e.to_string()))?;
+    let shasums = response
+        .text()
+        .await
+        .map_err(|e| skipped(VerificationSkipReason::MissingChecksums, e.to_string()))?;
+
+    offline::shasums_entry(&shasums, file_name)
+        .ok_or_else(|| skipped(VerificationSkipReason::MissingAsset, shasums_url))
 }
 
 /// 计算文件的 SHA256 哈希值
 pub fn calculate_file_sha256(file_path: &Path) -> N8nResult<String> {
+    calculate_file_sha256_with_progress(file_path, &|_, _| {})
+}
+
+/// 计算文件的 SHA256 哈希值，每读取约 1% 回调一次进度（开始与结束时各回调一次）
+pub fn calculate_file_sha256_with_progress(file_path: &Path, on_progress: HashProgress) -> N8nResult<String> {
     use std::io::Read;
 
     let mut file = fs::File::open(file_path)?;
+    let total = file.metadata()?.len();
+    let step = (total / 100).max(1);
     let mut hasher = Sha256::new();
     let mut buffer = [0; 8192];
+    let mut read: u64 = 0;
+    let mut reported: u64 = 0;
 
+    on_progress(0, total);
     loop {
         let bytes_read = file.read(&mut buffer)?;
         if bytes_read == 0 {
             break;
         }
         hasher.update(&buffer[..bytes_read]);
+        read += bytes_read as u64;
+        if read - reported >= step {
+            on_progress(read, total);
+            reported = read;
+        }
+    }
+    if reported != read {
+        on_progress(read, total);
     }
 
     Ok(format!("{:x}", hasher.finalize()))
@@ -0,0 +1,87 @@
+//! n8n 日志文件管理
+//!
+//! `piped` 模式下 n8n 的输出写入数据基础目录的 `logs/n8n.log`，超过 5MB 时轮转，
+//! 旧日志命名为 `n8n.log.<序号>`（见 `services::logging::RotatingLogFile`）。
+//! 这里提供日志占用空间统计与手动清理（删除轮转日志、清空当前日志）。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::constants::{N8N_LOG_DIRNAME, N8N_LOG_FILENAME};
+use super::error::N8nResult;
+use crate::services::manager;
+
+/// 当前日志文件路径
+pub fn active_log_path(base_dir: &Path) -> PathBuf {
+    base_dir.join(N8N_LOG_DIRNAME).join(N8N_LOG_FILENAME)
+}
+
+/// 列出轮转后的旧日志文件
+fn rotated_log_files(base_dir: &Path) -> Vec<PathBuf> {
+    let prefix = format!("{N8N_LOG_FILENAME}.");
+    fs::read_dir(base_dir.join(N8N_LOG_DIRNAME))
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name.starts_with(&prefix))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 日志文件占用的总字节数（当前日志 + 轮转日志）
+pub fn logs_size(base_dir: &Path) -> u64 {
+    std::iter::once(active_log_path(base_dir))
+        .chain(rotated_log_files(base_dir))
+        .filter_map(|path| fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// 删除所有轮转日志并清空当前日志，返回释放的字节数
+///
+/// 当前日志由 n8n 输出转发线程持续写入，截断时与写入线程共用同一把锁，不会产生竞争。
+pub fn clear_logs(base_dir: &Path) -> N8nResult<u64> {
+    let before = logs_size(base_dir);
+
+    for path in rotated_log_files(base_dir) {
+        fs::remove_file(&path)?;
+    }
+    manager::truncate_log_file(&active_log_path(base_dir))?;
+
+    let freed = before.saturating_sub(logs_size(base_dir));
+    println!("[n8n] 已清理日志，释放 {freed} 字节");
+    Ok(freed)
+}
+
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+
+    #[test]
+    fn test_clear_logs_removes_rotated_and_truncates_active() {
+        let base = temp_dir().join("n8n_logs_clear_test");
+        let _ = fs::remove_dir_all(&base);
+        let log_dir = base.join(N8N_LOG_DIRNAME);
+        fs::create_dir_all(&log_dir).expect("创建日志目录失败");
+        fs::write(active_log_path(&base), "current").expect("写入日志失败");
+        fs::write(log_dir.join(format!("{N8N_LOG_FILENAME}.1")), "old").expect("写入日志失败");
+        fs::write(log_dir.join("other.txt"), "keep").expect("写入文件失败");
+
+        assert_eq!(logs_size(&base), 10);
+        assert_eq!(clear_logs(&base).expect("清理日志失败"), 10);
+        assert_eq!(logs_size(&base), 0);
+        assert!(active_log_path(&base).exists());
+        assert!(log_dir.join("other.txt").exists());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+}
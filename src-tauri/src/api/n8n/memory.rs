@@ -0,0 +1,87 @@
+//! 启动前内存检查模块
+//!
+//! n8n 与 Node 需要一定的内存基线，在 4GB 等小内存机器上可能一启动就频繁换页甚至被系统结束。
+//! 可用物理内存低于阈值时按设置给出警告或拒绝启动，并为 Node 设置保守的堆上限。
+
+use serde::Serialize;
+use sysinfo::System;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use super::error::{N8nCoreError, N8nResult};
+use crate::api::settings::{settings_lock, LowMemoryPolicy, MemorySettings};
+use crate::i18n;
+
+/// 低内存警告事件
+pub const LOW_MEMORY_EVENT: &str = "low-memory-warning";
+
+/// 低内存时 Node 堆上限的取值范围（MB）
+const MIN_NODE_HEAP_MB: u64 = 256;
+const MAX_LOW_MEMORY_NODE_HEAP_MB: u64 = 1024;
+
+/// 系统内存状态（MB）
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MemoryStatus {
+    pub available_mb: u64,
+    pub total_mb: u64,
+}
+
+/// 读取系统内存状态，平台无法提供内存信息时返回 `None`
+pub fn memory_status() -> Option<MemoryStatus> {
+    let mut system = System::new();
+    system.refresh_memory();
+
+    let total = system.total_memory();
+    (total > 0).then(|| MemoryStatus {
+        available_mb: system.available_memory() / 1024 / 1024,
+        total_mb: total / 1024 / 1024,
+    })
+}
+
+/// 可用内存是否低于设置的阈值
+pub fn is_low_memory(status: &MemoryStatus, settings: &MemorySettings) -> bool {
+    status.available_mb < settings.min_available_mb
+}
+
+/// 低内存时为 Node 选择保守的堆上限（可用内存的一半，限制在 256MB ~ 1GB）
+pub fn conservative_heap_mb(status: &MemoryStatus) -> u64 {
+    (status.available_mb / 2).clamp(MIN_NODE_HEAP_MB, MAX_LOW_MEMORY_NODE_HEAP_MB)
+}
+
+/// 当前内存不足时建议的 Node 堆上限，内存充足时返回 `None`
+pub fn low_memory_heap_limit() -> Option<u64> {
+    let settings = settings_lock().memory.clone();
+    let status = memory_status()?;
+    is_low_memory(&status, &settings).then(|| conservative_heap_mb(&status))
+}
+
+/// 生成低内存提示
+pub fn low_memory_message(status: &MemoryStatus, settings: &MemorySettings) -> String {
+    format!(
+        "{} ({} MB < {} MB)",
+        i18n::t("memory.low"),
+        status.available_mb,
+        settings.min_available_mb
+    )
+}
+
+/// 启动前检查可用内存：按设置警告（发送事件后继续启动）或返回错误
+pub fn ensure_memory_for_launch<R: Runtime>(app: &AppHandle<R>) -> N8nResult<()> {
+    let settings = settings_lock().memory.clone();
+    let Some(status) = memory_status() else {
+        return Ok(());
+    };
+
+    if !is_low_memory(&status, &settings) {
+        return Ok(());
+    }
+
+    let message = low_memory_message(&status, &settings);
+    match settings.low_memory_policy {
+        LowMemoryPolicy::Warn => {
+            eprintln!("[n8n] ⚠️  {message}");
+            let _ = app.emit(LOW_MEMORY_EVENT, &message);
+            Ok(())
+        }
+        LowMemoryPolicy::Error => Err(N8nCoreError::ServiceUnavailable(message)),
+    }
+}
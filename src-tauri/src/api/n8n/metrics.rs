@@ -0,0 +1,98 @@
+//! n8n 进程资源监控模块
+//!
+//! 读取受管 n8n 进程的 CPU 与内存占用，供监控面板展示；
+//! 可选地在后台定时采样并通过 `n8n-metrics` 事件推送。
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use sysinfo::{Pid, System};
+use tauri::{AppHandle, Emitter, Runtime};
+
+use super::error::{N8nCoreError, N8nResult};
+use crate::i18n;
+use crate::services::manager::PROCESS_MANAGER;
+
+/// 监控事件名
+pub const METRICS_EVENT: &str = "n8n-metrics";
+
+/// 默认采样间隔
+const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 最小采样间隔（CPU 占用需两次刷新之间有足够间隔才有意义）
+const MIN_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 复用同一个 System 实例，CPU 占用基于相邻两次刷新的差值计算
+static SYSTEM: LazyLock<Mutex<System>> = LazyLock::new(|| Mutex::new(System::new()));
+
+/// 后台采样器是否在运行
+static SAMPLER_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// 进程资源占用
+#[derive(Clone, Serialize, Debug)]
+pub struct ProcessMetrics {
+    /// 进程 ID
+    pub pid: u32,
+    /// CPU 占用百分比（多核时可能超过 100）
+    pub cpu_percent: f32,
+    /// 常驻内存（字节）
+    pub memory_bytes: u64,
+}
+
+/// 读取当前 n8n 进程的资源占用
+///
+/// 首次采样时 CPU 占用为 0，之后的调用反映与上次采样之间的平均值。
+pub fn process_metrics() -> N8nResult<ProcessMetrics> {
+    let pid = PROCESS_MANAGER
+        .lock()
+        .map_err(|_| N8nCoreError::Process(i18n::t("n8n.state.process_manager_poisoned")))?
+        .pid()
+        .ok_or_else(|| N8nCoreError::ServiceUnavailable(i18n::t("n8n.not_running")))?;
+
+    let mut system = SYSTEM
+        .lock()
+        .map_err(|_| N8nCoreError::Process(i18n::t("n8n.metrics.unavailable")))?;
+
+    let sys_pid = Pid::from_u32(pid);
+    if !system.refresh_process(sys_pid) {
+        return Err(N8nCoreError::ServiceUnavailable(i18n::t("n8n.metrics.unavailable")));
+    }
+
+    let process = system
+        .process(sys_pid)
+        .ok_or_else(|| N8nCoreError::ServiceUnavailable(i18n::t("n8n.metrics.unavailable")))?;
+
+    Ok(ProcessMetrics {
+        pid,
+        cpu_percent: process.cpu_usage(),
+        memory_bytes: process.memory(),
+    })
+}
+
+/// 启动后台采样器（已在运行时直接返回）
+pub fn start_metrics_sampler<R: Runtime>(app: AppHandle<R>, interval_ms: Option<u64>) {
+    if SAMPLER_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let interval = interval_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_SAMPLE_INTERVAL)
+        .max(MIN_SAMPLE_INTERVAL);
+
+    tauri::async_runtime::spawn(async move {
+        while SAMPLER_RUNNING.load(Ordering::SeqCst) {
+            // n8n 未运行时跳过本次采样，继续等待
+            if let Ok(metrics) = process_metrics() {
+                let _ = app.emit(METRICS_EVENT, metrics);
+            }
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+/// 停止后台采样器（监控面板关闭时调用）
+pub fn stop_metrics_sampler() {
+    SAMPLER_RUNNING.store(false, Ordering::SeqCst);
+}
@@ -4,41 +4,174 @@
 //! 重构版本：解决原始代码中的架构问题、错误处理混乱、并发安全风险等。
 
 // 导出子模块
+pub mod boot_trace;
+pub mod cli;
+pub mod connectivity;
 pub mod constants;
+pub mod data_dir;
+pub mod database;
+pub mod db_status;
+pub mod db_vacuum;
+pub mod diagnostics;
+pub mod encryption_key;
+pub mod entrypoint;
 pub mod error;
+pub mod execution;
+pub mod install_state;
 pub mod installer;
+pub mod logs;
+pub mod memory;
+pub mod metrics;
+pub mod offline;
+pub mod n8n_tunnel;
+pub mod paths;
+pub mod permissions;
+pub mod platform;
+pub mod preflight;
+pub mod setup_phase;
+pub mod startup;
 pub mod state;
+pub mod uninstall;
+pub mod verify;
+pub mod versions;
 
 // 重新导出常用类型和函数
+pub use boot_trace::BootTrace;
+pub use cli::WorkflowExecution;
+pub use connectivity::{ConnectivityCheck, MirrorBenchmark};
 pub use constants::*;
+pub use data_dir::DataDirMigration;
+pub use db_status::DbStatus;
+pub use db_vacuum::VacuumReport;
+pub use uninstall::UninstallSummary;
+pub use entrypoint::{has_n8n_entrypoint, resolve_n8n_entrypoint};
 pub use error::{N8nCoreError, N8nResult};
-pub use installer::{calculate_file_sha256, fetch_latest_sha256, verify_file_hash, N8nInstaller};
-pub use state::{construct_n8n_envs, get_nodes_unlocked, set_nodes_unlocked, N8nHealthChecker};
+pub use install_state::{InstallStage, InstallState, InstallVerification};
+pub use installer::{
+    calculate_file_sha256, fetch_latest_digest, fetch_latest_sha256, verify_file_hash,
+    N8nInstaller, VerificationSkipReason, VerificationSkipped,
+};
+pub use paths::{
+    base_dir, config_dir, ensure_app_data_dir, env_data_dir, is_portable_mode, n8n_data_dir,
+};
+pub use metrics::ProcessMetrics;
+pub use permissions::PermissionFixReport;
+pub use platform::{platform_info, PlatformInfo};
+pub use preflight::{PreflightCheck, PreflightReport};
+pub use setup_phase::SetupPhase;
+pub use startup::StartupFailure;
+pub use verify::{VerifyCheck, VerifyComponent, VerifyReport};
+pub use versions::InstalledVersion;
+pub use state::{
+    construct_n8n_envs, editor_url, get_nodes_unlocked, redact_secret_envs,
+    set_log_level, set_nodes_unlocked, N8nHealthChecker,
+};
 
+use crate::api::settings::{settings_lock, DatabaseType, ProcessStdio, StartupSettings};
 use crate::i18n;
-use crate::services::{downloader, manager};
+use crate::services::{download_control, downloader, manager, privileges};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use tauri::{AppHandle, Manager, Runtime, Window};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewUrl, WebviewWindowBuilder, Window};
+
+/// 将进程管理器的状态变化以 `n8n-state` 事件广播给前端（应用启动时调用一次）
+pub fn init_state_events<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    manager::PROCESS_MANAGER
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .set_state_listener(Arc::new(move |event: &manager::N8nStateEvent| {
+            let _ = app.emit(N8N_STATE_EVENT, event);
+        }));
+}
+
+/// 按设置在应用启动时自动启动 n8n
+///
+/// 安装不完整时跳过并通知前端进入引导流程；n8n 已在运行时同样跳过，避免重复启动。
+/// 启动过程与手动启动相同，状态变化通过 `n8n-state` 事件通知。
+pub fn auto_launch<R: Runtime>(app: &AppHandle<R>) {
+    if !settings_lock().auto_launch {
+        return;
+    }
+
+    let complete = is_installed(app.clone())
+        && base_dir(app).is_ok_and(|dir| resolve_installed_binaries(&dir).is_ok());
+    if !complete {
+        println!("[n8n] 安装不完整，跳过自动启动");
+        let _ = app.emit(AUTO_LAUNCH_SKIPPED_EVENT, "not_installed");
+        return;
+    }
+
+    let running = manager::PROCESS_MANAGER
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .has_child();
+    if running {
+        let _ = app.emit(AUTO_LAUNCH_SKIPPED_EVENT, "already_running");
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        println!("[n8n] 自动启动 n8n");
+        if let Err(error) = launch_n8n_and_wait(app.clone()).await {
+            eprintln!("[n8n] 自动启动失败: {error}");
+            let _ = app.emit(AUTO_LAUNCH_FAILED_EVENT, error.to_string());
+        }
+    });
+}
+
+/// 检测到以 root / 管理员身份运行时提醒用户（只提醒，不阻止启动）
+pub fn warn_elevated_privileges<R: Runtime>(app: &AppHandle<R>) {
+    if privileges::is_elevated() {
+        eprintln!("[n8n] 警告：应用正以 root / 管理员身份运行");
+        let _ = app.emit(ELEVATED_PRIVILEGES_WARNING_EVENT, i18n::t("privileges.elevated_warning"));
+    }
+}
 
 /// 检查 n8n 是否已经安装在 AppData 目录
+///
+/// 存在未完成的安装状态（如安装中途重启）时视为未安装。
 pub fn is_installed<R: Runtime>(app: AppHandle<R>) -> bool {
-    app.path()
-        .app_data_dir()
-        .map(|p| {
-            let bin_path = p.join("n8n-core/node_modules/n8n/bin/n8n");
-            bin_path.exists()
-        })
+    base_dir(&app)
+        .map(|p| has_n8n_entrypoint(&p) && !InstallState::is_pending(&p))
         .unwrap_or(false)
 }
 
+/// 获取未完成的安装状态，前端据此决定是否继续安装
+pub fn get_pending_install<R: Runtime>(app: AppHandle<R>) -> N8nResult<Option<InstallState>> {
+    let app_path = base_dir(&app)?;
+    Ok(InstallState::load_pending(&app_path))
+}
+
+/// 获取当前安装的资源包校验结果（旧版本安装或尚未完成安装时为 None）
+pub fn get_verification_status<R: Runtime>(app: AppHandle<R>) -> N8nResult<Option<InstallVerification>> {
+    let app_path = base_dir(&app)?;
+    Ok(InstallState::load(&app_path)
+        .filter(|state| state.stage == InstallStage::Completed)
+        .and_then(|state| state.verification))
+}
+
 /// 全自动设置 Node 运行环境 (Runtime)
-pub async fn setup_runtime<R: Runtime>(window: Window<R>) -> N8nResult<()> {
-    let app_handle = window.app_handle();
-    let runtime_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| N8nCoreError::Path(e.to_string()))?
-        .join("runtime");
+///
+/// 在线下载时按镜像发布的 `SHASUMS256.txt` 校验资源包后再解压，镜像未提供摘要时跳过校验。
+/// 指定 `source` 或在 `offline/` 中预置了对应的 Node 资源包时，跳过网络下载直接校验并解压。
+/// 各阶段通过 `setup-phase` 事件通知前端，以 `done` 或 `failed` 结束。
+pub async fn setup_runtime<R: Runtime>(window: Window<R>, source: Option<PathBuf>) -> N8nResult<()> {
+    let _operation = begin_operation("runtime")?;
+    let result = install_runtime(&window, source).await;
+    setup_phase::finish(&window, &result);
+    result
+}
+
+/// 检查并按需安装 Node 运行时（调用方已占用操作锁）
+async fn install_runtime<R: Runtime>(window: &Window<R>, source: Option<PathBuf>) -> N8nResult<()> {
+    setup_phase::emit(window, SetupPhase::CheckingRuntime);
+    let base_dir = ensure_app_data_dir(window.app_handle())?;
+    let runtime_dir = base_dir.join("runtime");
 
     let node_path = manager::get_node_binary_path(runtime_dir.clone());
     let runtime_is_compatible = node_path.exists() && manager::is_runtime_compatible(&runtime_dir);
@@ -53,24 +186,39 @@ pub async fn setup_runtime<R: Runtime>(window: Window<R>) -> N8nResult<()> {
         let _ = fs::remove_dir_all(&runtime_dir);
     }
 
+    paths::ensure_disk_space(&base_dir, RUNTIME_INSTALL_REQUIRED_BYTES)?;
     let urls = manager::get_node_download_urls().map_err(N8nCoreError::Installation)?;
+
+    let offline_archive = source.or_else(|| {
+        let file_name = urls.first()?.rsplit('/').next()?;
+        offline::find_offline_archive(&base_dir, file_name)
+    });
+    if let Some(archive_path) = offline_archive {
+        return setup_runtime_from_archive(window, archive_path, runtime_dir);
+    }
+
     let mut last_error: Option<String> = None;
 
     for url in urls {
         println!("[n8n][setup_runtime] 尝试下载运行时: {url}");
-        match downloader::download_file(window.clone(), url.clone(), runtime_dir.clone(), "runtime".to_string()).await {
+        match download_runtime(window, &url, &base_dir, &runtime_dir).await {
             Ok(()) => {
                 let rechecked_path = manager::get_node_binary_path(runtime_dir.clone());
                 if rechecked_path.exists() && manager::is_runtime_compatible(&runtime_dir) {
                     println!("[n8n][setup_runtime] 运行时下载并验证成功: {url}");
+                    let _ = window.emit(RUNTIME_MIRROR_EVENT, &url);
                     return Ok(());
                 }
 
                 eprintln!("[n8n][setup_runtime] 下载完成但运行时仍不兼容: {url}");
             }
+            Err(err) if download_control::is_canceled("runtime") => {
+                println!("[n8n][setup_runtime] 运行时安装已取消");
+                return Err(err);
+            }
             Err(err) => {
                 eprintln!("[n8n][setup_runtime] 运行时下载失败 ({url}): {err}");
-                last_error = Some(err);
+                last_error = Some(err.to_string());
             }
         }
     }
@@ -80,60 +228,752 @@ pub async fn setup_runtime<R: Runtime>(window: Window<R>) -> N8nResult<()> {
     })))
 }
 
+/// 从 `url` 下载 Node 运行时资源包，校验 SHA256 后解压到 `runtime_dir`
+///
+/// 资源包先下载到数据基础目录（中断后可续传），校验不通过时删除，不会解压。
+async fn download_runtime<R: Runtime>(
+    window: &Window<R>,
+    url: &str,
+    base_dir: &Path,
+    runtime_dir: &Path,
+) -> N8nResult<()> {
+    let expected_sha256 = match installer::fetch_node_sha256(url).await {
+        Ok(sha256) => Some(sha256),
+        Err(skipped) => {
+            tracing::warn!(
+                asset = %skipped.asset,
+                reason = ?skipped.reason,
+                detail = %skipped.detail,
+                "无法获取运行时的 SHA256，跳过校验"
+            );
+            let _ = window.emit(VERIFICATION_SKIPPED_EVENT, &skipped);
+            None
+        }
+    };
+
+    let file_name = url.rsplit('/').next().unwrap_or(url);
+    let archive_path = base_dir.join(file_name);
+    setup_phase::emit(window, SetupPhase::DownloadingRuntime);
+    downloader::download_file_in_range(
+        window.clone(),
+        url.to_string(),
+        archive_path.clone(),
+        "runtime".to_string(),
+        (0.0, downloader::DOWNLOAD_PHASE_WEIGHT),
+    )
+    .await
+    .map_err(N8nCoreError::Installation)?;
+
+    let result = match expected_sha256 {
+        Some(sha256) => {
+            setup_phase::emit(window, SetupPhase::VerifyingHash);
+            verify_file_hash(&archive_path, &sha256)
+        }
+        None => Ok(()),
+    }
+    .and_then(|()| {
+        setup_phase::emit(window, SetupPhase::ExtractingRuntime);
+        downloader::install_from_local_archive(
+            window.clone(),
+            archive_path.clone(),
+            runtime_dir.to_path_buf(),
+            "runtime".to_string(),
+        )
+        .map_err(N8nCoreError::Installation)
+    });
+    let _ = fs::remove_file(&archive_path);
+    result
+}
+
+/// 占用安装操作锁，防止重复触发的下载与解压相互覆盖
+fn begin_operation(operation_id: &str) -> N8nResult<download_control::OperationGuard> {
+    download_control::begin_operation(operation_id)
+        .ok_or_else(|| N8nCoreError::AlreadyInProgress(operation_id.to_string()))
+}
+
+/// 从本地资源包安装 Node 运行时（离线部署）
+fn setup_runtime_from_archive<R: Runtime>(
+    window: &Window<R>,
+    archive_path: PathBuf,
+    runtime_dir: PathBuf,
+) -> N8nResult<()> {
+    println!("[n8n][setup_runtime] 使用离线资源包: {}", archive_path.display());
+    setup_phase::emit(window, SetupPhase::VerifyingHash);
+    offline::verify_offline_archive(&archive_path, None)?;
+
+    setup_phase::emit(window, SetupPhase::ExtractingRuntime);
+    downloader::install_from_local_archive(
+        window.clone(),
+        archive_path,
+        runtime_dir.clone(),
+        "runtime".to_string(),
+    )
+    .map_err(N8nCoreError::Installation)?;
+
+    if manager::is_runtime_compatible(&runtime_dir) {
+        Ok(())
+    } else {
+        Err(N8nCoreError::Installation(i18n::t("runtime.offline_incompatible")))
+    }
+}
+
 /// 安装 n8n 核心包 (下载 + 解压，带 SHA256 验证)
-pub async fn setup_n8n<R: Runtime>(window: Window<R>) -> N8nResult<()> {
+///
+/// 指定 `source` 或在 `offline/` 中预置了核心包时跳过下载，直接校验并解压。
+/// 各阶段通过 `setup-phase` 事件通知前端，以 `done` 或 `failed` 结束。
+pub async fn setup_n8n<R: Runtime>(window: Window<R>, source: Option<PathBuf>) -> N8nResult<()> {
+    let _operation = begin_operation(N8N_CORE_OPERATION)?;
+    setup_phase::emit(&window, SetupPhase::CheckingCore);
+    let result = install_core(&window, source).await;
+    setup_phase::finish(&window, &result);
+    result
+}
+
+/// 安装 n8n 核心包（调用方已占用操作锁）
+async fn install_core<R: Runtime>(window: &Window<R>, source: Option<PathBuf>) -> N8nResult<()> {
+    let base_dir = ensure_app_data_dir(window.app_handle())?;
+    paths::ensure_disk_space(&base_dir, CORE_INSTALL_REQUIRED_BYTES)?;
     let installer = N8nInstaller::new(&window.app_handle())?;
-    installer.install(window).await
+    installer.install(window.clone(), source).await
 }
 
 /// 启动本地 n8n 进程
-pub fn launch_n8n<R: Runtime>(app: AppHandle<R>) -> N8nResult<()> {
-    let app_path = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| N8nCoreError::Path(e.to_string()))?;
+///
+/// `tunnel` 指定本次是否以隧道模式（`--tunnel`）启动，未指定时取设置中的 `n8n_tunnel_enabled`；
+/// 隧道地址就绪后通过 `n8n-tunnel-url` 事件通知前端。
+pub fn launch_n8n<R: Runtime>(app: AppHandle<R>, tunnel: Option<bool>) -> N8nResult<()> {
+    launch_n8n_with(&app, None, false, tunnel)
+}
 
-    let runtime_dir = app_path.join("runtime");
-    let node_path = manager::get_node_binary_path(runtime_dir);
+/// 以安全模式启动 n8n：不加载社区节点与自定义扩展，便于进入编辑器移除导致崩溃的节点
+pub fn launch_n8n_safe<R: Runtime>(app: AppHandle<R>) -> N8nResult<()> {
+    launch_n8n_with(&app, None, true, None)
+}
 
-    if !node_path.exists() {
+/// 一次启动使用的运行时、入口与数据目录
+#[derive(Debug)]
+struct LaunchTarget {
+    node_path: PathBuf,
+    n8n_bin: PathBuf,
+    data_dir: PathBuf,
+    safe_mode: bool,
+    /// 是否以隧道模式启动
+    tunnel: bool,
+}
+
+/// 一次启动的模式（重启时沿用）
+#[derive(Debug, Clone, Copy)]
+struct LaunchMode {
+    safe_mode: bool,
+    tunnel: bool,
+}
+
+/// 本次会话最近一次成功启动 n8n 的模式（供 `relaunch_n8n` 复用）
+static LAST_LAUNCH: LazyLock<Mutex<Option<LaunchMode>>> = LazyLock::new(|| Mutex::new(None));
+
+/// 重启本次会话中启动过的 n8n
+///
+/// 先正常停止旧进程并等待其退出（见 `manager::stop_child`），再沿用上次的安全模式与隧道模式重新启动；
+/// 运行时、入口与数据目录按当前的启用版本与设置重新定位，环境变量按当前设置重新生成。
+/// n8n 已自行退出时直接启动；本次会话从未启动过 n8n 时返回错误。
+pub fn relaunch_n8n<R: Runtime>(app: AppHandle<R>) -> N8nResult<()> {
+    let mode = LAST_LAUNCH
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .ok_or_else(|| N8nCoreError::Process(i18n::t("n8n.restart.not_launched")))?;
+
+    shutdown_n8n()?;
+    tracing::info!("正在重启 n8n");
+    launch_n8n_with(&app, None, mode.safe_mode, Some(mode.tunnel))
+}
+
+/// 启动 n8n
+///
+/// `capture` 额外接收 n8n 的每一行输出（设置后无论输出模式如何都会通过管道读取）；
+/// `safe_mode` 时追加安全模式环境变量。每次启动都会发送 `safe-mode` 事件告知前端当前模式。
+/// `tunnel` 未指定时按设置决定是否使用隧道模式。
+fn launch_n8n_with<R: Runtime>(
+    app: &AppHandle<R>,
+    capture: Option<manager::LogLineHandler>,
+    safe_mode: bool,
+    tunnel: Option<bool>,
+) -> N8nResult<()> {
+    let app_path = ensure_app_data_dir(app)?;
+    let (node_path, n8n_bin) = resolve_installed_binaries(&app_path)?;
+    let target = LaunchTarget {
+        node_path,
+        n8n_bin,
+        data_dir: n8n_data_dir(&app_path),
+        safe_mode,
+        tunnel: tunnel.unwrap_or_else(n8n_tunnel::is_enabled),
+    };
+    launch_target(app, target, capture)
+}
+
+/// 确认 n8n 端口未被占用，被占用时改用 5700 以内的下一个可用端口并写入 `N8N_PORT`
+fn select_port(envs: &mut HashMap<String, String>) -> N8nResult<u16> {
+    let preferred = envs
+        .get("N8N_PORT")
+        .and_then(|value| value.parse::<u16>().ok())
+        .unwrap_or(manager::N8N_SERVICE_PORT);
+    let port = manager::find_available_port(preferred).ok_or_else(|| {
+        N8nCoreError::Process(format!("{}: {preferred}", i18n::t("n8n.port_unavailable")))
+    })?;
+
+    if port != preferred {
+        tracing::warn!(preferred, port, "n8n 端口已被占用，改用其他端口");
+        envs.insert("N8N_PORT".to_string(), port.to_string());
+    }
+    Ok(port)
+}
+
+/// 按指定的运行时、入口与数据目录启动 n8n，成功后记录为最近一次启动，并监视其是否在启动后立即退出
+fn launch_target<R: Runtime>(
+    app: &AppHandle<R>,
+    target: LaunchTarget,
+    capture: Option<manager::LogLineHandler>,
+) -> N8nResult<()> {
+    let app = app.clone();
+    if !target.data_dir.exists() {
+        fs::create_dir_all(&target.data_dir)?;
+    }
+
+    // 使用 PostgreSQL 时先校验配置并确认服务器可达，避免 n8n 启动后才失败
+    let (database, execution_settings) = {
+        let settings = settings_lock();
+        (settings.database.clone(), settings.execution.clone())
+    };
+    database::ensure_database_ready(&database)?;
+    // 队列模式需要 PostgreSQL 与可达的 Redis
+    execution::ensure_execution_ready(&execution_settings, &database)?;
+    memory::ensure_memory_for_launch(&app)?;
+
+    // 创建环境变量容器
+    let mut additional_envs = construct_n8n_envs();
+    if target.safe_mode {
+        tracing::info!("以安全模式启动 n8n，不加载社区节点与自定义扩展");
+        additional_envs.extend(
+            SAFE_MODE_ENVS
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string())),
+        );
+    }
+    // 先停止旧实例再选择端口，否则旧实例占用的端口会被当作已被占用而改用其他端口
+    let pid_file = base_dir(&app)?.join(manager::N8N_PID_FILENAME);
+    manager::stop_previous_instances(Some(&pid_file)).map_err(N8nCoreError::Process)?;
+    let port = select_port(&mut additional_envs)?;
+    let _ = app.emit(N8N_PORT_SELECTED_EVENT, port);
+
+    start_n8n_process_with_capture(
+        &app,
+        target.node_path,
+        target.n8n_bin,
+        target.data_dir,
+        additional_envs,
+        capture,
+        target.tunnel,
+    )
+    .map_err(N8nCoreError::Process)?;
+    let pid = manager::PROCESS_MANAGER
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .pid();
+    if let Some(pid) = pid {
+        startup::watch_startup_crash(&app, pid);
+    }
+    let _ = app.emit(SAFE_MODE_EVENT, target.safe_mode);
+    *LAST_LAUNCH.lock().unwrap_or_else(|p| p.into_inner()) = Some(LaunchMode {
+        safe_mode: target.safe_mode,
+        tunnel: target.tunnel,
+    });
+    Ok(())
+}
+
+/// 定位已安装的 Node.js 可执行文件与 n8n 入口文件
+fn resolve_installed_binaries(app_path: &Path) -> N8nResult<(PathBuf, PathBuf)> {
+    let runtime_dir = app_path.join("runtime");
+    if !manager::get_node_binary_path(runtime_dir.clone()).exists() {
         return Err(N8nCoreError::Installation(i18n::t("node.not_found")));
     }
+    let node_path = manager::resolve_node_binary(&runtime_dir).map_err(N8nCoreError::Installation)?;
 
-    let n8n_bin = app_path.join("n8n-core/node_modules/n8n/bin/n8n");
-    if !n8n_bin.exists() {
+    if !entrypoint::n8n_package_dir(app_path).exists() {
         return Err(N8nCoreError::Installation(i18n::t("n8n.core_not_found")));
     }
+    let n8n_bin = resolve_n8n_entrypoint(app_path)?;
+
+    Ok((node_path, n8n_bin))
+}
+
+/// 启动 n8n 并等待健康检查通过
+///
+/// 轮询间隔与超时取自启动设置，超时返回 `STARTUP_TIMEOUT` 并附带最后的错误信息。
+pub async fn launch_n8n_and_wait<R: Runtime>(app: AppHandle<R>) -> N8nResult<String> {
+    launch_n8n(app, None)?;
+    let settings = settings_lock().startup.clone();
+    startup::wait_until_ready(&settings).await
+}
+
+/// 以诊断模式启动 n8n：在 `window_secs` 秒内（或直到就绪）收集全部输出并返回
+///
+/// 进程同样注册到进程管理器，诊断结束后保持运行，可正常停止。
+pub async fn launch_n8n_diagnostic<R: Runtime>(
+    app: AppHandle<R>,
+    window_secs: Option<u64>,
+) -> N8nResult<BootTrace> {
+    let collector = boot_trace::BootTraceCollector::new();
+    launch_n8n_with(&app, Some(collector.handler()), false, None)?;
+
+    let settings = StartupSettings {
+        timeout_secs: window_secs.unwrap_or(boot_trace::DEFAULT_WINDOW_SECS).max(1),
+        max_attempts: 0,
+        ..settings_lock().startup.clone()
+    };
+    let result = startup::wait_until_ready(&settings).await;
+    Ok(collector.finish(result).await)
+}
 
-    let data_dir = app_path.join("n8n-data");
-    if !data_dir.exists() {
-        fs::create_dir_all(&data_dir)?;
+/// 获取 n8n 二进制数据目录（filesystem 模式下附件等文件的存放位置，打开数据目录与备份时需包含）
+pub fn get_binary_data_dir<R: Runtime>(app: AppHandle<R>) -> N8nResult<PathBuf> {
+    Ok(n8n_data_dir(&base_dir(&app)?).join(BINARY_DATA_RELATIVE_PATH))
+}
+
+/// 获取 `launch_n8n` 实际传给 n8n 的环境变量（敏感值已脱敏）
+///
+/// 只读查询：只查找已有的加密密钥，还没有密钥时显示占位说明，密钥由启动流程生成。
+pub fn get_effective_env<R: Runtime>(app: AppHandle<R>) -> N8nResult<BTreeMap<String, String>> {
+    let app_path = base_dir(&app)?;
+    let data_dir = n8n_data_dir(&app_path);
+    let mut additional_envs = construct_n8n_envs();
+    let key_pending = !additional_envs.contains_key(encryption_key::ENCRYPTION_KEY_ENV)
+        && match encryption_key::existing_encryption_key(&app_path, &data_dir) {
+            Some(key) => {
+                additional_envs.insert(encryption_key::ENCRYPTION_KEY_ENV.to_string(), key);
+                false
+            }
+            None => true,
+        };
+    let mut envs = manager::n8n_process_envs(&data_dir, &additional_envs)
+        .map_err(N8nCoreError::Config)?;
+
+    redact_secret_envs(&mut envs);
+    if key_pending {
+        envs.insert(
+            encryption_key::ENCRYPTION_KEY_ENV.to_string(),
+            i18n::t("n8n.encryption_key_pending"),
+        );
     }
+    Ok(envs)
+}
 
-    // 创建环境变量容器
-    let additional_envs = construct_n8n_envs();
+/// 按当前设置启动 n8n 进程
+///
+/// 启用隧道模式时追加 `--tunnel` 参数、提醒用户实例将暴露到公网，并从日志中扫描隧道地址。
+pub fn start_n8n_process<R: Runtime>(
+    app: &AppHandle<R>,
+    node_path: PathBuf,
+    n8n_bin: PathBuf,
+    data_dir: PathBuf,
+    additional_envs: HashMap<String, String>,
+) -> Result<(), String> {
+    start_n8n_process_with_capture(
+        app,
+        node_path,
+        n8n_bin,
+        data_dir,
+        additional_envs,
+        None,
+        n8n_tunnel::is_enabled(),
+    )
+}
+
+/// 按当前设置启动 n8n 进程，`capture` 额外接收每一行输出，`tunnel_enabled` 决定是否使用隧道模式
+fn start_n8n_process_with_capture<R: Runtime>(
+    app: &AppHandle<R>,
+    node_path: PathBuf,
+    n8n_bin: PathBuf,
+    data_dir: PathBuf,
+    mut additional_envs: HashMap<String, String>,
+    capture: Option<manager::LogLineHandler>,
+    tunnel_enabled: bool,
+) -> Result<(), String> {
+    startup::reset_startup_state();
+    let app_path = base_dir(app).map_err(|e| e.to_string())?;
+    encryption_key::inject_encryption_key(&app_path, &data_dir, &mut additional_envs)
+        .map_err(|e| e.to_string())?;
+    if tunnel_enabled {
+        n8n_tunnel::warn_exposure(app);
+    }
 
-    manager::start_node(node_path, n8n_bin, data_dir, additional_envs)
-        .map_err(N8nCoreError::Process)
+    let (stdio, worker_count, custom_args) = {
+        let settings = settings_lock();
+        (
+            settings.logging.stdio,
+            execution::worker_count(&settings.execution),
+            settings.extra_n8n_args.clone(),
+        )
+    };
+    // 内置参数在前、自定义参数在后（重复选项以后者为准）
+    let builtin_args = n8n_tunnel::start_args(tunnel_enabled);
+    let custom_args = custom_args.into_iter().filter(|arg| !builtin_args.contains(arg));
+    let extra_args: Vec<String> = builtin_args.iter().cloned().chain(custom_args).collect();
+    let mut handlers: Vec<manager::LogLineHandler> = Vec::new();
+    if stdio == ProcessStdio::Piped {
+        let app = app.clone();
+        handlers.push(Arc::new(move |stream, line: &str| {
+            let _ = app.emit(
+                N8N_LOG_EVENT,
+                N8nLogLine {
+                    stream,
+                    line: line.to_string(),
+                },
+            );
+        }));
+    }
+    if tunnel_enabled {
+        handlers.push(n8n_tunnel::tunnel_url_handler(app));
+    }
+    // 捕获启动错误行与就绪提示（用户选择丢弃输出时不扫描，诊断模式除外）
+    if stdio != ProcessStdio::Null || capture.is_some() {
+        handlers.push(startup::startup_error_handler());
+        handlers.push(startup::ready_banner_handler(app));
+    }
+    handlers.extend(capture);
+    let log_handler: Option<manager::LogLineHandler> = if handlers.is_empty() {
+        None
+    } else {
+        Some(Arc::new(move |stream, line: &str| {
+            handlers.iter().for_each(|handler| handler(stream, line))
+        }))
+    };
+
+    let log_file = Some(logs::active_log_path(&app_path));
+
+    manager::start_node_with_config(manager::N8nStartConfig {
+        node_path,
+        n8n_binary: n8n_bin,
+        user_data_dir: data_dir,
+        additional_envs,
+        extra_args,
+        log_handler,
+        stdio,
+        log_file,
+        worker_count,
+        pid_file: Some(app_path.join(manager::N8N_PID_FILENAME)),
+    })
 }
 
-/// 代理健康检查
-pub async fn proxy_health_check() -> N8nResult<String> {
-    N8nHealthChecker::check().await
+/// 重新修复运行时与 n8n 可执行文件的权限
+pub fn fix_permissions<R: Runtime>(app: AppHandle<R>) -> N8nResult<PermissionFixReport> {
+    let app_path = base_dir(&app)?;
+
+    Ok(permissions::fix_install_permissions(&app_path))
 }
 
-/// 关闭 n8n 进程
-pub fn shutdown_n8n() -> N8nResult<()> {
-    use crate::services::manager::PROCESS_MANAGER;
+/// 只读校验现有安装的完整性（不做任何修改）
+pub async fn verify_installation<R: Runtime>(app: AppHandle<R>) -> N8nResult<VerifyReport> {
+    verify::verify_installation(&app).await
+}
+
+/// 查询数据库迁移状态（升级后首次启动较慢或迁移失败时用于提示用户）
+pub fn db_status<R: Runtime>(app: AppHandle<R>) -> N8nResult<DbStatus> {
+    db_status::db_status(&app)
+}
+
+/// 压缩 SQLite 数据库
+///
+/// n8n 正在运行时先停止（避免数据库被锁定），压缩完成后重新启动。
+pub fn vacuum_database<R: Runtime>(app: AppHandle<R>) -> N8nResult<VacuumReport> {
+    if settings_lock().database.db_type == DatabaseType::Postgres {
+        return Err(N8nCoreError::Config(i18n::t("db.vacuum.postgres_unsupported")));
+    }
+
+    let db_path = n8n_data_dir(&base_dir(&app)?).join(db_status::SQLITE_DB_RELATIVE_PATH);
+    let was_running = shutdown_n8n()?;
+    let mut report = db_vacuum::vacuum_sqlite(&db_path);
+
+    if was_running {
+        match relaunch_n8n(app) {
+            Ok(()) => {
+                if let Ok(report) = report.as_mut() {
+                    report.restarted = true;
+                }
+            }
+            Err(error) => tracing::error!(%error, "压缩数据库后重新启动 n8n 失败"),
+        }
+    }
+    report
+}
+
+/// 将 n8n 数据目录迁移到 `new_path`（n8n 运行中时先停止，完成后重新启动）
+pub fn migrate_data_dir<R: Runtime>(app: AppHandle<R>, new_path: PathBuf) -> N8nResult<DataDirMigration> {
+    data_dir::migrate_data_dir(&app, &new_path)
+}
+
+/// 将 n8n 数据目录切换为 `path`（不移动现有数据，下次启动生效；为空时恢复默认位置）
+pub fn set_data_dir<R: Runtime>(app: AppHandle<R>, path: String) -> N8nResult<PathBuf> {
+    data_dir::set_data_dir(&app, &path)
+}
+
+/// 获取生效的 n8n 数据目录（`N8N_USER_FOLDER`）
+pub fn get_data_dir<R: Runtime>(app: AppHandle<R>) -> N8nResult<PathBuf> {
+    Ok(n8n_data_dir(&base_dir(&app)?))
+}
+
+/// 导出全部凭据（需先停止 n8n；`decrypted` 时导出明文）
+pub fn export_credentials<R: Runtime>(app: AppHandle<R>, dest: PathBuf, decrypted: bool) -> N8nResult<String> {
+    cli::export_credentials(&app, &dest, decrypted)
+}
+
+/// 导入凭据（需先停止 n8n）
+pub fn import_credentials<R: Runtime>(app: AppHandle<R>, src: PathBuf) -> N8nResult<String> {
+    cli::import_credentials(&app, &src)
+}
+
+/// 在后台执行一次指定 ID 的工作流
+pub fn execute_workflow<R: Runtime>(app: AppHandle<R>, id: String) -> N8nResult<WorkflowExecution> {
+    cli::execute_workflow(&app, &id)
+}
+
+/// 导出诊断包（日志、版本、平台、脱敏设置、预检结果与最近的退出信息）
+pub fn export_diagnostics<R: Runtime>(app: AppHandle<R>, dest: PathBuf) -> N8nResult<()> {
+    diagnostics::export_diagnostics(&app, &dest)
+}
+
+/// 当前 n8n 日志文件路径（`logs/n8n.log`，轮转后的旧日志位于同一目录）
+pub fn get_log_path<R: Runtime>(app: AppHandle<R>) -> N8nResult<PathBuf> {
+    Ok(logs::active_log_path(&base_dir(&app)?))
+}
+
+/// 日志文件占用的总字节数
+pub fn get_logs_size<R: Runtime>(app: AppHandle<R>) -> N8nResult<u64> {
+    Ok(logs::logs_size(&base_dir(&app)?))
+}
+
+/// 删除轮转日志并清空当前日志，返回释放的字节数
+pub fn clear_logs<R: Runtime>(app: AppHandle<R>) -> N8nResult<u64> {
+    logs::clear_logs(&base_dir(&app)?)
+}
+
+/// 列出已安装的 n8n 核心版本
+pub fn list_installed_versions<R: Runtime>(app: AppHandle<R>) -> N8nResult<Vec<InstalledVersion>> {
+    Ok(versions::installed_versions(&base_dir(&app)?))
+}
+
+/// 已安装的 n8n 版本及更新状态
+#[derive(Debug, Clone, Serialize)]
+pub struct N8nVersionStatus {
+    /// 当前启用的核心中 n8n 的版本号
+    pub version: String,
+    /// 核心包来源最新发布的标签（无法访问 GitHub 时为 None）
+    pub latest_version: Option<String>,
+    pub update_available: bool,
+}
+
+/// 读取已安装的 n8n 版本并与核心包来源的最新发布比较，未安装时返回 None
+///
+/// 获取最新发布失败（离线、限流等）不视为错误，只是不提示更新。
+pub async fn get_installed_n8n_version<R: Runtime>(app: AppHandle<R>) -> N8nResult<Option<N8nVersionStatus>> {
+    let Some(version) = versions::current_version(&base_dir(&app)?) else {
+        return Ok(None);
+    };
 
-    // 1. 使用 map_err 统一错误转换，减少缩进
-    let mut manager = PROCESS_MANAGER
+    let latest_version = match installer::fetch_latest_release_tag().await {
+        Ok(tag) => Some(tag),
+        Err(error) => {
+            tracing::warn!(%error, "获取 n8n 最新版本失败");
+            None
+        }
+    };
+    let update_available = latest_version
+        .as_deref()
+        .is_some_and(|latest| versions::is_newer_version(latest, &version));
+
+    Ok(Some(N8nVersionStatus {
+        version,
+        latest_version,
+        update_available,
+    }))
+}
+
+/// 切换到已安装的 n8n 核心版本：先停止 n8n，再改写启用版本指针（不影响 `n8n-data`）
+pub fn activate_version<R: Runtime>(app: AppHandle<R>, tag: String) -> N8nResult<()> {
+    let app_path = base_dir(&app)?;
+    shutdown_n8n()?;
+    versions::activate_version(&app_path, &tag)?;
+    println!("[n8n] 已切换到 n8n {tag}");
+    Ok(())
+}
+
+/// 卸载 n8n：先停止 n8n，再删除运行时、核心与缓存的资源包；`keep_user_data` 为 `false` 时一并删除 `n8n-data`
+pub fn uninstall_n8n<R: Runtime>(app: AppHandle<R>, keep_user_data: bool) -> N8nResult<UninstallSummary> {
+    // 占用安装操作锁，避免与进行中的安装同时读写同一目录
+    let _runtime = begin_operation("runtime")?;
+    let _core = begin_operation(N8N_CORE_OPERATION)?;
+    let app_path = base_dir(&app)?;
+    shutdown_n8n()?;
+    uninstall::uninstall(&app_path, keep_user_data)
+}
+
+/// 启动前预检
+pub fn preflight<R: Runtime>(app: AppHandle<R>) -> N8nResult<PreflightReport> {
+    preflight::run_preflight(&app)
+}
+
+/// 获取 n8n 编辑器地址（包含部署子路径），供前端内嵌或在浏览器中打开
+pub fn get_editor_url() -> N8nResult<String> {
+    let port = manager::PROCESS_MANAGER
         .lock()
-        .map_err(|_| N8nCoreError::Process(i18n::t("n8n.state.process_manager_poisoned")))?;
+        .map_err(|_| N8nCoreError::Process(i18n::t("n8n.state.process_manager_poisoned")))?
+        .port()
+        .unwrap_or(manager::N8N_SERVICE_PORT);
+
+    Ok(editor_url(&manager::n8n_base_url(port)))
+}
+
+/// 在新窗口中打开 n8n 编辑器
+///
+/// 仅连接到已由 `ProcessManager` 托管的 n8n 进程，不会再启动新的 n8n 实例；
+/// 同名窗口已存在时直接聚焦。
+pub async fn open_editor_window<R: Runtime>(app: AppHandle<R>, label: String) -> N8nResult<()> {
+    let is_valid_label = !label.is_empty()
+        && label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '/' | ':' | '_'));
+    if !is_valid_label {
+        return Err(N8nCoreError::Config(format!("{}: {label}", i18n::t("window.invalid_label"))));
+    }
+
+    if let Some(window) = app.get_webview_window(&label) {
+        window.set_focus().map_err(|e| N8nCoreError::Tauri(e.to_string()))?;
+        return Ok(());
+    }
+
+    let port = manager::PROCESS_MANAGER
+        .lock()
+        .map_err(|_| N8nCoreError::Process(i18n::t("n8n.state.process_manager_poisoned")))?
+        .port()
+        .ok_or_else(|| N8nCoreError::ServiceUnavailable(i18n::t("n8n.not_running")))?;
+
+    // 确认服务已就绪再创建窗口，避免打开空白页
+    N8nHealthChecker::check_port(port).await?;
+
+    let url = url::Url::parse(&editor_url(&manager::n8n_base_url(port)))
+        .map_err(|e| N8nCoreError::Config(e.to_string()))?;
 
-    // 2. 执行 kill
-    manager.kill_child();
+    WebviewWindowBuilder::new(&app, label, WebviewUrl::External(url))
+        .title("n8n")
+        .inner_size(EDITOR_WINDOW_WIDTH, EDITOR_WINDOW_HEIGHT)
+        .build()
+        .map_err(|e| N8nCoreError::Tauri(e.to_string()))?;
 
-    println!("[n8n] 进程已请求关闭");
     Ok(())
 }
+
+/// 检测 n8n 核心包来源（发布 API 与下载地址）是否可达
+pub async fn test_connectivity() -> N8nResult<Vec<ConnectivityCheck>> {
+    connectivity::test_connectivity().await
+}
+
+/// 测速各 Node.js 镜像与核心包下载地址
+pub async fn benchmark_mirrors() -> N8nResult<Vec<MirrorBenchmark>> {
+    connectivity::benchmark_mirrors().await
+}
+
+/// 代理健康检查
+///
+/// 最多探测 `retries` 轮（默认 3 轮），每轮失败后等待 `interval_ms`（默认 500ms）；
+/// 各端点并发探测且单次请求 2 秒超时，轮数与间隔均有上限，前端可直接用它等待 n8n 就绪而无需自行轮询。
+/// `endpoints` 为按优先级排列的探测路径，未指定时先探测 `rest/settings`（就绪）再探测 `/healthz`（存活）；
+/// 成功信息中包含成功的端点。
+pub async fn proxy_health_check(
+    retries: Option<usize>,
+    interval_ms: Option<u64>,
+    endpoints: Option<Vec<String>>,
+) -> N8nResult<String> {
+    let retries = retries
+        .unwrap_or(HEALTH_CHECK_RETRIES)
+        .clamp(1, MAX_HEALTH_CHECK_RETRIES);
+    let delay = interval_ms
+        .map_or(HEALTH_CHECK_RETRY_DELAY, std::time::Duration::from_millis)
+        .min(MAX_HEALTH_CHECK_RETRY_DELAY);
+    let endpoints = endpoints
+        .map(|paths| paths.into_iter().filter(|path| !path.trim().is_empty()).collect::<Vec<_>>())
+        .filter(|paths| !paths.is_empty());
+    N8nHealthChecker::check_with(retries, delay, endpoints).await
+}
+
+/// `n8n-log` 事件负载
+#[derive(Debug, Clone, Serialize)]
+pub struct N8nLogLine {
+    pub stream: manager::LogStream,
+    pub line: String,
+}
+
+/// n8n 运行状态
+#[derive(Debug, Clone, Serialize)]
+pub struct N8nStatus {
+    /// 进程管理器中的生命周期状态
+    pub state: manager::N8nProcessState,
+    pub process_alive: bool,
+    /// 进程已退出时的退出码（被信号终止时为 None）
+    pub exit_code: Option<i32>,
+    /// 健康探测结果：`healthy` / `unhealthy` / `not_running`
+    pub health: String,
+}
+
+/// 查询 n8n 运行状态
+///
+/// 先检查托管的进程是否已自行退出（退出时状态随之切换为 `stopped` / `crashed`），
+/// 进程存活时再对其端口做一次不重试的健康探测，前端据此区分启动中、运行中、崩溃与已停止。
+pub async fn get_n8n_status() -> N8nResult<N8nStatus> {
+    let (state, port, exit_code) = {
+        let mut manager = manager::PROCESS_MANAGER
+            .lock()
+            .map_err(|_| N8nCoreError::Process(i18n::t("n8n.state.process_manager_poisoned")))?;
+        manager.check_exit();
+        let exit_code = if manager.has_child() {
+            None
+        } else {
+            manager.last_exit_code()
+        };
+        (
+            manager.state(),
+            manager.has_child().then(|| manager.port().unwrap_or(manager::N8N_SERVICE_PORT)),
+            exit_code,
+        )
+    };
+
+    let health = match port {
+        Some(port) => match N8nHealthChecker::probe_port_once(port).await {
+            Ok(_) => "healthy",
+            Err(_) => "unhealthy",
+        },
+        None => "not_running",
+    };
+
+    Ok(N8nStatus {
+        state,
+        process_alive: port.is_some(),
+        exit_code,
+        health: health.to_string(),
+    })
+}
+
+/// 关闭 n8n 进程
+///
+/// 返回值表示是否确实终止了一个正在运行的 n8n 进程。
+pub fn shutdown_n8n() -> N8nResult<bool> {
+    use crate::services::manager::PROCESS_MANAGER;
+
+    // 等待进程退出期间不持有管理器的锁
+    let stopped = manager::stop_child(&PROCESS_MANAGER)
+        .map_err(|e| N8nCoreError::Process(format!("{}: {e}", i18n::t("n8n.kill_failed"))))?;
+
+    if stopped {
+        println!("[n8n] 进程已关闭");
+    } else {
+        println!("[n8n] 没有正在运行的 n8n 进程");
+    }
+    Ok(stopped)
+}
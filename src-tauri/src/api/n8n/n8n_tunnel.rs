@@ -0,0 +1,68 @@
+//! n8n 内置隧道模式（`n8n start --tunnel`）
+//!
+//! 启用后 n8n 会通过官方的 hooks.n8n.cloud 服务将实例暴露到公网，便于测试 webhook。
+//! 这意味着任何知道地址的人都能访问该实例，启动时会向用户发出警告。
+//! 与 cloudflared 隧道（`api::tunnel`）相互独立。
+
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::api::settings::settings_lock;
+use crate::i18n;
+use crate::services::manager::LogLineHandler;
+
+/// 隧道模式命令行参数
+const TUNNEL_ARG: &str = "--tunnel";
+
+/// n8n 隧道服务域名
+const N8N_TUNNEL_HOST: &str = "hooks.n8n.cloud";
+
+/// 隧道地址事件
+pub const TUNNEL_URL_EVENT: &str = "n8n-tunnel-url";
+
+/// 隧道模式警告事件
+pub const TUNNEL_WARNING_EVENT: &str = "n8n-tunnel-warning";
+
+/// 设置中是否启用了 n8n 隧道模式（启动时未指定 `tunnel` 参数时的默认值）
+pub fn is_enabled() -> bool {
+    settings_lock().n8n_tunnel_enabled
+}
+
+/// 构造 `n8n start` 的附加参数（`enabled` 为本次启动是否使用隧道模式）
+pub fn start_args(enabled: bool) -> Vec<String> {
+    if enabled {
+        vec![TUNNEL_ARG.to_string()]
+    } else {
+        Vec::new()
+    }
+}
+
+/// 从 n8n 的日志行中提取隧道地址
+pub fn extract_tunnel_url(line: &str) -> Option<String> {
+    let start = line.find("https://")?;
+    let url: String = line[start..]
+        .chars()
+        .take_while(|c| !c.is_whitespace())
+        .collect();
+
+    let is_tunnel_line = line.to_lowercase().contains("tunnel") || url.contains(N8N_TUNNEL_HOST);
+    is_tunnel_line.then_some(url)
+}
+
+/// 创建扫描隧道地址的日志处理器，识别到地址后通过事件通知前端
+pub fn tunnel_url_handler<R: Runtime>(app: &AppHandle<R>) -> LogLineHandler {
+    let app = app.clone();
+    Arc::new(move |_, line: &str| {
+        if let Some(url) = extract_tunnel_url(line) {
+            println!("[n8n] 隧道地址: {url}");
+            let _ = app.emit(TUNNEL_URL_EVENT, url);
+        }
+    })
+}
+
+/// 提醒用户隧道模式会将实例暴露到公网
+pub fn warn_exposure<R: Runtime>(app: &AppHandle<R>) {
+    let message = i18n::t("n8n.tunnel.exposure_warning");
+    eprintln!("[n8n] ⚠️  {message}");
+    let _ = app.emit(TUNNEL_WARNING_EVENT, message);
+}
@@ -0,0 +1,79 @@
+//! 离线安装支持
+//!
+//! 面向无法访问任何镜像的离线 / 内网环境：IT 人员可将 Node 与 n8n-core 资源包预先放入
+//! 数据目录下的 `offline/` 文件夹（或在调用时显式指定路径），安装时跳过网络下载直接校验并解压。
+//!
+//! 摘要来源（任选其一，均不存在时跳过校验）：
+//! - 与资源包同目录的 `<文件名>.sha256`
+//! - 同目录下 Node 官方格式的 `SHASUMS256.txt`
+//! - 调用方提供的远程摘要
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::error::N8nResult;
+use super::installer::verify_file_hash;
+
+/// 离线资源包目录名（位于数据基础目录下）
+pub const OFFLINE_DIRNAME: &str = "offline";
+
+/// Node 官方摘要清单文件名
+pub const SHASUMS_FILENAME: &str = "SHASUMS256.txt";
+
+/// 获取离线资源包目录
+pub fn offline_dir(base_dir: &Path) -> PathBuf {
+    base_dir.join(OFFLINE_DIRNAME)
+}
+
+/// 查找预先放置的离线资源包
+pub fn find_offline_archive(base_dir: &Path, file_name: &str) -> Option<PathBuf> {
+    let path = offline_dir(base_dir).join(file_name);
+    path.is_file().then_some(path)
+}
+
+/// 读取离线资源包旁的摘要（`.sha256` 文件或 `SHASUMS256.txt`）
+pub fn local_digest(archive_path: &Path) -> Option<String> {
+    let file_name = archive_path.file_name()?.to_str()?;
+    let dir = archive_path.parent()?;
+
+    let sidecar = dir.join(format!("{file_name}.sha256"));
+    if let Ok(content) = fs::read_to_string(sidecar) {
+        if let Some(hash) = content.split_whitespace().next() {
+            return Some(hash.to_lowercase());
+        }
+    }
+
+    let shasums = fs::read_to_string(dir.join(SHASUMS_FILENAME)).ok()?;
+    shasums_entry(&shasums, file_name)
+}
+
+/// 从 `SHASUMS256.txt` 格式的清单（`<hash>  <文件名>`）中查找文件的摘要
+pub fn shasums_entry(shasums: &str, file_name: &str) -> Option<String> {
+    shasums.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        let name = parts.next()?;
+        (name.trim_start_matches('*') == file_name).then(|| hash.to_lowercase())
+    })
+}
+
+/// 校验离线资源包（优先使用调用方提供的摘要，其次是本地摘要文件）
+pub fn verify_offline_archive(archive_path: &Path, expected_sha256: Option<&str>) -> N8nResult<()> {
+    let expected = expected_sha256
+        .map(ToString::to_string)
+        .or_else(|| local_digest(archive_path));
+
+    match expected {
+        Some(hash) => {
+            println!("[n8n][offline] 校验离线资源包: {}", archive_path.display());
+            verify_file_hash(archive_path, &hash)
+        }
+        None => {
+            println!(
+                "[n8n][offline] 未找到 {} 的摘要，跳过 SHA256 校验",
+                archive_path.display()
+            );
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! 应用数据目录模块
+//!
+//! 统一解析 `runtime`、`n8n-core`、`n8n-data` 等目录所在的基础路径，
+//! 并在下载、解压、启动之前确认数据目录可用，避免在深层 IO 操作中才暴露出难以理解的错误。
+//!
+//! 基础目录的优先级：`N8N_DESKTOP_DATA_DIR` 环境变量 > 便携模式 > 系统 AppData。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use sysinfo::Disks;
+use tauri::{AppHandle, Manager, Runtime};
+
+use super::error::{N8nCoreError, N8nResult};
+use crate::api::settings::settings_lock;
+
+/// 写入测试使用的临时文件名
+const WRITE_PROBE_FILENAME: &str = ".write-probe";
+
+/// 便携模式标记文件名（与可执行文件同级）
+pub const PORTABLE_MARKER_FILENAME: &str = "portable.txt";
+
+/// 便携模式数据目录名（与可执行文件同级）
+pub const PORTABLE_DATA_DIRNAME: &str = "data";
+
+/// n8n 数据目录的默认目录名（位于数据基础目录下）
+pub const N8N_DATA_DIRNAME: &str = "n8n-data";
+
+/// n8n 在数据目录（`N8N_USER_FOLDER`）中实际存放数据的子目录
+pub const N8N_USER_DIRNAME: &str = ".n8n";
+
+/// 覆盖数据基础目录的环境变量（用于测试与多配置档）
+pub const DATA_DIR_ENV: &str = "N8N_DESKTOP_DATA_DIR";
+
+/// 环境变量覆盖的数据目录（进程内只解析一次）
+static ENV_DATA_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// 获取环境变量指定的数据目录
+///
+/// 未设置时返回 `None`；路径不合法或不可写时打印警告并回退到默认目录。
+pub fn env_data_dir() -> Option<PathBuf> {
+    ENV_DATA_DIR
+        .get_or_init(|| {
+            let value = std::env::var(DATA_DIR_ENV).ok()?;
+            validate_data_dir_override(&value)
+                .map_err(|reason| {
+                    eprintln!("[n8n] ⚠️  忽略 {DATA_DIR_ENV}={value}: {reason}，使用默认数据目录");
+                })
+                .ok()
+        })
+        .clone()
+}
+
+/// 校验覆盖的数据目录：必须是绝对路径，且可以创建并写入
+fn validate_data_dir_override(value: &str) -> Result<PathBuf, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err("路径为空".to_string());
+    }
+
+    let dir = PathBuf::from(value);
+    if !dir.is_absolute() {
+        return Err("必须是绝对路径".to_string());
+    }
+
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    probe_writable(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+/// 获取便携模式的数据目录
+///
+/// 可执行文件旁存在 `portable.txt` 或 `data/` 时启用便携模式，返回 `<exe_dir>/data`。
+pub fn portable_data_dir() -> Option<PathBuf> {
+    let exe_path = std::env::current_exe().ok()?;
+    let exe_dir = exe_path.parent()?;
+    let data_dir = exe_dir.join(PORTABLE_DATA_DIRNAME);
+
+    let is_portable = exe_dir.join(PORTABLE_MARKER_FILENAME).exists() || data_dir.is_dir();
+    is_portable.then_some(data_dir)
+}
+
+/// 是否运行在便携模式
+pub fn is_portable_mode() -> bool {
+    portable_data_dir().is_some()
+}
+
+/// 获取数据基础目录（环境变量覆盖 > 便携模式下可执行文件旁的 `data/` > AppData）
+pub fn base_dir<R: Runtime>(app: &AppHandle<R>) -> N8nResult<PathBuf> {
+    if let Some(data_dir) = env_data_dir().or_else(portable_data_dir) {
+        return Ok(data_dir);
+    }
+
+    app.path()
+        .app_data_dir()
+        .map_err(|e| N8nCoreError::Path(e.to_string()))
+}
+
+/// 获取 n8n 数据目录（设置中指定了自定义位置时优先，否则为基础目录下的 `n8n-data`）
+pub fn n8n_data_dir(base_dir: &Path) -> PathBuf {
+    settings_lock()
+        .n8n_data_dir
+        .clone()
+        .unwrap_or_else(|| base_dir.join(N8N_DATA_DIRNAME))
+}
+
+/// 获取配置文件目录（环境变量覆盖或便携模式下与数据目录相同，不写入系统配置目录）
+pub fn config_dir<R: Runtime>(app: &AppHandle<R>) -> N8nResult<PathBuf> {
+    if let Some(data_dir) = env_data_dir().or_else(portable_data_dir) {
+        return Ok(data_dir);
+    }
+
+    app.path()
+        .app_config_dir()
+        .map_err(|e| N8nCoreError::Path(e.to_string()))
+}
+
+/// 解析、创建并写入测试应用数据目录
+pub fn ensure_app_data_dir<R: Runtime>(app: &AppHandle<R>) -> N8nResult<PathBuf> {
+    let app_data_dir = base_dir(app)?;
+
+    fs::create_dir_all(&app_data_dir).map_err(|e| unwritable(&app_data_dir, &e))?;
+    probe_writable(&app_data_dir).map_err(|e| unwritable(&app_data_dir, &e))?;
+
+    Ok(app_data_dir)
+}
+
+/// 路径所在磁盘的挂载点与可用空间（按最长挂载点匹配；找不到对应磁盘时为 None）
+pub fn disk_space(path: &Path) -> Option<(PathBuf, u64)> {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    Disks::new_with_refreshed_list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| (disk.mount_point().to_path_buf(), disk.available_space()))
+}
+
+/// 下载前确认 `dir` 所在磁盘至少有 `required` 字节可用（无法查询磁盘信息时不阻止安装）
+pub fn ensure_disk_space(dir: &Path, required: u64) -> N8nResult<()> {
+    match disk_space(dir) {
+        Some((_, available)) if available < required => Err(N8nCoreError::InsufficientDiskSpace {
+            path: dir.display().to_string(),
+            required,
+            available,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// 写入并删除一个临时文件，确认目录确实可写
+pub(crate) fn probe_writable(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(WRITE_PROBE_FILENAME);
+    fs::write(&probe, b"")?;
+    fs::remove_file(&probe)
+}
+
+pub(crate) fn unwritable(dir: &Path, error: &std::io::Error) -> N8nCoreError {
+    N8nCoreError::DataDirUnwritable {
+        path: dir.display().to_string(),
+        reason: error.to_string(),
+    }
+}
+
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+
+    #[test]
+    fn test_validate_data_dir_override() {
+        assert!(validate_data_dir_override("").is_err());
+        assert!(validate_data_dir_override("relative/data").is_err());
+
+        let dir = temp_dir().join("n8n_data_dir_override_test");
+        let resolved = validate_data_dir_override(&dir.to_string_lossy()).expect("应接受可写的绝对路径");
+        assert_eq!(resolved, dir);
+        assert!(dir.is_dir());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,67 @@
+//! n8n 文件权限修复模块
+//!
+//! 从备份恢复或跨机器拷贝数据后，可执行位和 macOS 隔离属性可能丢失，
+//! 导致 `launch_n8n` 在 Unix 上失败。此模块按需重新修复已知的可执行文件。
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::versions;
+use crate::services::{downloader, manager};
+
+/// 权限修复结果
+#[derive(Clone, Serialize, Debug, Default)]
+pub struct PermissionFixReport {
+    /// 已修复的文件
+    pub fixed: Vec<String>,
+    /// 修复失败的文件及原因
+    pub failed: Vec<String>,
+}
+
+/// 收集需要可执行权限的已知文件
+fn collect_executables(app_data_dir: &Path) -> Vec<PathBuf> {
+    let runtime_dir = app_data_dir.join("runtime");
+    let core_dir = versions::core_dir(app_data_dir);
+
+    let mut targets = vec![
+        manager::get_node_binary_path(runtime_dir.clone()),
+        core_dir.join("node_modules/n8n/bin/n8n"),
+    ];
+
+    // runtime/bin 下的 npm/npx 以及 node_modules/.bin 下的脚本
+    for bin_dir in [runtime_dir.join("bin"), core_dir.join("node_modules/.bin")] {
+        if let Ok(entries) = fs::read_dir(&bin_dir) {
+            targets.extend(entries.flatten().map(|entry| entry.path()));
+        }
+    }
+
+    targets.sort();
+    targets.dedup();
+    targets.into_iter().filter(|path| path.exists()).collect()
+}
+
+/// 重新为已知可执行文件设置权限，并移除 macOS 隔离属性
+pub fn fix_install_permissions(app_data_dir: &Path) -> PermissionFixReport {
+    let mut report = PermissionFixReport::default();
+
+    for path in collect_executables(app_data_dir) {
+        match downloader::make_executable(&path) {
+            Ok(()) => report.fixed.push(path.display().to_string()),
+            Err(error) => report.failed.push(format!("{}: {error}", path.display())),
+        }
+    }
+
+    for dir in [app_data_dir.join("runtime"), versions::core_dir(app_data_dir)] {
+        if dir.exists() {
+            downloader::clear_quarantine(&dir);
+        }
+    }
+
+    println!(
+        "[n8n][fix_permissions] 已修复 {} 个文件，失败 {} 个",
+        report.fixed.len(),
+        report.failed.len()
+    );
+    report
+}
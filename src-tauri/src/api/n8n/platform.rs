@@ -0,0 +1,39 @@
+//! 平台信息模块
+//!
+//! 汇总当前系统、架构以及对应的 Node 下载地址和 n8n 核心包名称，
+//! 前端据此展示下载说明，并在平台不受支持时提前提示，而不是等安装失败。
+
+use serde::Serialize;
+use std::env;
+
+use super::installer::{core_asset_name, core_platform};
+use crate::services::manager;
+
+/// 平台信息
+#[derive(Debug, Clone, Serialize)]
+pub struct PlatformInfo {
+    /// 操作系统（`env::consts::OS`）
+    pub os: String,
+    /// CPU 架构（`env::consts::ARCH`）
+    pub arch: String,
+    /// 是否同时支持下载 Node 运行时与 n8n 核心包
+    pub supported: bool,
+    /// Node 运行时首选下载地址（不支持时为空）
+    pub node_url: String,
+    /// n8n 核心包资源文件名
+    pub core_asset: String,
+}
+
+/// 检测当前平台信息
+pub fn platform_info() -> PlatformInfo {
+    let node_url = manager::get_node_url().ok();
+    let platform = core_platform();
+
+    PlatformInfo {
+        os: env::consts::OS.to_string(),
+        arch: env::consts::ARCH.to_string(),
+        supported: node_url.is_some() && platform != "unknown",
+        node_url: node_url.unwrap_or_default(),
+        core_asset: core_asset_name(platform),
+    }
+}
@@ -0,0 +1,123 @@
+//! n8n 启动前预检模块
+//!
+//! 汇总启动前的环境检查结果，供前端在用户遇到问题之前给出提示。
+
+use serde::Serialize;
+use std::path::Path;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use super::error::N8nResult;
+use super::memory;
+use super::paths::base_dir;
+use crate::api::settings::settings_lock;
+use crate::i18n;
+use crate::services::manager;
+
+/// 单项预检结果
+#[derive(Clone, Serialize, Debug)]
+pub struct PreflightCheck {
+    /// 检查项标识
+    pub id: String,
+    /// 是否通过
+    pub passed: bool,
+    /// 结果说明
+    pub message: String,
+}
+
+impl PreflightCheck {
+    fn new(id: &str, passed: bool, message: String) -> Self {
+        Self {
+            id: id.to_string(),
+            passed,
+            message,
+        }
+    }
+}
+
+/// 预检报告
+#[derive(Clone, Serialize, Debug)]
+pub struct PreflightReport {
+    /// 所有检查是否均通过
+    pub ok: bool,
+    /// 各项检查结果
+    pub checks: Vec<PreflightCheck>,
+}
+
+/// 运行时架构不一致事件负载
+#[derive(Clone, Serialize, Debug)]
+pub struct RuntimeArchMismatch {
+    /// 系统架构对应的 Node.js 架构
+    pub expected: String,
+    /// 运行时 Node 报告的架构
+    pub actual: String,
+}
+
+/// 执行启动前预检
+pub fn run_preflight<R: Runtime>(app: &AppHandle<R>) -> N8nResult<PreflightReport> {
+    let app_path = base_dir(app)?;
+
+    let checks = vec![
+        check_runtime_arch(app, &app_path.join("runtime")),
+        check_memory(),
+    ];
+
+    Ok(PreflightReport {
+        ok: checks.iter().all(|check| check.passed),
+        checks,
+    })
+}
+
+/// 检查运行时 Node 架构是否与系统一致（如 Apple Silicon 上误装 x64 构建）
+fn check_runtime_arch<R: Runtime>(app: &AppHandle<R>, runtime_dir: &Path) -> PreflightCheck {
+    const ID: &str = "runtime_arch";
+
+    if !manager::get_node_binary_path(runtime_dir.to_path_buf()).exists() {
+        return PreflightCheck::new(ID, false, i18n::t("preflight.runtime_missing"));
+    }
+
+    let expected = manager::expected_node_arch();
+    match manager::get_runtime_arch(runtime_dir) {
+        Some(actual) if actual == expected => {
+            PreflightCheck::new(ID, true, i18n::t("preflight.runtime_arch_ok"))
+        }
+        Some(actual) => {
+            eprintln!("[n8n][preflight] 运行时架构不一致: 期望 {expected}, 实际 {actual}");
+            let _ = app.emit(
+                "runtime-arch-mismatch",
+                RuntimeArchMismatch {
+                    expected: expected.to_string(),
+                    actual: actual.clone(),
+                },
+            );
+            PreflightCheck::new(
+                ID,
+                false,
+                format!(
+                    "{} ({expected} / {actual})",
+                    i18n::t("preflight.runtime_arch_mismatch")
+                ),
+            )
+        }
+        None => PreflightCheck::new(ID, false, i18n::t("preflight.runtime_arch_unknown")),
+    }
+}
+
+/// 检查可用物理内存是否满足设置的阈值
+fn check_memory() -> PreflightCheck {
+    const ID: &str = "memory";
+
+    let Some(status) = memory::memory_status() else {
+        return PreflightCheck::new(ID, true, i18n::t("memory.unknown"));
+    };
+
+    let settings = settings_lock().memory.clone();
+    if memory::is_low_memory(&status, &settings) {
+        PreflightCheck::new(ID, false, memory::low_memory_message(&status, &settings))
+    } else {
+        PreflightCheck::new(
+            ID,
+            true,
+            format!("{} ({} MB)", i18n::t("memory.ok"), status.available_mb),
+        )
+    }
+}
@@ -0,0 +1,71 @@
+//! 安装阶段状态机
+//!
+//! `setup_runtime` 与 `setup_n8n` 在每次阶段切换时发送 `setup-phase` 事件，前端据此展示安装进度，
+//! 无需再从下载进度、解压开始等零散事件推断当前状态。每次安装以 `done` 或 `failed` 结束。
+
+use serde::Serialize;
+use tauri::{Emitter, Runtime, Window};
+
+use super::constants::SETUP_PHASE_EVENT;
+use super::error::N8nResult;
+
+/// 安装阶段（序列化为 `{"phase": "downloading_core"}`，失败时附带 `reason`）
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(tag = "phase", rename_all = "snake_case")]
+pub enum SetupPhase {
+    /// 检查已安装的 Node 运行时是否可用
+    CheckingRuntime,
+    /// 下载 Node 运行时
+    DownloadingRuntime,
+    /// 解压 Node 运行时
+    ExtractingRuntime,
+    /// 检查 n8n 核心包（获取发布摘要与本地缓存）
+    CheckingCore,
+    /// 校验资源包 SHA256
+    VerifyingHash,
+    /// 下载 n8n 核心包
+    DownloadingCore,
+    /// 解压 n8n 核心包
+    ExtractingCore,
+    /// 本次安装已完成
+    Done,
+    /// 本次安装失败（含被取消）
+    Failed { reason: String },
+}
+
+/// 发送阶段切换事件
+pub fn emit<R: Runtime>(window: &Window<R>, phase: SetupPhase) {
+    let _ = window.emit(SETUP_PHASE_EVENT, phase);
+}
+
+/// 按安装结果发送 `done` 或 `failed`
+pub fn finish<R: Runtime>(window: &Window<R>, result: &N8nResult<()>) {
+    let phase = match result {
+        Ok(()) => SetupPhase::Done,
+        Err(error) => SetupPhase::Failed {
+            reason: error.to_string(),
+        },
+    };
+    emit(window, phase);
+}
+
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setup_phase_serialization() {
+        assert_eq!(
+            serde_json::to_value(SetupPhase::DownloadingCore).expect("序列化失败"),
+            serde_json::json!({ "phase": "downloading_core" })
+        );
+        assert_eq!(
+            serde_json::to_value(SetupPhase::Failed {
+                reason: "timeout".to_string()
+            })
+            .expect("序列化失败"),
+            serde_json::json!({ "phase": "failed", "reason": "timeout" })
+        );
+    }
+}
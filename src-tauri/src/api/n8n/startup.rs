@@ -0,0 +1,393 @@
+//! n8n 启动等待
+//!
+//! 启动 n8n 后按设置轮询健康检查，直到服务就绪或超时。轮询间隔随尝试次数逐渐拉长，
+//! 避免冷启动（首次数据库迁移）期间频繁请求。超时时附带最后一次健康检查错误
+//! 以及从 n8n 输出中捕获的最近一条启动错误。
+//!
+//! n8n 完全就绪时会输出 `Editor is now accessible via: <地址>`，部分版本上比轮询 `/healthz` 更可靠：
+//! 扫描到该提示时发送 `n8n-ready` 事件，等待中的启动流程也会立即结束。
+//!
+//! 环境变量错误、缺少模块等问题会让 n8n 在启动后几秒内退出，编辑器窗口只显示空白页。
+//! 每次启动后监视 `STARTUP_CRASH_WINDOW`，期间进程已退出时发送 `n8n-startup-failed` 事件，
+//! 附带退出码与最近的 stderr 输出（需通过管道读取输出，`null` 模式下无法捕获）。
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio::sync::Notify;
+
+use super::constants::{N8N_READY_EVENT, N8N_STARTUP_FAILED_EVENT};
+use super::error::{N8nCoreError, N8nResult};
+use super::state::N8nHealthChecker;
+use crate::api::settings::StartupSettings;
+use crate::i18n;
+use crate::services::manager::{self, LogLineHandler, LogStream};
+
+/// n8n 就绪提示（不同版本的写法）
+const READY_BANNERS: [&str; 2] = ["Editor is now accessible via", "n8n ready on"];
+
+/// 每次尝试后轮询间隔的增长比例
+const HEALTH_CHECK_BACKOFF_FACTOR: f64 = 1.25;
+
+/// 轮询间隔上限
+const MAX_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 捕获的启动错误行最大长度（字符）
+const MAX_STARTUP_ERROR_CHARS: usize = 500;
+
+/// 启动后在该时间内退出视为启动失败
+const STARTUP_CRASH_WINDOW: Duration = Duration::from_secs(5);
+
+/// 启动失败时附带的 stderr 最大行数
+const MAX_STARTUP_STDERR_LINES: usize = 20;
+
+/// 本次启动中 n8n 输出的最近一条错误行
+static LAST_STARTUP_ERROR: LazyLock<Mutex<Option<String>>> = LazyLock::new(|| Mutex::new(None));
+
+/// 本次启动中 n8n 最近输出的 stderr 行
+static RECENT_STDERR: LazyLock<Mutex<VecDeque<String>>> = LazyLock::new(|| Mutex::new(VecDeque::new()));
+
+/// 本次启动的 n8n 主进程 ID
+static LAUNCHED_PID: LazyLock<Mutex<Option<u32>>> = LazyLock::new(|| Mutex::new(None));
+
+/// n8n 启动后立即退出的说明
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupFailure {
+    /// 退出码（被信号终止时为 None）
+    pub exit_code: Option<i32>,
+    /// 退出状态说明（如 `exit status: 1`）
+    pub status: Option<String>,
+    /// 退出前最近的 stderr 输出（未捕获时为空）
+    pub stderr: String,
+}
+
+impl StartupFailure {
+    /// 面向用户的错误信息（未捕获到输出时提示切换输出模式）
+    pub fn message(&self) -> String {
+        let output = if self.stderr.is_empty() {
+            i18n::t("n8n.startup.no_output")
+        } else {
+            self.stderr.clone()
+        };
+        format!(
+            "{} ({}):\n{output}",
+            i18n::t("n8n.startup.exited"),
+            self.status.as_deref().unwrap_or("-")
+        )
+    }
+}
+
+/// 就绪提示扫描状态
+#[derive(Default)]
+struct ReadyBanner {
+    /// 已看到提示但地址在下一行输出
+    awaiting_url: bool,
+    /// 已就绪时的编辑器地址
+    url: Option<String>,
+}
+
+/// 本次启动的就绪提示扫描状态
+static READY_BANNER: LazyLock<Mutex<ReadyBanner>> = LazyLock::new(|| Mutex::new(ReadyBanner::default()));
+
+/// 扫描到就绪提示时唤醒等待中的启动流程
+static READY_NOTIFY: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// 清空上次启动捕获的错误行、stderr 输出与就绪状态（每次启动前调用）
+pub fn reset_startup_state() {
+    *LAST_STARTUP_ERROR.lock().unwrap_or_else(|p| p.into_inner()) = None;
+    RECENT_STDERR.lock().unwrap_or_else(|p| p.into_inner()).clear();
+    *LAUNCHED_PID.lock().unwrap_or_else(|p| p.into_inner()) = None;
+    *READY_BANNER.lock().unwrap_or_else(|p| p.into_inner()) = ReadyBanner::default();
+}
+
+/// 本次启动中从就绪提示解析出的编辑器地址
+pub fn ready_url() -> Option<String> {
+    READY_BANNER
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .url
+        .clone()
+}
+
+/// 扫描 n8n 就绪提示的日志处理器，就绪时发送 `n8n-ready` 事件（载荷为编辑器地址）
+pub fn ready_banner_handler<R: Runtime>(app: &AppHandle<R>) -> LogLineHandler {
+    let app = app.clone();
+    Arc::new(move |_, line: &str| {
+        let url = {
+            let mut banner = READY_BANNER.lock().unwrap_or_else(|p| p.into_inner());
+            if banner.url.is_some() {
+                return;
+            }
+            let Some(url) = scan_ready_line(&mut banner, line) else {
+                return;
+            };
+            banner.url = Some(url.clone());
+            url
+        };
+
+        println!("[n8n] 已就绪: {url}");
+        manager::PROCESS_MANAGER
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .mark_running();
+        READY_NOTIFY.notify_one();
+        let _ = app.emit(N8N_READY_EVENT, url);
+    })
+}
+
+/// 检查一行输出是否表示就绪，返回其中的地址
+///
+/// 新版本将地址与提示写在同一行，部分版本在下一行单独输出地址；
+/// `n8n ready on 0.0.0.0, port 5678` 这类没有地址的写法按端口拼出本机地址。
+fn scan_ready_line(banner: &mut ReadyBanner, line: &str) -> Option<String> {
+    let line = line.trim();
+    if banner.awaiting_url {
+        if let Some(url) = extract_url(line) {
+            banner.awaiting_url = false;
+            return Some(url);
+        }
+    }
+
+    let matched = READY_BANNERS.iter().find(|marker| line.contains(*marker))?;
+    if let Some(url) = extract_url(line) {
+        return Some(url);
+    }
+    if let Some(port) = line.rsplit("port").next().and_then(|rest| rest.trim().parse::<u16>().ok()) {
+        return Some(manager::n8n_base_url(port));
+    }
+    banner.awaiting_url = *matched == READY_BANNERS[0];
+    None
+}
+
+/// 提取行中的第一个 http(s) 地址
+fn extract_url(line: &str) -> Option<String> {
+    let start = line.find("http://").or_else(|| line.find("https://"))?;
+    line[start..]
+        .split_whitespace()
+        .next()
+        .map(|url| url.trim_end_matches(['.', ',']).to_string())
+}
+
+/// 最近捕获的启动错误行
+pub fn last_startup_error() -> Option<String> {
+    LAST_STARTUP_ERROR
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .clone()
+}
+
+/// 扫描 n8n 输出并记录错误行与最近 stderr 输出的日志处理器
+pub fn startup_error_handler() -> LogLineHandler {
+    Arc::new(|stream, line: &str| {
+        if stream == LogStream::Stderr {
+            push_recent_line(&mut RECENT_STDERR.lock().unwrap_or_else(|p| p.into_inner()), line);
+        }
+        if is_error_line(line) {
+            let line: String = line.trim().chars().take(MAX_STARTUP_ERROR_CHARS).collect();
+            *LAST_STARTUP_ERROR.lock().unwrap_or_else(|p| p.into_inner()) = Some(line);
+        }
+    })
+}
+
+/// 追加一行输出，只保留最近 `MAX_STARTUP_STDERR_LINES` 行（空行不记录）
+fn push_recent_line(lines: &mut VecDeque<String>, line: &str) {
+    let line = line.trim_end();
+    if line.trim().is_empty() {
+        return;
+    }
+    if lines.len() >= MAX_STARTUP_STDERR_LINES {
+        lines.pop_front();
+    }
+    lines.push_back(line.chars().take(MAX_STARTUP_ERROR_CHARS).collect());
+}
+
+/// 监视刚启动的 n8n：`STARTUP_CRASH_WINDOW` 内已退出时发送 `n8n-startup-failed` 事件
+pub fn watch_startup_crash<R: Runtime>(app: &AppHandle<R>, pid: u32) {
+    *LAUNCHED_PID.lock().unwrap_or_else(|p| p.into_inner()) = Some(pid);
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(STARTUP_CRASH_WINDOW);
+        if let Some(failure) = exited_failure(pid) {
+            tracing::error!(pid, "{}", failure.message());
+            let _ = app.emit(N8N_STARTUP_FAILED_EVENT, failure);
+        }
+    });
+}
+
+/// 本次启动的 n8n 已自行退出时返回退出说明
+pub fn startup_failure() -> Option<StartupFailure> {
+    let pid = (*LAUNCHED_PID.lock().unwrap_or_else(|p| p.into_inner()))?;
+    exited_failure(pid)
+}
+
+/// `pid` 仍是最近一次启动的进程且已自行退出（非用户停止）时返回退出说明
+fn exited_failure(pid: u32) -> Option<StartupFailure> {
+    if *LAUNCHED_PID.lock().unwrap_or_else(|p| p.into_inner()) != Some(pid) {
+        return None;
+    }
+
+    let (exit_code, status) = {
+        let mut manager = manager::PROCESS_MANAGER.lock().unwrap_or_else(|p| p.into_inner());
+        manager.check_exit();
+        if manager.exited_pid() != Some(pid) {
+            return None;
+        }
+        (manager.last_exit_code(), manager.last_exit())
+    };
+    let stderr = RECENT_STDERR
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .iter()
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Some(StartupFailure {
+        exit_code,
+        status,
+        stderr,
+    })
+}
+
+/// 判断输出行是否为错误信息
+fn is_error_line(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("Error")
+        || line.contains("There was an error")
+        || line.contains("Error:")
+        || line.contains("[ERROR]")
+}
+
+/// 第 `attempt` 次尝试（从 0 开始）失败后的等待时间
+///
+/// 用户设置的初始间隔本身超过上限时以初始间隔为准，不再增长。
+fn poll_interval(settings: &StartupSettings, attempt: u32) -> Duration {
+    let base = Duration::from_millis(settings.health_check_interval_ms.max(1));
+    let grown = base.mul_f64(HEALTH_CHECK_BACKOFF_FACTOR.powi(attempt.min(32) as i32));
+    grown.min(MAX_HEALTH_CHECK_INTERVAL.max(base))
+}
+
+/// 轮询健康检查直到 n8n 就绪，超时返回 `STARTUP_TIMEOUT`
+pub async fn wait_until_ready(settings: &StartupSettings) -> N8nResult<String> {
+    let deadline = Instant::now() + Duration::from_secs(settings.timeout_secs);
+    let mut attempt: u32 = 0;
+
+    let last_error = loop {
+        // n8n 已输出就绪提示时无需再等待健康检查
+        if let Some(url) = ready_url() {
+            return Ok(format!("ready - {url}"));
+        }
+
+        let port = manager::PROCESS_MANAGER
+            .lock()
+            .map_err(|_| N8nCoreError::Process(i18n::t("n8n.state.process_manager_poisoned")))?
+            .port();
+        // 进程已退出时附带退出状态与 stderr 输出，而不是笼统的"未运行"
+        let Some(port) = port else {
+            return Err(match startup_failure() {
+                Some(failure) => N8nCoreError::StartupFailed(failure.message()),
+                None => N8nCoreError::ServiceUnavailable(i18n::t("n8n.not_running")),
+            });
+        };
+
+        let error = match N8nHealthChecker::probe_port_once(port).await {
+            Ok(message) => {
+                println!("[n8n] 第 {} 次健康检查通过", attempt + 1);
+                manager::PROCESS_MANAGER
+                    .lock()
+                    .unwrap_or_else(|p| p.into_inner())
+                    .mark_running();
+                return Ok(message);
+            }
+            Err(error) => error,
+        };
+
+        let delay = poll_interval(settings, attempt);
+        attempt += 1;
+        let attempts_exhausted = settings.max_attempts > 0 && attempt >= settings.max_attempts;
+        if attempts_exhausted || Instant::now() + delay > deadline {
+            break error;
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = READY_NOTIFY.notified() => {}
+        }
+    };
+
+    Err(N8nCoreError::StartupTimeout(timeout_message(
+        attempt,
+        &last_error,
+        last_startup_error().as_deref(),
+    )))
+}
+
+/// 组装超时说明（尝试次数、最后一次健康检查错误与捕获的启动错误）
+fn timeout_message(attempts: u32, last_error: &str, startup_error: Option<&str>) -> String {
+    let mut message = format!(
+        "{} ({attempts}): {last_error}",
+        i18n::t("n8n.startup.health_check_failed")
+    );
+    if let Some(startup_error) = startup_error {
+        message.push_str(&format!("; {}: {startup_error}", i18n::t("n8n.startup.last_error")));
+    }
+    message
+}
+
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_interval_backs_off_and_caps() {
+        let settings = StartupSettings::default();
+        assert_eq!(poll_interval(&settings, 0), Duration::from_millis(1000));
+        assert!(poll_interval(&settings, 1) > poll_interval(&settings, 0));
+        assert_eq!(poll_interval(&settings, 100), MAX_HEALTH_CHECK_INTERVAL);
+
+        assert!(is_error_line("Error: There was an error initializing DB"));
+        assert!(!is_error_line("Editor is now accessible via:"));
+    }
+
+    #[test]
+    fn test_scan_ready_line_handles_banner_variants() {
+        let mut banner = ReadyBanner::default();
+        assert_eq!(
+            scan_ready_line(&mut banner, "Editor is now accessible via: http://localhost:5678/"),
+            Some("http://localhost:5678/".to_string())
+        );
+
+        let mut banner = ReadyBanner::default();
+        assert_eq!(scan_ready_line(&mut banner, "Editor is now accessible via:"), None);
+        assert_eq!(scan_ready_line(&mut banner, "Press \"o\" to open in Browser."), None);
+        assert_eq!(
+            scan_ready_line(&mut banner, "http://localhost:5678"),
+            Some("http://localhost:5678".to_string())
+        );
+
+        let mut banner = ReadyBanner::default();
+        assert_eq!(
+            scan_ready_line(&mut banner, "n8n ready on 0.0.0.0, port 5679"),
+            Some(manager::n8n_base_url(5679))
+        );
+        assert_eq!(scan_ready_line(&mut ReadyBanner::default(), "Version: 1.90.0"), None);
+    }
+
+    #[test]
+    fn test_push_recent_line_keeps_latest_lines() {
+        let mut lines = VecDeque::new();
+        for index in 0..MAX_STARTUP_STDERR_LINES + 5 {
+            push_recent_line(&mut lines, &format!("line {index}"));
+        }
+        push_recent_line(&mut lines, "   ");
+
+        assert_eq!(lines.len(), MAX_STARTUP_STDERR_LINES);
+        assert_eq!(lines.front().map(String::as_str), Some("line 5"));
+        assert_eq!(
+            lines.back().cloned(),
+            Some(format!("line {}", MAX_STARTUP_STDERR_LINES + 4))
+        );
+    }
+}
@@ -2,15 +2,25 @@
 //!
 //! 提供全局状态管理、健康检查和环境变量构造功能。
 
+use crate::api::settings::{
+    is_locked_n8n_env, join_n8n_path, save_settings, settings_lock, BinaryDataMode,
+    DatabaseSettings, ExecutionSettings, LogLevel, LogOutput, LogSettings, DEFAULT_N8N_PATH,
+};
 use crate::api::tunnel::{tunnel_config_lock, tunnel_running_lock, tunnel_url_lock};
 use crate::i18n;
-use crate::services::manager::PROCESS_MANAGER;
+use crate::services::http;
+use crate::services::manager::{self, PROCESS_MANAGER};
 use reqwest;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use futures_util::future::join_all;
 use std::sync::{Arc, LazyLock};
-use tauri::{AppHandle, Manager, Runtime};
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
 
 use super::constants::*;
+use super::database::database_envs;
+use super::execution::execution_envs;
+use super::memory;
 use super::error::{N8nCoreError, N8nResult};
 
 // --- 状态管理 ---
@@ -55,6 +65,14 @@ pub struct N8nEnvBuilder {
     tunnel_url: Option<String>,
     custom_domain: Option<String>,
     nodes_unlocked: bool,
+    database: Option<DatabaseSettings>,
+    execution: Option<ExecutionSettings>,
+    logging: Option<LogSettings>,
+    node_heap_limit_mb: Option<u64>,
+    n8n_path: String,
+    workflows_locked: bool,
+    binary_data_mode: Option<BinaryDataMode>,
+    custom_envs: BTreeMap<String, String>,
 }
 
 impl N8nEnvBuilder {
@@ -66,6 +84,14 @@ impl N8nEnvBuilder {
             tunnel_url: None,
             custom_domain: None,
             nodes_unlocked: false,
+            database: None,
+            execution: None,
+            logging: None,
+            node_heap_limit_mb: None,
+            n8n_path: DEFAULT_N8N_PATH.to_string(),
+            workflows_locked: false,
+            binary_data_mode: None,
+            custom_envs: BTreeMap::new(),
         }
     }
 
@@ -89,20 +115,110 @@ impl N8nEnvBuilder {
         self
     }
 
+    /// 设置数据库配置
+    pub fn with_database(mut self, database: DatabaseSettings) -> Self {
+        self.database = Some(database);
+        self
+    }
+
+    /// 设置执行模式配置
+    pub fn with_execution(mut self, execution: ExecutionSettings) -> Self {
+        self.execution = Some(execution);
+        self
+    }
+
+    /// 设置 Node 堆上限（MB，低内存时使用）
+    pub fn with_node_heap_limit(mut self, heap_limit_mb: Option<u64>) -> Self {
+        self.node_heap_limit_mb = heap_limit_mb;
+        self
+    }
+
+    /// 设置部署子路径
+    pub fn with_n8n_path(mut self, n8n_path: String) -> Self {
+        self.n8n_path = n8n_path;
+        self
+    }
+
+    /// 设置二进制数据存储方式
+    pub fn with_binary_data_mode(mut self, mode: BinaryDataMode) -> Self {
+        self.binary_data_mode = Some(mode);
+        self
+    }
+
+    /// 设置用户自定义环境变量（覆盖默认值，锁定的变量除外）
+    pub fn with_custom_envs(mut self, custom_envs: BTreeMap<String, String>) -> Self {
+        self.custom_envs = custom_envs;
+        self
+    }
+
+    /// 锁定工作流（关闭编辑器界面与 Public API）
+    pub fn with_workflows_locked(mut self, locked: bool) -> Self {
+        self.workflows_locked = locked;
+        self
+    }
+
+    /// 设置日志配置
+    pub fn with_logging(mut self, logging: LogSettings) -> Self {
+        self.logging = Some(logging);
+        self
+    }
+
     /// 构建环境变量映射
     pub fn build(self) -> HashMap<String, String> {
         let mut envs = HashMap::new();
 
+        envs.insert("N8N_PATH".to_string(), self.n8n_path.clone());
+
+        // 日志相关环境变量
+        if let Some(logging) = &self.logging {
+            envs.insert("N8N_LOG_LEVEL".to_string(), logging.level.as_str().to_string());
+            envs.insert("N8N_LOG_OUTPUT".to_string(), logging.output.as_str().to_string());
+        }
+
+        // 数据库相关环境变量
+        if let Some(database) = &self.database {
+            envs.extend(database_envs(database));
+        }
+
+        if let Some(heap_limit_mb) = self.node_heap_limit_mb {
+            envs.insert(
+                "NODE_OPTIONS".to_string(),
+                format!("--max-old-space-size={heap_limit_mb}"),
+            );
+        }
+
+        // 二进制数据存储方式（filesystem 模式下写入 `N8N_USER_FOLDER/.n8n/binaryData`）
+        if let Some(mode) = self.binary_data_mode {
+            envs.insert("N8N_DEFAULT_BINARY_DATA_MODE".to_string(), mode.as_str().to_string());
+        }
+
+        // 执行模式相关环境变量
+        if let Some(execution) = &self.execution {
+            envs.extend(execution_envs(execution));
+        }
+
         // 隧道相关环境变量
         if self.tunnel_enabled {
             if let Some(final_url) = self.determine_tunnel_url() {
                 envs.insert("WEBHOOK_URL".to_string(), final_url.clone());
                 envs.insert("N8N_WEBHOOK_URL".to_string(), final_url.clone());
-                envs.insert("N8N_EDITOR_BASE_URL".to_string(), final_url);
+                envs.insert(
+                    "N8N_EDITOR_BASE_URL".to_string(),
+                    join_n8n_path(&final_url, &self.n8n_path),
+                );
                 envs.insert("N8N_CORS_ALLOWED_ORIGINS".to_string(), "*".to_string());
             }
         }
 
+        // 用户自定义环境变量覆盖以上默认值；锁定的变量由应用管理，忽略用户设置
+        for (key, value) in &self.custom_envs {
+            if is_locked_n8n_env(key) {
+                tracing::warn!(key = %key, "忽略自定义环境变量：该变量由应用管理");
+                continue;
+            }
+            envs.insert(key.clone(), value.clone());
+        }
+
         // 节点解禁相关环境变量
         if self.nodes_unlocked {
             envs.insert("NODES_EXCLUDE".to_string(), "[]".to_string());
@@ -118,6 +234,15 @@ impl N8nEnvBuilder {
             );
         }
 
+        // 锁定工作流相关环境变量
+        if self.workflows_locked {
+            envs.extend(
+                LOCKED_WORKFLOW_ENVS
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string())),
+            );
+        }
+
         envs
     }
 
@@ -141,7 +266,29 @@ pub fn construct_n8n_envs() -> HashMap<String, String> {
     let tunnel_enabled = *tunnel_running_lock();
     let nodes_unlocked = N8N_STATE.nodes_unlocked();
 
-    let mut builder = N8nEnvBuilder::new().with_nodes_unlocked(nodes_unlocked);
+    let (database, execution, logging, n8n_path, lock_workflows, binary_data_mode, custom_envs) = {
+        let settings = settings_lock();
+        (
+            settings.database.clone(),
+            settings.execution.clone(),
+            settings.logging.clone(),
+            settings.n8n_path.clone(),
+            settings.lock_workflows,
+            settings.binary_data_mode,
+            settings.n8n_envs.clone(),
+        )
+    };
+
+    let mut builder = N8nEnvBuilder::new()
+        .with_nodes_unlocked(nodes_unlocked)
+        .with_database(database)
+        .with_execution(execution)
+        .with_logging(logging)
+        .with_node_heap_limit(memory::low_memory_heap_limit())
+        .with_n8n_path(n8n_path)
+        .with_workflows_locked(lock_workflows)
+        .with_binary_data_mode(binary_data_mode)
+        .with_custom_envs(custom_envs);
 
     if tunnel_enabled {
         let (tunnel_mode, custom_domain) = {
@@ -159,6 +306,27 @@ pub fn construct_n8n_envs() -> HashMap<String, String> {
     builder.build()
 }
 
+/// 脱敏后显示的占位值
+pub const REDACTED_VALUE: &str = "******";
+
+/// 变量名包含这些片段时视为敏感信息（代理地址可能包含认证信息）
+const SECRET_ENV_MARKERS: [&str; 6] = ["PASSWORD", "SECRET", "TOKEN", "ENCRYPTION_KEY", "API_KEY", "PROXY"];
+
+/// 判断变量名 / 字段名是否表示敏感信息（不区分大小写）
+pub fn is_secret_key(key: &str) -> bool {
+    let upper = key.to_uppercase();
+    SECRET_ENV_MARKERS.iter().any(|marker| upper.contains(marker))
+}
+
+/// 将敏感环境变量的值替换为占位符
+pub fn redact_secret_envs(envs: &mut BTreeMap<String, String>) {
+    for (key, value) in envs.iter_mut() {
+        if is_secret_key(key) {
+            *value = REDACTED_VALUE.to_string();
+        }
+    }
+}
+
 // --- 健康检查 ---
 
 /// n8n 健康检查器
@@ -167,43 +335,117 @@ pub struct N8nHealthChecker;
 impl N8nHealthChecker {
     /// 执行健康检查
     pub async fn check() -> N8nResult<String> {
-        let client = reqwest::Client::builder()
-            .timeout(HEALTH_CHECK_TIMEOUT)
-            .build()?;
+        Self::check_with(HEALTH_CHECK_RETRIES, HEALTH_CHECK_RETRY_DELAY, None).await
+    }
+
+    /// 执行健康检查：最多 `retries` 轮，每轮失败后等待 `delay`
+    ///
+    /// 探测 n8n 实际监听的端口（默认端口被占用时启动会改用其他端口），未启动时探测默认端口。
+    /// `paths` 为按优先级排列的探测路径（如 `/healthz`），未指定时使用 `default_health_paths`。
+    pub async fn check_with(
+        retries: usize,
+        delay: Duration,
+        paths: Option<Vec<String>>,
+    ) -> N8nResult<String> {
+        let port = PROCESS_MANAGER
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .port()
+            .unwrap_or(manager::N8N_SERVICE_PORT);
+        let paths = paths.unwrap_or_else(default_health_paths);
+        Self::check_endpoints(&Self::endpoints_for(&[manager::n8n_base_url(port)], &paths), retries, delay).await
+    }
+
+    /// 对指定端口执行健康检查（n8n 未使用默认端口时）
+    pub async fn check_port(port: u16) -> N8nResult<String> {
+        Self::check_endpoints(
+            &Self::endpoints_for(&[manager::n8n_base_url(port)], &default_health_paths()),
+            HEALTH_CHECK_RETRIES,
+            HEALTH_CHECK_RETRY_DELAY,
+        )
+        .await
+    }
+
+    /// 对指定端口的所有端点各探测一次（不重试），返回成功信息或最后一个端点的错误
+    pub async fn probe_port_once(port: u16) -> Result<String, String> {
+        let client = http::client().map_err(|e| e.to_string())?;
+        let endpoints = Self::endpoints_for(&[manager::n8n_base_url(port)], &default_health_paths());
+        Self::probe_endpoints(&client, &endpoints).await
+    }
+
+    /// 按路径优先级生成各基础地址下的健康检查端点
+    fn endpoints_for<S: AsRef<str>>(base_urls: &[S], paths: &[String]) -> Vec<String> {
+        paths
+            .iter()
+            .flat_map(|path| {
+                let path = format!("/{}", path.trim().trim_start_matches('/'));
+                base_urls
+                    .iter()
+                    .map(move |base| format!("{}{path}", base.as_ref().trim_end_matches('/')))
+            })
+            .collect()
+    }
+
+    /// 按轮探测给定端点，任意一个成功即视为健康（至少一轮）
+    async fn check_endpoints<S: AsRef<str>>(
+        endpoints: &[S],
+        retries: usize,
+        delay: Duration,
+    ) -> N8nResult<String> {
+        let client = http::client()?;
+        let retries = retries.max(1);
 
         let mut last_error_msg = i18n::t("n8n.state.no_check_run");
 
-        for retry in 0..HEALTH_CHECK_RETRIES {
-            // 每一轮重试，依次尝试所有端点
-            for endpoint in HEALTH_CHECK_ENDPOINTS {
-                match Self::attempt_ping(&client, endpoint).await {
-                    Ok(msg) => return Ok(msg), // 任意一个成功，立即返回
-                    Err(e) => {
-                        eprintln!(" 端点 {} 尝试失败: {}", endpoint, e);
-                        last_error_msg = format!("端点 {}: {}", endpoint, e);
-                        // 这里不 sleep，立即尝试下一个端点（Failover 逻辑）
-                    }
-                }
+        for retry in 0..retries {
+            match Self::probe_endpoints(&client, endpoints).await {
+                Ok(msg) => return Ok(msg),
+                Err(e) => last_error_msg = e,
             }
 
             // 一轮尝试（所有端点）全部失败后，才进行重试等待
-            if retry < HEALTH_CHECK_RETRIES - 1 {
-                println!(
-                    "本轮健康检查全灭，等待重试 ({}/{})",
-                    retry + 1,
-                    HEALTH_CHECK_RETRIES
-                );
-                tokio::time::sleep(HEALTH_CHECK_RETRY_DELAY).await;
+            if retry < retries - 1 {
+                tracing::warn!(attempt = retry + 1, retries, "本轮健康检查所有端点均失败，等待重试");
+                tokio::time::sleep(delay).await;
             }
         }
 
         Err(N8nCoreError::ServiceUnavailable(last_error_msg))
     }
 
-    /// 将单个请求的逻辑提取出来，消除嵌套
+    /// 并发探测所有端点一次，返回优先级最高（排在最前）的成功端点
+    ///
+    /// 端点无响应（半开连接）时由单次请求超时兜底，一轮耗时不超过 `HEALTH_CHECK_TIMEOUT`。
+    /// 全部失败时返回最后一个端点的错误。
+    async fn probe_endpoints<S: AsRef<str>>(
+        client: &reqwest::Client,
+        endpoints: &[S],
+    ) -> Result<String, String> {
+        let attempts = endpoints.iter().map(|endpoint| {
+            let endpoint = endpoint.as_ref();
+            async move {
+                Self::attempt_ping(client, endpoint).await.map_err(|e| {
+                    tracing::debug!(%endpoint, error = %e, "健康检查端点尝试失败");
+                    format!("端点 {}: {}", endpoint, e)
+                })
+            }
+        });
+
+        let mut last_error_msg = i18n::t("n8n.state.no_check_run");
+        for result in join_all(attempts).await {
+            match result {
+                Ok(msg) => return Ok(msg),
+                Err(e) => last_error_msg = e,
+            }
+        }
+        Err(last_error_msg)
+    }
+
+    /// 请求单个端点，成功时返回 `healthy - <端点> - <状态码>`，前端据端点区分就绪与存活
     async fn attempt_ping(client: &reqwest::Client, url: &str) -> Result<String, String> {
         let response = client
             .get(url)
+            .timeout(HEALTH_CHECK_TIMEOUT)
             .send()
             .await
             .map_err(|e| format!("{}: {e}", i18n::t("n8n.state.network_error")))?;
@@ -211,11 +453,7 @@ impl N8nHealthChecker {
         let status = response.status();
 
         if status.is_success() {
-            let body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| i18n::t("n8n.state.cannot_read_body"));
-            return Ok(format!("healthy - {status} - {body}"));
+            return Ok(format!("healthy - {url} - {status}"));
         }
 
         // 可以在这里细化错误分类，哪些是瞬态的，哪些是永久的
@@ -223,104 +461,114 @@ impl N8nHealthChecker {
     }
 }
 
+/// 默认健康检查路径（按优先级排列）
+///
+/// `rest/settings` 只有 n8n 完全初始化后才会成功（就绪）；`/healthz` 在服务开始监听后即返回 200（存活），
+/// 可能早于编辑器可用；最后是编辑器所在的部署路径。
+pub fn default_health_paths() -> Vec<String> {
+    let n8n_path = settings_lock().n8n_path.clone();
+    vec![
+        format!("{}/rest/settings", n8n_path.trim_end_matches('/')),
+        "/healthz".to_string(),
+        n8n_path,
+    ]
+}
+
 // --- 状态访问函数 ---
 
+/// 获取编辑器地址（基础地址拼接当前的 n8n 部署路径）
+pub fn editor_url(base_url: &str) -> String {
+    join_n8n_path(base_url, &settings_lock().n8n_path)
+}
+
 /// 获取节点解禁状态
 pub fn get_nodes_unlocked() -> N8nResult<bool> {
     Ok(N8N_STATE.nodes_unlocked())
 }
 
-/// 设置节点解禁状态并重启 n8n
+/// 设置节点解禁状态，n8n 正在运行时重启使其生效
 pub async fn set_nodes_unlocked<R: Runtime>(app: AppHandle<R>, enabled: bool) -> N8nResult<()> {
-    use crate::api::utils::emit_global_sync;
-    use crate::services::manager;
-    use std::fs;
-    use tokio::time::Duration;
-
     // 1. 更新全局状态
     N8N_STATE.set_nodes_unlocked(enabled);
-    println!("[DEBUG] 节点解禁状态已设置为: {enabled}");
-
-    // 2. 检查 n8n 是否正在运行
-    let is_running = {
-        let manager = PROCESS_MANAGER
-            .lock()
-            .map_err(|_| N8nCoreError::Process(i18n::t("n8n.state.process_manager_poisoned")))?;
-        manager.has_child()
-    };
-
-    println!("[DEBUG] n8n 运行状态: {is_running}");
-
-    if !is_running {
-        println!("[DEBUG] n8n 未运行，无需重启");
-        return Ok(());
-    }
-
-    // 3. 获取应用路径和二进制
-    let app_path = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| N8nCoreError::Path(format!("{}: {e}", i18n::t("cloudflared.path.app_data_dir_failed"))))?;
-
-    println!("[DEBUG] 应用路径: {}", app_path.display());
+    tracing::info!(enabled, "节点解禁状态已更新");
 
-    let n8n_bin = app_path.join("n8n-core/node_modules/n8n/bin/n8n");
-    println!("[DEBUG] n8n 二进制路径: {}", n8n_bin.display());
+    // 2. n8n 正在运行时重启以应用新设置
+    relaunch_if_running(app).await.map(|_| ())
+}
 
-    if !n8n_bin.exists() {
-        println!("[DEBUG] n8n 二进制文件不存在");
-        return Err(N8nCoreError::Installation(
-            i18n::t("n8n.binary_not_found"),
-        ));
+/// 设置 n8n 日志级别与输出目标，并重启 n8n 使其生效
+///
+/// 返回值表示是否已重启；n8n 未运行时仅保存设置，下次启动时生效。
+pub async fn set_log_level<R: Runtime>(
+    app: AppHandle<R>,
+    level: LogLevel,
+    output: Option<LogOutput>,
+) -> N8nResult<bool> {
+    {
+        let mut settings = settings_lock();
+        settings.logging.level = level;
+        if let Some(output) = output {
+            settings.logging.output = output;
+        }
     }
+    save_settings(&app).map_err(N8nCoreError::Config)?;
+    tracing::info!(level = level.as_str(), "n8n 日志级别已更新");
 
-    let runtime_dir = app_path.join("runtime");
-    let node_path = manager::get_node_binary_path(runtime_dir);
-    println!("[DEBUG] node 二进制路径: {}", node_path.display());
+    relaunch_if_running(app).await
+}
 
-    if !node_path.exists() {
-        println!("[DEBUG] node 二进制文件不存在");
-        return Err(N8nCoreError::Installation(
-            i18n::t("node.not_found"),
-        ));
-    }
+/// n8n 正在运行时重启以应用新设置（见 `relaunch_n8n`），返回是否执行了重启
+///
+/// 停止旧进程时最长等待 `shutdown_timeout_secs`，放到阻塞线程池中执行。
+async fn relaunch_if_running<R: Runtime>(app: AppHandle<R>) -> N8nResult<bool> {
+    use crate::api::utils::emit_global_sync;
 
-    let data_dir = app_path.join("n8n-data");
-    if !data_dir.exists() {
-        println!("[DEBUG] 创建数据目录: {}", data_dir.display());
-        fs::create_dir_all(&data_dir)?;
+    if !PROCESS_MANAGER.lock().unwrap_or_else(|p| p.into_inner()).has_child() {
+        return Ok(false);
     }
+    let relaunch_app = app.clone();
+    tokio::task::spawn_blocking(move || super::relaunch_n8n(relaunch_app))
+        .await
+        .map_err(|e| N8nCoreError::Process(e.to_string()))??;
+
+    // 广播全局同步事件，通知前端刷新 UI
+    emit_global_sync(&app).map_err(|e| N8nCoreError::Tauri(e.to_string()))?;
+    Ok(true)
+}
 
-    // 4. 构建新的环境变量
-    let additional_envs = construct_n8n_envs();
-    println!("[DEBUG] 构建的环境变量: {additional_envs:?}");
-
-    // 5. 物理重启：杀掉再重启
-    println!("[DEBUG] 正在重启 n8n 以应用节点解禁设置...");
-
-    // 5.1 杀掉现有进程
-    println!("[DEBUG] 杀掉现有进程...");
-    if let Ok(mut manager) = PROCESS_MANAGER.lock() {
-        manager.kill_child();
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_endpoints_follow_path_priority() {
+        let paths = vec!["/rest/settings".to_string(), "healthz".to_string()];
+        assert_eq!(
+            N8nHealthChecker::endpoints_for(&["http://localhost:5678/", "http://127.0.0.1:5678"], &paths),
+            vec![
+                "http://localhost:5678/rest/settings",
+                "http://127.0.0.1:5678/rest/settings",
+                "http://localhost:5678/healthz",
+                "http://127.0.0.1:5678/healthz",
+            ]
+        );
     }
 
-    // 5.2 等待 500ms 确保端口释放
-    println!("[DEBUG] 等待 500ms 确保端口释放...");
-    tokio::time::sleep(Duration::from_millis(500)).await;
-
-    // 5.3 重新启动 n8n
-    println!("[DEBUG] 重新启动 n8n...");
-    match manager::start_node(node_path, n8n_bin, data_dir, additional_envs) {
-        Ok(()) => {
-            println!("[DEBUG] n8n 已重启，节点解禁设置已应用");
-
-            // 广播全局同步事件，通知前端刷新 UI
-            emit_global_sync(&app).map_err(|e| N8nCoreError::Tauri(e.to_string()))?;
-            Ok(())
-        }
-        Err(e) => {
-            println!("[DEBUG] 重启 n8n 失败: {e}");
-            Err(N8nCoreError::Process(e))
-        }
+    #[test]
+    fn test_custom_envs_override_defaults_except_locked() {
+        let custom_envs = BTreeMap::from([
+            ("WEBHOOK_URL".to_string(), "https://hooks.example.com/".to_string()),
+            ("N8N_LOG_LEVEL".to_string(), "debug".to_string()),
+            ("NODES_EXCLUDE".to_string(), "[]".to_string()),
+        ]);
+        let envs = N8nEnvBuilder::new()
+            .with_logging(LogSettings::default())
+            .with_custom_envs(custom_envs)
+            .build();
+
+        assert_eq!(envs.get("WEBHOOK_URL").map(String::as_str), Some("https://hooks.example.com/"));
+        assert_eq!(envs.get("N8N_LOG_LEVEL").map(String::as_str), Some("debug"));
+        assert_eq!(envs.get("NODES_EXCLUDE").map(String::as_str), Some(DEFAULT_BLOCKED_NODES));
     }
 }
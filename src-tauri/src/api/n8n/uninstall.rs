@@ -0,0 +1,134 @@
+//! n8n 卸载
+//!
+//! 删除数据基础目录中安装的 Node 运行时、n8n 核心与缓存的资源包，便于干净地重新安装。
+//! `n8n-data`（工作流、凭据、SQLite 数据库）默认保留，只有明确要求时才一并删除，
+//! 自定义位置的数据目录只删除其中的 `.n8n`；
+//! 设置、日志、`config/` 中保存的凭据加密密钥与 `offline/` 中预置的离线资源包不受影响。
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::data_dir::dir_stats;
+use super::error::N8nResult;
+use super::install_state::INSTALL_STATE_FILENAME;
+use super::paths::{n8n_data_dir, N8N_DATA_DIRNAME, N8N_USER_DIRNAME};
+use super::versions::{ACTIVE_CORE_FILENAME, CORES_DIRNAME, LEGACY_CORE_DIRNAME};
+
+/// 运行时目录名
+const RUNTIME_DIRNAME: &str = "runtime";
+
+/// 缓存资源包的文件名前缀（n8n 核心包与 Node 运行时）
+const CACHED_ARCHIVE_PREFIXES: [&str; 2] = ["n8n-core-", "node-v"];
+
+/// 卸载结果
+#[derive(Debug, Clone, Serialize)]
+pub struct UninstallSummary {
+    /// 已删除的文件与目录
+    pub removed: Vec<PathBuf>,
+    /// 释放的字节数
+    pub freed_bytes: u64,
+    /// 是否删除了 n8n 数据目录
+    pub user_data_removed: bool,
+}
+
+/// 删除 `base_dir` 中已安装的运行时、核心与缓存资源包（调用方需先停止 n8n）
+///
+/// `keep_user_data` 为 `false` 时同时删除 n8n 数据（见 `user_data_target`）。
+pub fn uninstall(base_dir: &Path, keep_user_data: bool) -> N8nResult<UninstallSummary> {
+    let mut targets: Vec<PathBuf> = [
+        RUNTIME_DIRNAME,
+        LEGACY_CORE_DIRNAME,
+        CORES_DIRNAME,
+        ACTIVE_CORE_FILENAME,
+        INSTALL_STATE_FILENAME,
+    ]
+    .iter()
+    .map(|name| base_dir.join(name))
+    .collect();
+    targets.extend(cached_archives(base_dir));
+    if !keep_user_data {
+        targets.push(user_data_target(base_dir));
+    }
+
+    let mut summary = UninstallSummary {
+        removed: Vec::new(),
+        freed_bytes: 0,
+        user_data_removed: false,
+    };
+    for target in targets.into_iter().filter(|path| path.exists()) {
+        let bytes = if target.is_dir() {
+            let (_, bytes) = dir_stats(&target)?;
+            fs::remove_dir_all(&target)?;
+            bytes
+        } else {
+            let bytes = fs::metadata(&target)?.len();
+            fs::remove_file(&target)?;
+            bytes
+        };
+        tracing::info!(path = %target.display(), bytes, "已删除");
+        summary.freed_bytes += bytes;
+        summary.removed.push(target);
+    }
+    summary.user_data_removed = !keep_user_data;
+
+    tracing::info!(freed_bytes = summary.freed_bytes, "卸载完成");
+    Ok(summary)
+}
+
+/// 要删除的 n8n 数据：默认数据目录整体删除；自定义数据目录可能与其他文件共用，只删除其中的 `.n8n`
+fn user_data_target(base_dir: &Path) -> PathBuf {
+    let data_dir = n8n_data_dir(base_dir);
+    if data_dir == base_dir.join(N8N_DATA_DIRNAME) {
+        data_dir
+    } else {
+        data_dir.join(N8N_USER_DIRNAME)
+    }
+}
+
+/// 数据基础目录中缓存的资源包（含下载中断留下的 `.part` 文件）
+fn cached_archives(base_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(base_dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| CACHED_ARCHIVE_PREFIXES.iter().any(|prefix| name.starts_with(prefix)))
+        })
+        .collect()
+}
+
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+
+    #[test]
+    fn test_uninstall_keeps_user_data() {
+        let base = temp_dir().join("n8n_uninstall_test");
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(base.join("runtime/bin")).expect("创建运行时目录失败");
+        fs::create_dir_all(base.join("n8n-data/.n8n")).expect("创建数据目录失败");
+        fs::write(base.join("runtime/bin/node"), "node").expect("写入运行时失败");
+        fs::write(base.join("n8n-core-macos.zip.part"), "zip").expect("写入资源包失败");
+        fs::write(base.join("n8n-data/.n8n/database.sqlite"), "db").expect("写入数据库失败");
+        fs::write(base.join("config.json"), "{}").expect("写入设置失败");
+
+        let summary = uninstall(&base, true).expect("卸载失败");
+
+        assert_eq!(summary.removed.len(), 2);
+        assert_eq!(summary.freed_bytes, 7);
+        assert!(!summary.user_data_removed);
+        assert!(base.join("n8n-data/.n8n/database.sqlite").exists());
+        assert!(base.join("config.json").exists());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+}
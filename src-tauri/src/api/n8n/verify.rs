@@ -0,0 +1,295 @@
+//! n8n 安装完整性校验模块
+//!
+//! 只读地检查现有安装：将缓存的核心包与安装时记录的 SHA256 比对、比对解压目录与压缩包清单、确认运行时可执行。
+//! 与修复不同，这里不会修改任何文件，供用户在决定是否修复前进行诊断。
+//!
+//! 每项检查标注所属组件（运行时 / 核心 / 核心包），前端可据此只重新安装出问题的组件，
+//! 而不是整体重装。
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Runtime};
+use zip::ZipArchive;
+
+use super::entrypoint::resolve_n8n_entrypoint;
+use super::error::N8nResult;
+use super::install_state::{InstallStage, InstallState};
+use super::installer::{calculate_file_sha256, N8nInstaller};
+use super::versions;
+use crate::i18n;
+use crate::services::manager;
+
+/// 报告中每项检查最多列出的缺失 / 损坏路径数
+const MAX_REPORTED_PATHS: usize = 50;
+
+/// n8n 运行依赖的核心包（缺失时 n8n 启动后才会报出难以理解的模块加载错误）
+const CORE_PACKAGES: [&str; 4] = ["n8n-core", "n8n-workflow", "n8n-nodes-base", "n8n-editor-ui"];
+
+/// 检查项所属的组件（对应的修复方式）
+#[derive(Clone, Copy, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VerifyComponent {
+    /// Node 运行时（重新执行 `setup_runtime`）
+    Runtime,
+    /// 解压后的 n8n 核心（重新执行 `setup_n8n`）
+    Core,
+    /// 缓存的核心包（删除后重新下载）
+    CoreArchive,
+}
+
+/// 单项校验结果
+#[derive(Clone, Serialize, Debug)]
+pub struct VerifyCheck {
+    /// 检查项标识
+    pub id: String,
+    /// 所属组件
+    pub component: VerifyComponent,
+    /// 是否通过
+    pub passed: bool,
+    /// 结果说明
+    pub message: String,
+    /// 缺失或损坏的路径（最多 `MAX_REPORTED_PATHS` 个）
+    pub paths: Vec<String>,
+}
+
+impl VerifyCheck {
+    fn new(id: &str, component: VerifyComponent, passed: bool, message: String) -> Self {
+        Self {
+            id: id.to_string(),
+            component,
+            passed,
+            message,
+            paths: Vec::new(),
+        }
+    }
+
+    fn with_paths(mut self, paths: Vec<String>) -> Self {
+        self.paths = paths.into_iter().take(MAX_REPORTED_PATHS).collect();
+        self
+    }
+}
+
+/// 安装校验报告
+#[derive(Clone, Serialize, Debug)]
+pub struct VerifyReport {
+    /// 所有检查是否均通过
+    pub ok: bool,
+    /// 需要修复的组件（去重，按检查顺序）
+    pub broken_components: Vec<VerifyComponent>,
+    /// 各项检查结果
+    pub checks: Vec<VerifyCheck>,
+}
+
+/// 执行安装完整性校验
+pub async fn verify_installation<R: Runtime>(app: &AppHandle<R>) -> N8nResult<VerifyReport> {
+    let installer = N8nInstaller::new(app)?;
+    let runtime_dir = installer.app_data_dir().join("runtime");
+    let archive = inspect_active_archive(&installer);
+
+    let checks = vec![
+        check_key_paths(installer.app_data_dir(), &runtime_dir),
+        check_archive_hash(&archive),
+        check_core_tree(&installer, &archive),
+        check_runtime(&runtime_dir),
+    ];
+
+    let mut broken_components = Vec::new();
+    for check in checks.iter().filter(|check| !check.passed) {
+        if !broken_components.contains(&check.component) {
+            broken_components.push(check.component);
+        }
+    }
+
+    Ok(VerifyReport {
+        ok: broken_components.is_empty(),
+        broken_components,
+        checks,
+    })
+}
+
+/// 缓存的核心包与启用版本安装记录的比对结果
+enum ActiveArchive {
+    /// 缓存的核心包就是启用版本安装时使用的资源包，可作为解压目录的清单
+    Matches(PathBuf),
+    /// 内容与安装时记录的 SHA256 不一致（已损坏或被替换）
+    Mismatch {
+        path: PathBuf,
+        recorded: String,
+        actual: String,
+    },
+    /// 无法比对，附跳过原因（没有缓存的核心包，或缓存与安装记录属于其他版本）
+    Skipped(String),
+    /// 读取核心包失败
+    Unreadable(String),
+}
+
+/// 按安装状态中记录的启用版本 SHA256 检查缓存的核心包
+///
+/// 缓存的核心包只对应最近一次安装；切换到其他已安装版本后它不再描述启用的核心，不能用于比对。
+fn inspect_active_archive(installer: &N8nInstaller) -> ActiveArchive {
+    let zip_path = installer.zip_path();
+    if !zip_path.exists() {
+        return ActiveArchive::Skipped(i18n::t("verify.archive_missing"));
+    }
+
+    let app_data_dir = installer.app_data_dir();
+    let active = versions::active_version(app_data_dir);
+    let recorded = InstallState::load(app_data_dir)
+        .filter(|state| state.stage == InstallStage::Completed)
+        .filter(|state| active.is_some() && state.version == active)
+        .and_then(|state| state.verification)
+        .map(|verification| verification.sha256);
+    let Some(recorded) = recorded else {
+        return ActiveArchive::Skipped(i18n::t("verify.archive_other_version"));
+    };
+
+    match calculate_file_sha256(&zip_path) {
+        Ok(actual) if actual.eq_ignore_ascii_case(&recorded) => ActiveArchive::Matches(zip_path),
+        Ok(actual) => ActiveArchive::Mismatch {
+            path: zip_path,
+            recorded,
+            actual,
+        },
+        Err(e) => ActiveArchive::Unreadable(e.to_string()),
+    }
+}
+
+/// 检查关键路径是否存在：Node 可执行文件、n8n 入口、n8n 的 `package.json` 与核心依赖包
+fn check_key_paths(app_data_dir: &Path, runtime_dir: &Path) -> VerifyCheck {
+    const ID: &str = "key_paths";
+
+    let node_path = manager::get_node_binary_path(runtime_dir.to_path_buf());
+    let mut missing = Vec::new();
+    if !node_path.is_file() {
+        missing.push((VerifyComponent::Runtime, node_path));
+    }
+
+    let core_dir = versions::core_dir(app_data_dir);
+    let package_dir = versions::package_dir(&core_dir);
+    if resolve_n8n_entrypoint(app_data_dir).is_err() {
+        missing.push((VerifyComponent::Core, package_dir.join("bin")));
+    }
+    let package_json = package_dir.join("package.json");
+    if !package_json.is_file() {
+        missing.push((VerifyComponent::Core, package_json));
+    }
+    // 依赖包可能被提升到核心目录的 node_modules，也可能嵌套在 n8n 包内
+    for package in CORE_PACKAGES {
+        let candidates: [PathBuf; 2] = [
+            core_dir.join("node_modules").join(package),
+            package_dir.join("node_modules").join(package),
+        ];
+        if !candidates.iter().any(|dir| dir.join("package.json").is_file()) {
+            missing.push((VerifyComponent::Core, candidates[0].clone()));
+        }
+    }
+
+    if missing.is_empty() {
+        return VerifyCheck::new(ID, VerifyComponent::Core, true, i18n::t("verify.key_paths_ok"));
+    }
+    // 运行时与核心同时缺失时归为运行时，修复运行时后再次校验即可定位核心问题
+    let component = missing[0].0;
+    let paths = missing
+        .into_iter()
+        .map(|(_, path)| path.display().to_string())
+        .collect();
+    VerifyCheck::new(ID, component, false, i18n::t("verify.key_paths_missing")).with_paths(paths)
+}
+
+/// 缓存的核心包是否与启用版本安装时记录的 SHA256 一致（无法比对时跳过，不判定为失败）
+fn check_archive_hash(archive: &ActiveArchive) -> VerifyCheck {
+    const ID: &str = "core_archive_hash";
+    const COMPONENT: VerifyComponent = VerifyComponent::CoreArchive;
+
+    match archive {
+        ActiveArchive::Matches(_) => VerifyCheck::new(ID, COMPONENT, true, i18n::t("verify.archive_hash_ok")),
+        ActiveArchive::Mismatch {
+            path,
+            recorded,
+            actual,
+        } => VerifyCheck::new(
+            ID,
+            COMPONENT,
+            false,
+            format!("{} ({recorded} / {actual})", i18n::t("verify.archive_hash_mismatch")),
+        )
+        .with_paths(vec![path.display().to_string()]),
+        ActiveArchive::Skipped(reason) => VerifyCheck::new(ID, COMPONENT, true, reason.clone()),
+        ActiveArchive::Unreadable(error) => VerifyCheck::new(ID, COMPONENT, false, error.clone()),
+    }
+}
+
+/// 以启用版本的核心包的中央目录为清单，检查解压目录中的文件是否齐全且大小一致
+fn check_core_tree(installer: &N8nInstaller, archive: &ActiveArchive) -> VerifyCheck {
+    const ID: &str = "core_tree";
+    const COMPONENT: VerifyComponent = VerifyComponent::Core;
+
+    if !installer.has_core_binary() {
+        return VerifyCheck::new(ID, COMPONENT, false, i18n::t("n8n.binary_not_found"));
+    }
+
+    let ActiveArchive::Matches(zip_path) = archive else {
+        // 没有属于启用版本的清单可比对，仅确认核心可执行文件存在
+        return VerifyCheck::new(ID, COMPONENT, true, i18n::t("verify.tree_manifest_missing"));
+    };
+
+    match find_tree_mismatches(zip_path, &versions::core_dir(installer.app_data_dir())) {
+        Ok(mismatches) if mismatches.is_empty() => {
+            VerifyCheck::new(ID, COMPONENT, true, i18n::t("verify.tree_ok"))
+        }
+        Ok(mismatches) => VerifyCheck::new(
+            ID,
+            COMPONENT,
+            false,
+            format!(
+                "{}: {} ({})",
+                i18n::t("verify.tree_mismatch"),
+                mismatches.len(),
+                mismatches.iter().take(5).cloned().collect::<Vec<_>>().join(", ")
+            ),
+        )
+        .with_paths(mismatches),
+        Err(e) => VerifyCheck::new(ID, COMPONENT, false, e.to_string()),
+    }
+}
+
+/// 列出缺失或大小不一致的文件
+fn find_tree_mismatches(zip_path: &Path, extract_dir: &Path) -> N8nResult<Vec<String>> {
+    let mut archive = ZipArchive::new(fs::File::open(zip_path)?)?;
+    let mut mismatches = Vec::new();
+
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(relative_path) = entry.enclosed_name() else {
+            continue;
+        };
+
+        let is_intact = fs::metadata(extract_dir.join(relative_path))
+            .is_ok_and(|metadata| metadata.len() == entry.size());
+        if !is_intact {
+            mismatches.push(entry.name().to_string());
+        }
+    }
+
+    Ok(mismatches)
+}
+
+/// 确认运行时 `node --version` 可以正常执行
+fn check_runtime(runtime_dir: &Path) -> VerifyCheck {
+    const ID: &str = "runtime_node";
+    const COMPONENT: VerifyComponent = VerifyComponent::Runtime;
+
+    match manager::get_runtime_version(runtime_dir) {
+        Some(version) => VerifyCheck::new(
+            ID,
+            COMPONENT,
+            true,
+            format!("{}: {version}", i18n::t("verify.runtime_ok")),
+        ),
+        None => VerifyCheck::new(ID, COMPONENT, false, i18n::t("preflight.runtime_missing")),
+    }
+}
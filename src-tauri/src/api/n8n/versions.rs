@@ -0,0 +1,238 @@
+//! n8n 核心版本管理模块
+//!
+//! 每次安装的 n8n 核心保存在 `cores/<版本>/` 下，数据基础目录中的 `active-core` 指针文件记录当前启用的版本。
+//! 切换版本只需停止 n8n 并改写指针，无需重新下载，也不会触碰 `n8n-data`。
+//! 旧版本安装（只有 `n8n-core/`、没有指针文件）继续按原路径使用。
+
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::entrypoint::resolve_entrypoint_in;
+use super::error::{N8nCoreError, N8nResult};
+use crate::i18n;
+use crate::services::downloader;
+
+/// 旧版本的核心目录名（也用作安装时的解压暂存目录）
+pub const LEGACY_CORE_DIRNAME: &str = "n8n-core";
+
+/// 多版本核心的存放目录名
+pub const CORES_DIRNAME: &str = "cores";
+
+/// 记录当前启用版本的指针文件名
+pub const ACTIVE_CORE_FILENAME: &str = "active-core";
+
+/// n8n 包在核心目录中的相对路径
+const N8N_PACKAGE_SUBDIR: &str = "node_modules/n8n";
+
+/// 已安装的 n8n 核心版本
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledVersion {
+    pub version: String,
+    pub active: bool,
+}
+
+/// 读取指针文件记录的启用版本
+pub fn active_version(base_dir: &Path) -> Option<String> {
+    let content = fs::read_to_string(base_dir.join(ACTIVE_CORE_FILENAME)).ok()?;
+    let version = content.trim();
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+/// 获取当前启用的核心目录（指针无效时回退到旧的 `n8n-core/`）
+pub fn core_dir(base_dir: &Path) -> PathBuf {
+    active_version(base_dir)
+        .map(|version| base_dir.join(CORES_DIRNAME).join(version))
+        .filter(|dir| dir.is_dir())
+        .unwrap_or_else(|| base_dir.join(LEGACY_CORE_DIRNAME))
+}
+
+/// 核心目录中 n8n 包的位置
+pub fn package_dir(core_dir: &Path) -> PathBuf {
+    core_dir.join(N8N_PACKAGE_SUBDIR)
+}
+
+/// 当前启用的核心中 n8n 包的版本号
+pub fn current_version(base_dir: &Path) -> Option<String> {
+    read_package_version(&package_dir(&core_dir(base_dir)))
+}
+
+/// 列出 `cores/` 中已安装的版本（按版本名排序）
+pub fn installed_versions(base_dir: &Path) -> Vec<InstalledVersion> {
+    let active = active_version(base_dir);
+    let mut versions: Vec<InstalledVersion> = fs::read_dir(base_dir.join(CORES_DIRNAME))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().to_str().map(ToString::to_string))
+                .map(|version| InstalledVersion {
+                    active: active.as_deref() == Some(version.as_str()),
+                    version,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    versions.sort_by(|a, b| a.version.cmp(&b.version));
+    versions
+}
+
+/// 将解压好的核心从暂存目录移入 `cores/<版本>/` 并设为启用版本，返回版本号
+pub fn store_installed_core(base_dir: &Path, staging_dir: &Path) -> N8nResult<String> {
+    let version = read_package_version(&package_dir(staging_dir))
+        .ok_or_else(|| N8nCoreError::Installation(i18n::t("n8n.version.unknown")))?;
+    validate_version_name(&version)?;
+
+    // 切换前先让关键文件落盘，避免断电后指针指向内容不完整的目录
+    sync_key_files(staging_dir)?;
+
+    let cores_dir = base_dir.join(CORES_DIRNAME);
+    let target = cores_dir.join(&version);
+    if target.exists() {
+        fs::remove_dir_all(&target)?;
+    }
+    fs::create_dir_all(&cores_dir)?;
+    fs::rename(staging_dir, &target)?;
+    downloader::sync_dir(&cores_dir)?;
+
+    write_active_version(base_dir, &version)?;
+    Ok(version)
+}
+
+/// 切换启用的版本（仅改写指针，调用方负责先停止 n8n）
+pub fn activate_version(base_dir: &Path, version: &str) -> N8nResult<()> {
+    validate_version_name(version)?;
+
+    let dir = base_dir.join(CORES_DIRNAME).join(version);
+    if !package_dir(&dir).is_dir() {
+        return Err(N8nCoreError::Installation(format!(
+            "{}: {version}",
+            i18n::t("n8n.version.not_installed")
+        )));
+    }
+
+    write_active_version(base_dir, version)
+}
+
+/// 写入指针文件（先写临时文件并落盘再重命名，避免中途失败或断电留下半截内容）
+fn write_active_version(base_dir: &Path, version: &str) -> N8nResult<()> {
+    let pointer = base_dir.join(ACTIVE_CORE_FILENAME);
+    let temp = pointer.with_extension("tmp");
+    fs::write(&temp, version)?;
+    downloader::sync_file(&temp)?;
+    fs::rename(&temp, &pointer)?;
+    downloader::sync_dir(base_dir)?;
+    Ok(())
+}
+
+/// 将核心目录中的关键文件（`package.json` 与入口文件）刷写到磁盘
+fn sync_key_files(core_dir: &Path) -> N8nResult<()> {
+    downloader::sync_file(&package_dir(core_dir).join("package.json"))?;
+    let entrypoint = resolve_entrypoint_in(core_dir)?;
+    downloader::sync_file(&entrypoint)?;
+    Ok(())
+}
+
+/// 版本名只能作为单层目录名使用
+fn validate_version_name(version: &str) -> N8nResult<()> {
+    let is_valid = !version.is_empty()
+        && version != "."
+        && version != ".."
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+'));
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(N8nCoreError::Config(format!(
+            "{}: {version}",
+            i18n::t("n8n.version.invalid")
+        )))
+    }
+}
+
+/// `candidate` 是否比 `current` 更新
+///
+/// 两者均按点分数字比较，允许 `v` 前缀与 `n8n@` 形式的发布标签，忽略预发布后缀；
+/// 任一无法解析时视为没有更新。
+pub fn is_newer_version(candidate: &str, current: &str) -> bool {
+    match (parse_version(candidate), parse_version(current)) {
+        (Some(candidate), Some(current)) => candidate > current,
+        _ => false,
+    }
+}
+
+/// 解析版本号为数字段（如 `v1.70.2-beta` -> `[1, 70, 2]`）
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let version = version.trim();
+    let version = version.rsplit('@').next().unwrap_or(version);
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let release = version.split(['-', '+']).next().unwrap_or(version);
+    release.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// 读取 n8n 包 `package.json` 中的版本号
+fn read_package_version(package_dir: &Path) -> Option<String> {
+    let content = fs::read_to_string(package_dir.join("package.json")).ok()?;
+    let package: Value = serde_json::from_str(&content).ok()?;
+    package["version"].as_str().map(ToString::to_string)
+}
+
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+
+    fn write_core(dir: &Path, version: &str) {
+        let package = package_dir(dir);
+        fs::create_dir_all(&package).expect("创建 n8n 包目录失败");
+        fs::write(
+            package.join("package.json"),
+            format!(r#"{{"name":"n8n","version":"{version}"}}"#),
+        )
+        .expect("写入 package.json 失败");
+        fs::create_dir_all(package.join("bin")).expect("创建 bin 目录失败");
+        fs::write(package.join("bin/n8n"), "").expect("写入入口文件失败");
+    }
+
+    #[test]
+    fn test_store_and_activate_versions() {
+        let base = temp_dir().join("n8n_core_versions_test");
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).expect("创建测试目录失败");
+
+        // 没有指针时使用旧目录
+        assert_eq!(core_dir(&base), base.join(LEGACY_CORE_DIRNAME));
+
+        for version in ["1.0.0", "1.1.0"] {
+            let staging = base.join(LEGACY_CORE_DIRNAME);
+            write_core(&staging, version);
+            assert_eq!(store_installed_core(&base, &staging).expect("保存核心失败"), version);
+        }
+        assert_eq!(core_dir(&base), base.join(CORES_DIRNAME).join("1.1.0"));
+
+        activate_version(&base, "1.0.0").expect("切换版本失败");
+        assert_eq!(core_dir(&base), base.join(CORES_DIRNAME).join("1.0.0"));
+        assert!(activate_version(&base, "9.9.9").is_err());
+        assert!(activate_version(&base, "../n8n-data").is_err());
+
+        let versions = installed_versions(&base);
+        assert_eq!(versions.len(), 2);
+        assert!(versions.iter().any(|v| v.version == "1.0.0" && v.active));
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_is_newer_version() {
+        assert!(is_newer_version("v1.70.0", "1.69.2"));
+        assert!(is_newer_version("n8n@1.10.0", "1.9.9"));
+        assert!(!is_newer_version("1.70.0", "1.70.0"));
+        assert!(!is_newer_version("1.70.0-beta.1", "1.70.0"));
+        assert!(!is_newer_version("latest", "1.0.0"));
+    }
+}
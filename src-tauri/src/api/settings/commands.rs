@@ -0,0 +1,186 @@
+use crate::api::n8n::data_dir::validate_custom_data_dir;
+use crate::api::n8n::paths::base_dir;
+use crate::api::n8n::N8nCoreError;
+use crate::services::{http, keychain};
+use crate::services::logging::{self, AppLogLevel};
+use crate::services::manager::PROCESS_MANAGER;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use super::config::{backup_settings, move_database_password_to_keychain, save_settings};
+use super::models::{validate_n8n_env, AppSettings, DownloadMirror, FieldError};
+use super::state::settings_lock;
+
+/// 设置变化事件（载荷为新的设置）
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+/// 重置设置的结果
+#[derive(Clone, Serialize, Debug)]
+pub struct SettingsReset {
+    pub settings: AppSettings,
+    /// 旧设置文件的备份位置（原本没有设置文件时为 None）
+    pub backup_path: Option<PathBuf>,
+    /// n8n 正在运行，需重启后影响环境变量的设置才会生效
+    pub restart_required: bool,
+}
+
+/// 获取当前应用设置
+pub fn get_settings<R: Runtime>(_app: AppHandle<R>) -> Result<AppSettings, String> {
+    Ok(settings_lock().clone())
+}
+
+/// 更新并持久化应用设置
+///
+/// 所有字段校验通过后才会生效并保存；否则返回每个不合法字段的错误，供前端逐项标注。
+/// 修改了 n8n 数据目录时按 `set_data_dir` 的规则校验（见 `validate_custom_data_dir`）。
+pub fn update_settings<R: Runtime>(
+    app: AppHandle<R>,
+    mut settings: AppSettings,
+) -> Result<(), Vec<FieldError>> {
+    settings.validate()?;
+    let current_data_dir = settings_lock().n8n_data_dir.clone();
+    if settings.n8n_data_dir != current_data_dir {
+        if let Some(path) = &settings.n8n_data_dir {
+            let invalid = |error: N8nCoreError| {
+                let message = match error {
+                    N8nCoreError::Config(message) => message,
+                    other => other.to_string(),
+                };
+                vec![FieldError::new("n8n_data_dir", message)]
+            };
+            let app_path = base_dir(&app).map_err(invalid)?;
+            settings.n8n_data_dir = Some(validate_custom_data_dir(&app_path, path).map_err(invalid)?);
+        }
+    }
+    move_database_password_to_keychain(&mut settings);
+    logging::set_level(settings.app_log_level);
+    *settings_lock() = settings;
+
+    // User-Agent 等网络设置可能已变化，让共享客户端按新设置重建
+    http::invalidate_client();
+    save_settings(&app).map_err(|error| vec![FieldError::new("", error)])
+}
+
+/// 修改应用日志级别（立即生效并保存）
+pub fn set_app_log_level<R: Runtime>(app: AppHandle<R>, level: AppLogLevel) -> Result<(), String> {
+    logging::set_level(level);
+    settings_lock().app_log_level = level;
+    save_settings(&app)
+}
+
+/// 修改下载源（下次下载 Node.js 运行时与 n8n 核心包时生效）
+pub fn set_download_mirror<R: Runtime>(app: AppHandle<R>, mirror: DownloadMirror) -> Result<(), String> {
+    let mut settings = settings_lock().clone();
+    settings.download_mirror = mirror.clone();
+    // 只校验下载源本身，其他字段的问题不影响此次修改
+    let invalid = settings
+        .validate()
+        .err()
+        .and_then(|errors| errors.into_iter().find(|error| error.field == "download_mirror"));
+    if let Some(error) = invalid {
+        return Err(error.error);
+    }
+
+    settings_lock().download_mirror = mirror;
+    save_settings(&app)
+}
+
+/// 替换自定义 n8n 环境变量（下次启动 n8n 时生效）
+///
+/// 变量名不合法、属于锁定变量（见 `LOCKED_N8N_ENV_KEYS`）或值含 NUL 时整体拒绝，不做部分保存。
+pub fn set_n8n_env<R: Runtime>(app: AppHandle<R>, vars: HashMap<String, String>) -> Result<(), String> {
+    let vars: BTreeMap<String, String> = vars
+        .into_iter()
+        .map(|(key, value)| (key.trim().to_string(), value))
+        .collect();
+    if let Some(error) = vars
+        .iter()
+        .find_map(|(key, value)| validate_n8n_env(key, value).err())
+    {
+        return Err(error);
+    }
+
+    let keys: Vec<&String> = vars.keys().collect();
+    tracing::info!(?keys, "已更新自定义 n8n 环境变量");
+    settings_lock().n8n_envs = vars;
+    save_settings(&app)
+}
+
+/// 修改下载使用的 HTTP / HTTPS 代理（为空时恢复使用环境变量中的代理）
+pub fn set_http_proxy<R: Runtime>(app: AppHandle<R>, url: Option<String>) -> Result<(), String> {
+    let mut settings = settings_lock().clone();
+    settings.http_proxy = url.map(|url| url.trim().to_string()).filter(|url| !url.is_empty());
+    // 只校验代理地址本身，其他字段的问题不影响此次修改
+    let invalid = settings
+        .validate()
+        .err()
+        .and_then(|errors| errors.into_iter().find(|error| error.field == "http_proxy"));
+    if let Some(error) = invalid {
+        return Err(error.error);
+    }
+
+    let configured = settings.http_proxy.is_some();
+    settings_lock().http_proxy = settings.http_proxy;
+    save_settings(&app)?;
+    http::invalidate_client();
+    // 代理地址可能包含认证信息，日志中只记录是否配置
+    tracing::info!(configured, "已更新下载代理");
+    Ok(())
+}
+
+/// 保存 GitHub 令牌到系统钥匙串（为空时删除，恢复匿名访问 GitHub API）
+pub fn set_github_token(token: Option<String>) -> Result<(), String> {
+    keychain::set_github_token(token.as_deref())
+}
+
+/// 是否已配置 GitHub 令牌（不返回令牌本身）
+pub fn has_github_token() -> bool {
+    keychain::github_token().is_some()
+}
+
+/// 保存 PostgreSQL 密码到系统钥匙串（为空时删除）
+pub fn set_postgres_password(password: Option<String>) -> Result<(), String> {
+    keychain::set_postgres_password(password.as_deref())
+}
+
+/// 是否已配置 PostgreSQL 密码（不返回密码本身）
+pub fn has_postgres_password() -> bool {
+    keychain::postgres_password().is_some() || !settings_lock().database.password.is_empty()
+}
+
+/// 将应用设置恢复为默认值（先备份旧设置文件，不影响 n8n 数据）
+///
+/// 数据目录、数据库与自定义 n8n 环境变量保持不变：它们决定 n8n 使用哪份数据（以及如何解密凭据），
+/// 恢复为默认值会让 n8n 打开另一份（通常是空的）数据，看起来像工作流与凭据丢失。
+pub fn reset_settings<R: Runtime>(app: AppHandle<R>) -> Result<SettingsReset, String> {
+    let backup_path = backup_settings(&app)?;
+    let settings = {
+        let current = settings_lock();
+        AppSettings {
+            n8n_data_dir: current.n8n_data_dir.clone(),
+            database: current.database.clone(),
+            n8n_envs: current.n8n_envs.clone(),
+            ..AppSettings::default()
+        }
+    };
+    logging::set_level(settings.app_log_level);
+    *settings_lock() = settings.clone();
+
+    http::invalidate_client();
+    save_settings(&app)?;
+    tracing::info!("设置已恢复为默认值");
+
+    let restart_required = PROCESS_MANAGER
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .has_child();
+    let _ = app.emit(SETTINGS_CHANGED_EVENT, &settings);
+
+    Ok(SettingsReset {
+        settings,
+        backup_path,
+        restart_required,
+    })
+}
@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+use crate::api::n8n::config_dir;
+use crate::services::keychain;
+use tauri::{AppHandle, Runtime};
+
+use super::models::{validate_n8n_path, AppSettings, DEFAULT_N8N_PATH};
+use super::state::settings_lock;
+
+/// 设置文件名
+pub const SETTINGS_FILENAME: &str = "config.json";
+
+/// 重置设置前的备份文件名
+pub const SETTINGS_BACKUP_FILENAME: &str = "config.json.bak";
+
+/// 获取设置文件路径
+pub fn settings_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    Ok(config_dir(app)
+        .map_err(|e| e.to_string())?
+        .join(SETTINGS_FILENAME))
+}
+
+/// 加载应用设置
+pub fn load_settings<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let config_path = settings_path(app)?;
+
+    if !config_path.exists() {
+        return Ok(());
+    }
+
+    let config_json = std::fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
+    let mut settings: AppSettings = serde_json::from_str(&config_json).map_err(|e| e.to_string())?;
+
+    // 手动编辑导致部署路径非法时回退到默认值，避免 n8n 无法访问
+    if let Err(e) = validate_n8n_path(&settings.n8n_path) {
+        eprintln!("[settings] {e}，已回退为 {DEFAULT_N8N_PATH}");
+        settings.n8n_path = DEFAULT_N8N_PATH.to_string();
+    }
+
+    // 旧版本将 PostgreSQL 密码明文保存在设置文件中：转存到钥匙串后从设置文件中移除
+    let password_moved = move_database_password_to_keychain(&mut settings);
+    *settings_lock() = settings;
+    if password_moved {
+        save_settings(app)?;
+    }
+    Ok(())
+}
+
+/// 将设置中的 PostgreSQL 密码转存到系统钥匙串并清空，返回是否转存
+///
+/// 钥匙串不可用时保留在设置中（仍会写入设置文件），避免丢失密码。
+pub(super) fn move_database_password_to_keychain(settings: &mut AppSettings) -> bool {
+    if settings.database.password.is_empty() {
+        return false;
+    }
+    match keychain::set_postgres_password(Some(&settings.database.password)) {
+        Ok(()) => {
+            settings.database.password.clear();
+            true
+        }
+        Err(error) => {
+            tracing::warn!(%error, "PostgreSQL 密码无法保存到钥匙串，保留在设置文件中");
+            false
+        }
+    }
+}
+
+/// 保存应用设置
+pub fn save_settings<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let settings = settings_lock().clone();
+    let config_path = settings_path(app)?;
+
+    if let Some(parent) = config_path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+
+    let config_json = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    std::fs::write(&config_path, config_json).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// 将当前设置文件备份为 `config.json.bak`（覆盖旧备份），设置文件不存在时返回 None
+pub fn backup_settings<R: Runtime>(app: &AppHandle<R>) -> Result<Option<PathBuf>, String> {
+    let config_path = settings_path(app)?;
+    if !config_path.exists() {
+        return Ok(None);
+    }
+
+    let backup_path = config_path.with_file_name(SETTINGS_BACKUP_FILENAME);
+    std::fs::copy(&config_path, &backup_path).map_err(|e| e.to_string())?;
+    Ok(Some(backup_path))
+}
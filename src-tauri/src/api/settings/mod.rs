@@ -0,0 +1,18 @@
+//! 应用设置模块 - 管理持久化的用户设置
+//!
+//! 模块结构：
+//! - `models` - 数据结构定义
+//! - `state` - 全局状态管理
+//! - `config` - 配置文件读写
+//! - `commands` - Tauri 命令函数
+
+pub mod commands;
+pub mod config;
+pub mod models;
+pub mod state;
+
+// 重新导出常用类型和函数
+pub use commands::*;
+pub use config::*;
+pub use models::*;
+pub use state::*;
@@ -0,0 +1,743 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::i18n;
+use crate::services::archive::MAX_EXTRACTION_THREADS;
+use crate::services::logging::AppLogLevel;
+
+/// 下载写入缓冲区默认大小（256KB）
+///
+/// 基准：本地 virtio 磁盘上以 16KB 数据块写入 256MB（取 5 次最快值），缓冲区 8KB / 64KB / 256KB / 1MB / 4MB
+/// 分别耗时约 201 / 175 / 164 / 186 / 176ms，256KB 时写系统调用为 1024 次（8KB 时为 16384 次）。
+/// 本地磁盘上差异不大，取耗时最短且积压数据较少的 256KB；网络盘上每次写调用开销更高，可适当调大。
+pub const DEFAULT_DOWNLOAD_BUFFER_SIZE: usize = 256 * 1024;
+
+/// 下载写入缓冲区允许的范围（8KB ~ 16MB）
+pub const MIN_DOWNLOAD_BUFFER_SIZE: usize = 8 * 1024;
+pub const MAX_DOWNLOAD_BUFFER_SIZE: usize = 16 * 1024 * 1024;
+
+/// 下载数据默认刷盘间隔（每写入 8MB 调用一次 `sync_data`）
+///
+/// 基准：同上条件、256KB 缓冲区，每 1MB / 8MB / 32MB 刷盘一次分别耗时约 209 / 182 / 188ms（不刷盘约 164ms）。
+/// 8MB 时额外开销约 11%，断电或崩溃后最多丢失约 8MB 已下载数据。
+pub const DEFAULT_DOWNLOAD_FLUSH_INTERVAL: u64 = 8 * 1024 * 1024;
+
+/// 支持 Range 请求的服务器上默认使用的下载连接数
+pub const DEFAULT_DOWNLOAD_CONNECTIONS: usize = 4;
+
+/// 下载连接数上限
+pub const MAX_DOWNLOAD_CONNECTIONS: usize = 16;
+
+/// n8n 默认部署路径（对应 `N8N_PATH`）
+pub const DEFAULT_N8N_PATH: &str = "/";
+
+/// PostgreSQL 默认端口
+pub const DEFAULT_POSTGRES_PORT: u16 = 5432;
+
+/// Redis 默认端口
+pub const DEFAULT_REDIS_PORT: u16 = 6379;
+
+/// 启动前要求的默认最低可用内存（MB）
+pub const DEFAULT_MIN_AVAILABLE_MEMORY_MB: u64 = 1024;
+
+/// 启动健康检查默认初始轮询间隔（毫秒）
+pub const DEFAULT_HEALTH_CHECK_INTERVAL_MS: u64 = 1000;
+
+/// 启动健康检查默认总超时（秒）
+pub const DEFAULT_STARTUP_TIMEOUT_SECS: u64 = 60;
+
+/// 停止 n8n 时默认等待其自行退出的秒数
+pub const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 10;
+
+/// 停止等待时间上限（秒）
+pub const MAX_SHUTDOWN_TIMEOUT_SECS: u64 = 300;
+
+/// n8n 核心包默认构建仓库
+pub const DEFAULT_CORE_REPO_OWNER: &str = "tangtao646";
+pub const DEFAULT_CORE_REPO_NAME: &str = "n8n-core-builder";
+
+/// 默认 GitHub API 地址
+pub const DEFAULT_GITHUB_API_BASE_URL: &str = "https://api.github.com";
+
+/// n8n 数据库类型
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseType {
+    /// 内置 SQLite（默认）
+    #[default]
+    Sqlite,
+    /// 外部 PostgreSQL
+    Postgres,
+}
+
+/// n8n 数据库设置
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct DatabaseSettings {
+    /// 数据库类型
+    pub db_type: DatabaseType,
+    /// PostgreSQL 主机
+    pub host: String,
+    /// PostgreSQL 端口
+    pub port: u16,
+    /// PostgreSQL 用户名
+    pub user: String,
+    /// 待保存的 PostgreSQL 密码
+    ///
+    /// 密码保存在系统钥匙串中（见 `keychain::postgres_password`），不写入 `config.json`：
+    /// 通过 `update_settings` 提交或从旧版本的设置文件读取到时转存到钥匙串并清空，钥匙串不可用时保留在此处。
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub password: String,
+    /// PostgreSQL 数据库名
+    pub database: String,
+}
+
+impl DatabaseSettings {
+    /// 选择 PostgreSQL 时为空的必填字段
+    pub fn missing_fields(&self) -> Vec<&'static str> {
+        if self.db_type == DatabaseType::Sqlite {
+            return Vec::new();
+        }
+
+        [
+            ("host", self.host.trim().is_empty()),
+            ("user", self.user.trim().is_empty()),
+            ("database", self.database.trim().is_empty()),
+            ("port", self.port == 0),
+        ]
+        .into_iter()
+        .filter_map(|(field, missing)| missing.then_some(field))
+        .collect()
+    }
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        Self {
+            db_type: DatabaseType::Sqlite,
+            host: "localhost".to_string(),
+            port: DEFAULT_POSTGRES_PORT,
+            user: String::new(),
+            password: String::new(),
+            database: "n8n".to_string(),
+        }
+    }
+}
+
+/// n8n 执行模式（对应 `EXECUTIONS_MODE`）
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionMode {
+    /// 单进程执行（默认）
+    #[default]
+    Regular,
+    /// 队列模式：主进程负责调度，由 worker 进程通过 Redis 队列执行工作流
+    Queue,
+}
+
+impl ExecutionMode {
+    /// n8n 识别的取值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionMode::Regular => "regular",
+            ExecutionMode::Queue => "queue",
+        }
+    }
+}
+
+/// n8n 执行设置
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ExecutionSettings {
+    /// 执行模式
+    pub mode: ExecutionMode,
+    /// 队列模式使用的 Redis 主机
+    pub redis_host: String,
+    /// 队列模式使用的 Redis 端口
+    pub redis_port: u16,
+    /// 队列模式下启动的 worker 进程数量
+    pub workers: usize,
+}
+
+impl Default for ExecutionSettings {
+    fn default() -> Self {
+        Self {
+            mode: ExecutionMode::Regular,
+            redis_host: "localhost".to_string(),
+            redis_port: DEFAULT_REDIS_PORT,
+            workers: 1,
+        }
+    }
+}
+
+/// 可用内存不足时的处理方式
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LowMemoryPolicy {
+    /// 警告后继续启动（默认）
+    #[default]
+    Warn,
+    /// 拒绝启动
+    Error,
+}
+
+/// 启动前内存检查设置
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct MemorySettings {
+    /// 最低可用物理内存（MB）
+    pub min_available_mb: u64,
+    /// 低于阈值时的处理方式
+    pub low_memory_policy: LowMemoryPolicy,
+}
+
+impl Default for MemorySettings {
+    fn default() -> Self {
+        Self {
+            min_available_mb: DEFAULT_MIN_AVAILABLE_MEMORY_MB,
+            low_memory_policy: LowMemoryPolicy::Warn,
+        }
+    }
+}
+
+/// 启动等待设置
+///
+/// n8n 冷启动（首次执行 SQLite 迁移）可能远慢于热启动，慢速机器上可调大等待时间。
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct StartupSettings {
+    /// 健康检查初始轮询间隔（毫秒，随尝试次数逐渐拉长）
+    pub health_check_interval_ms: u64,
+    /// 等待 n8n 就绪的总超时（秒）
+    pub timeout_secs: u64,
+    /// 最大健康检查次数（0 表示仅受总超时限制）
+    pub max_attempts: u32,
+}
+
+impl Default for StartupSettings {
+    fn default() -> Self {
+        Self {
+            health_check_interval_ms: DEFAULT_HEALTH_CHECK_INTERVAL_MS,
+            timeout_secs: DEFAULT_STARTUP_TIMEOUT_SECS,
+            max_attempts: 0,
+        }
+    }
+}
+
+/// n8n 核心包来源（构建仓库与发布地址）
+///
+/// fork 或企业内部部署可指向自己的构建仓库（GitHub Enterprise 需同时修改 API 地址），
+/// 或通过 `release_base_url` 从内部发布服务器下载资源包。
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct CoreSourceSettings {
+    /// GitHub API 地址（GitHub Enterprise 形如 `https://ghe.example.com/api/v3`）
+    pub api_base_url: String,
+    /// 构建仓库所有者
+    pub owner: String,
+    /// 构建仓库名称
+    pub repo: String,
+    /// 资源包下载地址（`<地址>/<资源包文件名>`；为空时经代理从 GitHub Releases 下载）
+    pub release_base_url: Option<String>,
+}
+
+impl Default for CoreSourceSettings {
+    fn default() -> Self {
+        Self {
+            api_base_url: DEFAULT_GITHUB_API_BASE_URL.to_string(),
+            owner: DEFAULT_CORE_REPO_OWNER.to_string(),
+            repo: DEFAULT_CORE_REPO_NAME.to_string(),
+            release_base_url: None,
+        }
+    }
+}
+
+/// n8n 日志级别（对应 `N8N_LOG_LEVEL`）
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    /// n8n 识别的取值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+        }
+    }
+}
+
+/// n8n 日志输出目标（对应 `N8N_LOG_OUTPUT`）
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LogOutput {
+    /// 输出到控制台（默认）
+    #[default]
+    Console,
+    /// 输出到文件（n8n 用户目录下的 logs）
+    File,
+    /// 同时输出到控制台和文件
+    Both,
+}
+
+impl LogOutput {
+    /// n8n 识别的取值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogOutput::Console => "console",
+            LogOutput::File => "file",
+            LogOutput::Both => "console,file",
+        }
+    }
+}
+
+/// n8n 二进制数据（附件、文件等）的存储方式（对应 `N8N_DEFAULT_BINARY_DATA_MODE`）
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BinaryDataMode {
+    /// 执行期间保存在内存中，随执行记录写入数据库（n8n 自身的默认值）
+    Default,
+    /// 写入数据目录下的 `binaryData`（桌面端默认，避免大文件撑大 SQLite 数据库）
+    #[default]
+    Filesystem,
+}
+
+impl BinaryDataMode {
+    /// n8n 识别的取值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BinaryDataMode::Default => "default",
+            BinaryDataMode::Filesystem => "filesystem",
+        }
+    }
+}
+
+/// Node.js 运行时与 n8n 核心包的下载源
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadMirror {
+    /// 按界面语言选择：中文环境优先国内 Node.js 镜像；核心包经 gh-proxy 下载
+    #[default]
+    Auto,
+    /// 优先华为云 Node.js 镜像；核心包经 gh-proxy 下载
+    Huawei,
+    /// 官方地址：nodejs.org 与 GitHub 直连
+    Official,
+    /// 核心包经 gh-proxy 下载；Node.js 按界面语言选择
+    GhProxy,
+    /// 优先自定义 Node.js 镜像（目录结构与 `nodejs.org/dist` 一致）；核心包直连 GitHub
+    ///
+    /// 核心包的自定义下载地址通过 `core_source.release_base_url` 设置。
+    Custom(String),
+}
+
+impl DownloadMirror {
+    /// 核心包是否经 gh-proxy 下载
+    pub fn uses_gh_proxy(&self) -> bool {
+        matches!(self, Self::Auto | Self::Huawei | Self::GhProxy)
+    }
+}
+
+/// n8n 进程标准输出的处理方式
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessStdio {
+    /// 继承应用的控制台（便于调试，日志与应用日志混在一起）
+    Inherit,
+    /// 通过管道写入日志文件并以事件推送给前端
+    Piped,
+    /// 丢弃输出
+    Null,
+}
+
+impl Default for ProcessStdio {
+    /// 调试构建默认继承控制台，发布构建默认走管道
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            ProcessStdio::Inherit
+        } else {
+            ProcessStdio::Piped
+        }
+    }
+}
+
+/// n8n 日志设置
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct LogSettings {
+    /// 日志级别
+    pub level: LogLevel,
+    /// 日志输出目标
+    pub output: LogOutput,
+    /// n8n 进程标准输出的处理方式
+    pub stdio: ProcessStdio,
+}
+
+/// 应用设置结构
+///
+/// 所有字段均带默认值，旧版本的配置文件缺少字段时可以正常加载。
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AppSettings {
+    /// 自定义 HTTP User-Agent（为空时使用默认值，用于需要特定 UA 的企业代理）
+    pub user_agent: Option<String>,
+    /// 下载写入缓冲区大小（字节）
+    pub download_buffer_size: usize,
+    /// 下载数据刷盘间隔（字节，每写入这么多数据调用一次 `sync_data`；0 表示仅在下载结束时刷盘）
+    pub download_flush_interval: u64,
+    /// 下载连接数：服务器支持 Range 请求时并发分段下载（1 表示始终单连接下载）
+    pub download_connections: usize,
+    /// n8n 核心包解压线程数（0 表示按 CPU 核数自动选择；机械硬盘建议设为 1）
+    pub extraction_threads: usize,
+    /// n8n 数据库设置
+    pub database: DatabaseSettings,
+    /// n8n 日志设置
+    pub logging: LogSettings,
+    /// 应用自身日志（`logs/app.log`）的级别
+    pub app_log_level: AppLogLevel,
+    /// n8n 执行模式设置
+    pub execution: ExecutionSettings,
+    /// n8n 二进制数据存储方式
+    pub binary_data_mode: BinaryDataMode,
+    /// 启动前内存检查设置
+    pub memory: MemorySettings,
+    /// 启动等待设置
+    pub startup: StartupSettings,
+    /// 停止 n8n 时等待其自行退出的秒数（超时后强制终止；0 表示直接强制终止）
+    pub shutdown_timeout_secs: u64,
+    /// n8n 核心包来源
+    pub core_source: CoreSourceSettings,
+    /// Node.js 运行时与 n8n 核心包的下载源
+    pub download_mirror: DownloadMirror,
+    /// 应用启动时自动启动 n8n（仅在安装完整时生效）
+    pub auto_launch: bool,
+    /// n8n 运行期间阻止系统空闲 / App Nap（目前仅 macOS 生效）
+    pub prevent_idle: bool,
+    /// 以 `n8n start --tunnel` 启动，将实例暴露到公网以测试 webhook
+    pub n8n_tunnel_enabled: bool,
+    /// n8n 部署子路径（如 `/n8n/`），用于在反向代理下按子路径托管
+    pub n8n_path: String,
+    /// 锁定工作流：关闭编辑器界面与 Public API，作为受控运行环境使用（具体变量见 `LOCKED_WORKFLOW_ENVS`）
+    pub lock_workflows: bool,
+    /// 追加在 `n8n start` 之后的自定义命令行参数
+    ///
+    /// 顺序为：`start` 子命令、内置参数（如隧道模式的 `--tunnel`）、自定义参数；
+    /// 同一选项重复出现时 n8n 以最后一次为准，因此自定义参数优先于内置参数。
+    pub extra_n8n_args: Vec<String>,
+    /// 自定义 n8n 环境变量（如 `WEBHOOK_URL`、`EXECUTIONS_DATA_PRUNE`），下次启动时生效
+    ///
+    /// 覆盖应用的默认值；`LOCKED_N8N_ENV_KEYS` 中的变量由应用管理，设置了也会被忽略。
+    pub n8n_envs: BTreeMap<String, String>,
+    /// HTTP / HTTPS 代理地址（如 `http://proxy.example.com:8080`，可包含认证信息）
+    ///
+    /// 为空时使用 `HTTPS_PROXY` / `HTTP_PROXY` 等环境变量中的代理。
+    pub http_proxy: Option<String>,
+    /// 下载允许重定向到的主机（为空表示不限制；`example.com` 同时匹配其子域名）
+    pub redirect_allowed_hosts: Vec<String>,
+    /// 自定义 n8n 数据目录（为空时使用数据基础目录下的 `n8n-data`；由 `migrate_data_dir` 或 `set_data_dir` 写入）
+    pub n8n_data_dir: Option<PathBuf>,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            user_agent: None,
+            download_buffer_size: DEFAULT_DOWNLOAD_BUFFER_SIZE,
+            download_flush_interval: DEFAULT_DOWNLOAD_FLUSH_INTERVAL,
+            download_connections: DEFAULT_DOWNLOAD_CONNECTIONS,
+            extraction_threads: 0,
+            database: DatabaseSettings::default(),
+            logging: LogSettings::default(),
+            app_log_level: AppLogLevel::default(),
+            execution: ExecutionSettings::default(),
+            binary_data_mode: BinaryDataMode::default(),
+            memory: MemorySettings::default(),
+            startup: StartupSettings::default(),
+            shutdown_timeout_secs: DEFAULT_SHUTDOWN_TIMEOUT_SECS,
+            core_source: CoreSourceSettings::default(),
+            download_mirror: DownloadMirror::default(),
+            auto_launch: false,
+            prevent_idle: true,
+            n8n_tunnel_enabled: false,
+            n8n_path: DEFAULT_N8N_PATH.to_string(),
+            lock_workflows: false,
+            extra_n8n_args: Vec::new(),
+            n8n_envs: BTreeMap::new(),
+            http_proxy: None,
+            redirect_allowed_hosts: Vec::new(),
+            n8n_data_dir: None,
+        }
+    }
+}
+
+/// 单个设置字段的校验错误
+#[derive(Clone, Serialize, Debug, PartialEq, Eq)]
+pub struct FieldError {
+    /// 字段路径（如 `database.host`，与设置 JSON 的结构一致；与字段无关的错误为空）
+    pub field: String,
+    pub error: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, error: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            error: error.into(),
+        }
+    }
+}
+
+impl AppSettings {
+    /// 校验所有字段，返回全部不合法字段的错误（而不是遇到第一个就停止）
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = Vec::new();
+
+        if let Err(error) = validate_n8n_path(&self.n8n_path) {
+            errors.push(FieldError::new("n8n_path", error));
+        }
+
+        if self
+            .user_agent
+            .as_deref()
+            .is_some_and(|ua| ua.chars().any(char::is_control))
+        {
+            errors.push(FieldError::new("user_agent", i18n::t("settings.invalid_user_agent")));
+        }
+
+        if !(MIN_DOWNLOAD_BUFFER_SIZE..=MAX_DOWNLOAD_BUFFER_SIZE).contains(&self.download_buffer_size) {
+            errors.push(FieldError::new(
+                "download_buffer_size",
+                format!(
+                    "{}: {}KB ~ {}KB",
+                    i18n::t("settings.out_of_range"),
+                    MIN_DOWNLOAD_BUFFER_SIZE / 1024,
+                    MAX_DOWNLOAD_BUFFER_SIZE / 1024
+                ),
+            ));
+        }
+
+        if !(1..=MAX_DOWNLOAD_CONNECTIONS).contains(&self.download_connections) {
+            errors.push(FieldError::new(
+                "download_connections",
+                format!("{}: 1 ~ {MAX_DOWNLOAD_CONNECTIONS}", i18n::t("settings.out_of_range")),
+            ));
+        }
+
+        if self.extraction_threads > MAX_EXTRACTION_THREADS {
+            errors.push(FieldError::new(
+                "extraction_threads",
+                format!("{}: 0 ~ {MAX_EXTRACTION_THREADS}", i18n::t("settings.out_of_range")),
+            ));
+        }
+
+        errors.extend(self.database.missing_fields().into_iter().map(|field| {
+            FieldError::new(format!("database.{field}"), i18n::t("db.postgres.missing_field"))
+        }));
+
+        if self.execution.mode == ExecutionMode::Queue {
+            if self.database.db_type != DatabaseType::Postgres {
+                errors.push(FieldError::new("execution.mode", i18n::t("queue.requires_postgres")));
+            }
+            if self.execution.redis_host.trim().is_empty() {
+                errors.push(FieldError::new("execution.redis_host", i18n::t("settings.required")));
+            }
+            if self.execution.redis_port == 0 {
+                errors.push(FieldError::new("execution.redis_port", i18n::t("settings.invalid_port")));
+            }
+        }
+
+        let is_repo_segment = |value: &str| {
+            !value.is_empty()
+                && value
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        };
+        if !is_repo_segment(&self.core_source.owner) {
+            errors.push(FieldError::new("core_source.owner", i18n::t("settings.invalid_repo")));
+        }
+        if !is_repo_segment(&self.core_source.repo) {
+            errors.push(FieldError::new("core_source.repo", i18n::t("settings.invalid_repo")));
+        }
+        if !is_http_url(&self.core_source.api_base_url) {
+            errors.push(FieldError::new("core_source.api_base_url", i18n::t("settings.invalid_url")));
+        }
+        if self
+            .core_source
+            .release_base_url
+            .as_deref()
+            .is_some_and(|url| !is_http_url(url))
+        {
+            errors.push(FieldError::new(
+                "core_source.release_base_url",
+                i18n::t("settings.invalid_url"),
+            ));
+        }
+        if self.effective_http_proxy().is_some_and(|url| !is_http_url(&url)) {
+            errors.push(FieldError::new("http_proxy", i18n::t("settings.invalid_url")));
+        }
+        if let DownloadMirror::Custom(url) = &self.download_mirror {
+            if !is_http_url(url) {
+                errors.push(FieldError::new("download_mirror", i18n::t("settings.invalid_url")));
+            }
+        }
+
+        for (index, arg) in self.extra_n8n_args.iter().enumerate() {
+            if let Err(error) = validate_n8n_arg(arg) {
+                errors.push(FieldError::new(format!("extra_n8n_args[{index}]"), error));
+            }
+        }
+
+        for (key, value) in &self.n8n_envs {
+            if let Err(error) = validate_n8n_env(key, value) {
+                errors.push(FieldError::new(format!("n8n_envs.{key}"), error));
+            }
+        }
+
+        for (index, host) in self.redirect_allowed_hosts.iter().enumerate() {
+            let host = host.trim();
+            if host.is_empty() || host.contains(|c: char| c.is_whitespace() || matches!(c, '/' | ':')) {
+                errors.push(FieldError::new(
+                    format!("redirect_allowed_hosts[{index}]"),
+                    i18n::t("settings.invalid_host"),
+                ));
+            }
+        }
+
+        if self.n8n_data_dir.as_deref().is_some_and(|dir| !dir.is_absolute()) {
+            errors.push(FieldError::new("n8n_data_dir", i18n::t("data_dir.not_absolute")));
+        }
+
+        if self.startup.health_check_interval_ms == 0 {
+            errors.push(FieldError::new(
+                "startup.health_check_interval_ms",
+                i18n::t("settings.must_be_positive"),
+            ));
+        }
+        if self.startup.timeout_secs == 0 {
+            errors.push(FieldError::new("startup.timeout_secs", i18n::t("settings.must_be_positive")));
+        }
+
+        if self.shutdown_timeout_secs > MAX_SHUTDOWN_TIMEOUT_SECS {
+            errors.push(FieldError::new(
+                "shutdown_timeout_secs",
+                format!("{}: 0 ~ {MAX_SHUTDOWN_TIMEOUT_SECS}", i18n::t("settings.out_of_range")),
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// 获取限制在合法范围内的下载缓冲区大小
+    pub fn effective_download_buffer_size(&self) -> usize {
+        self.download_buffer_size
+            .clamp(MIN_DOWNLOAD_BUFFER_SIZE, MAX_DOWNLOAD_BUFFER_SIZE)
+    }
+
+    /// 获取限制在合法范围内的下载连接数
+    pub fn effective_download_connections(&self) -> usize {
+        self.download_connections.clamp(1, MAX_DOWNLOAD_CONNECTIONS)
+    }
+
+    /// 获取配置的代理地址（空字符串视为未配置）
+    pub fn effective_http_proxy(&self) -> Option<String> {
+        self.http_proxy
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(str::to_string)
+    }
+}
+
+/// 自定义 n8n 参数中不允许出现的字符
+///
+/// 参数通过 `Command` 逐个传递、不经过 shell，本身没有注入风险；
+/// 这里拒绝明显的 shell 元字符，避免用户误以为可以写管道或重定向。
+const FORBIDDEN_ARG_CHARS: [char; 9] = [';', '|', '&', '$', '`', '<', '>', '\n', '\r'];
+
+/// 校验单个自定义 n8n 参数
+pub fn validate_n8n_arg(arg: &str) -> Result<(), String> {
+    if arg.trim().is_empty() {
+        return Err(i18n::t("settings.required"));
+    }
+    if arg.contains(FORBIDDEN_ARG_CHARS) || arg.chars().any(char::is_control) {
+        return Err(format!("{}: {arg}", i18n::t("settings.invalid_n8n_arg")));
+    }
+    Ok(())
+}
+
+/// 由应用管理、不允许通过 `n8n_envs` 覆盖的环境变量
+///
+/// - `N8N_USER_FOLDER`：数据目录（通过数据目录迁移修改）
+/// - `N8N_PORT` / `N8N_HOST`：端口选择与健康检查依赖的监听地址
+/// - `N8N_PATH`：编辑器地址与健康检查使用的部署路径（通过 `n8n_path` 设置修改）
+/// - `NODES_EXCLUDE` / `N8N_BLOCK_NODES`：默认禁用执行命令等危险节点（通过节点解禁开关修改）
+///
+/// 锁定工作流与安全模式追加的变量在自定义变量之后写入，同样不会被覆盖。
+pub const LOCKED_N8N_ENV_KEYS: [&str; 6] = [
+    "N8N_USER_FOLDER",
+    "N8N_PORT",
+    "N8N_HOST",
+    "N8N_PATH",
+    "NODES_EXCLUDE",
+    "N8N_BLOCK_NODES",
+];
+
+/// 是否为不允许自定义的环境变量（不区分大小写，Windows 环境变量名不区分大小写）
+pub fn is_locked_n8n_env(key: &str) -> bool {
+    LOCKED_N8N_ENV_KEYS
+        .iter()
+        .any(|locked| locked.eq_ignore_ascii_case(key))
+}
+
+/// 校验自定义环境变量：变量名只能包含字母、数字和 `_` 且不以数字开头，不能是锁定变量，值不能包含 NUL
+pub fn validate_n8n_env(key: &str, value: &str) -> Result<(), String> {
+    let is_valid_name = key.chars().next().is_some_and(|c| !c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !is_valid_name {
+        return Err(format!("{}: {key}", i18n::t("settings.invalid_env_name")));
+    }
+    if is_locked_n8n_env(key) {
+        return Err(format!("{}: {key}", i18n::t("settings.locked_env")));
+    }
+    if value.contains('\0') {
+        return Err(format!("{}: {key}", i18n::t("settings.invalid_env_value")));
+    }
+    Ok(())
+}
+
+/// 是否为合法的 http / https 地址
+fn is_http_url(value: &str) -> bool {
+    url::Url::parse(value).is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.has_host())
+}
+
+/// 校验 n8n 部署路径：必须以 `/` 开头和结尾，且不含空白字符
+pub fn validate_n8n_path(path: &str) -> Result<(), String> {
+    let is_valid = path.starts_with('/')
+        && path.ends_with('/')
+        && !path.contains(char::is_whitespace)
+        && !path.contains("//");
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(format!("{}: {path}", i18n::t("settings.invalid_n8n_path")))
+    }
+}
+
+/// 将 n8n 部署路径拼接到基础地址（如 `http://127.0.0.1:5678` + `/n8n/`）
+pub fn join_n8n_path(base_url: &str, n8n_path: &str) -> String {
+    format!("{}{}", base_url.trim_end_matches('/'), n8n_path)
+}
@@ -0,0 +1,12 @@
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+
+use super::models::AppSettings;
+
+/// 全局应用设置状态
+pub(crate) static SETTINGS: LazyLock<Arc<Mutex<AppSettings>>> =
+    LazyLock::new(|| Arc::new(Mutex::new(AppSettings::default())));
+
+/// 安全获取 SETTINGS 的锁
+pub fn settings_lock() -> MutexGuard<'static, AppSettings> {
+    SETTINGS.lock().expect("SETTINGS mutex poisoned")
+}
@@ -1,13 +1,12 @@
-use tauri::{AppHandle, Manager, Runtime};
+use crate::api::n8n::config_dir;
+use tauri::{AppHandle, Runtime};
 
 use super::models::TunnelConfig;
 use super::state::{tunnel_config_lock, tunnel_url_lock};
 
 /// 加载隧道配置
 pub fn load_tunnel_config<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
-    let config_path = app
-        .path()
-        .app_config_dir()
+    let config_path = config_dir(app)
         .map_err(|e| e.to_string())?
         .join("tunnel_config.json");
 
@@ -32,9 +31,7 @@ pub fn load_tunnel_config<R: Runtime>(app: &AppHandle<R>) -> Result<(), String>
 /// 保存隧道配置
 pub fn save_tunnel_config<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
     let config = tunnel_config_lock().clone();
-    let config_path = app
-        .path()
-        .app_config_dir()
+    let config_path = config_dir(app)
         .map_err(|e| e.to_string())?
         .join("tunnel_config.json");
 
@@ -1,8 +1,8 @@
 use std::time::Duration;
-use tauri::{AppHandle, Manager, Runtime};
+use tauri::{AppHandle, Runtime};
 
 use super::state::tunnel_running_lock;
-use crate::api::n8n::{self, shutdown_n8n};
+use crate::api::n8n::{self, base_dir, shutdown_n8n};
 use crate::api::utils::emit_global_sync;
 use crate::services::manager;
 
@@ -18,13 +18,21 @@ pub fn restart_n8n_with_env<R: Runtime>(app: &AppHandle<R>, url: &str) {
     }
 
     println!("[Tunnel] 正在应用 URL 并物理重启 n8n...");
-    shutdown_n8n();
+    if let Err(e) = shutdown_n8n() {
+        println!("[Tunnel] ✗ 关闭 n8n 失败: {e}");
+    }
     std::thread::sleep(Duration::from_millis(800));
 
-    if let Ok(app_path) = app.path().app_data_dir() {
-        let n8n_bin = app_path.join("n8n-core/node_modules/n8n/bin/n8n");
+    if let Ok(app_path) = base_dir(app) {
+        let n8n_bin = match n8n::resolve_n8n_entrypoint(&app_path) {
+            Ok(path) => path,
+            Err(e) => {
+                println!("[Tunnel] ✗ 启动失败: {e}");
+                return;
+            }
+        };
         let node_path = manager::get_node_binary_path(app_path.join("runtime"));
-        let data_dir = app_path.join("n8n-data");
+        let data_dir = n8n::paths::n8n_data_dir(&app_path);
 
         // 【关键】清除前端会话和缓存，强制用户重新登录以刷新 webhook URL
         // 这会清除浏览器在 n8n 中的会话，使得用户需要重新登录
@@ -39,10 +47,10 @@ pub fn restart_n8n_with_env<R: Runtime>(app: &AppHandle<R>, url: &str) {
         envs.insert("N8N_HOST".to_string(), "127.0.0.1".to_string()); // 强制监听 IPv4
         envs.insert("N8N_PORT".to_string(), "5678".to_string());
         envs.insert("WEBHOOK_URL".to_string(), url.to_string());
-        envs.insert("N8N_EDITOR_BASE_URL".to_string(), url.to_string());
+        envs.insert("N8N_EDITOR_BASE_URL".to_string(), n8n::editor_url(url));
 
         println!("[Tunnel] 启动 n8n...");
-        match manager::start_node(node_path, n8n_bin, data_dir, envs) {
+        match n8n::start_n8n_process(app, node_path, n8n_bin, data_dir, envs) {
             Ok(()) => {
                 println!("[Tunnel] ✓ n8n 重启成功");
                 println!("[Tunnel] ✓ 新的 WEBHOOK_URL: {url}");
@@ -11,6 +11,8 @@ use thiserror::Error;
 use url::Url;
 use zip::ZipArchive;
 
+use crate::services::archive;
+
 // --- 错误定义 ---
 
 #[derive(Debug, Error)]
@@ -30,6 +32,9 @@ pub enum UtilsError {
     #[error("解压路径异常")]
     InvalidPath,
 
+    #[error("{0}")]
+    MaliciousArchive(String),
+
     #[error("Tauri 事件发射失败: {0}")]
     TauriError(String),
 }
@@ -84,10 +89,8 @@ pub fn extract_zip_file(
     // 使用索引迭代器，结合 ? 自动传播错误
     for i in 0..archive.len() {
         let mut file = archive.by_index(i)?;
-        let outpath = match file.enclosed_name() {
-            Some(path) => target_dir.join(path),
-            None => continue,
-        };
+        let outpath =
+            archive::zip_entry_outpath(target_dir, &file).map_err(UtilsError::MaliciousArchive)?;
 
         if file.is_dir() {
             fs::create_dir_all(&outpath)?;
@@ -76,17 +76,100 @@ fn en(key: &str) -> Option<&'static str> {
 
         // ── Node.js / Runtime ──
         "node.not_found" => "Node.js not found. Please run setup_runtime first",
+        "node.no_working_binary" => "No working Node.js executable found in the runtime directory. Please reinstall the runtime",
         "n8n.core_not_found" => "n8n core not found. Please run setup_n8n first",
         "n8n.binary_not_found" => "n8n binary not found",
+        "n8n.encryption_key_pending" => "(generated on first launch)",
         "runtime.unsupported_platform" => "Unsupported platform architecture",
+        "runtime.link_error" => "Node.js failed to start because required shared libraries are missing or too old",
+
+        // ── Database / 数据库 ──
+        "db.postgres.missing_field" => "PostgreSQL setting is required",
+        "db.postgres.unreachable" => "Cannot connect to the PostgreSQL server",
+        "queue.requires_postgres" => "Queue mode requires a PostgreSQL database",
+        "queue.redis_unreachable" => "Cannot connect to the Redis server for queue mode",
+
+        // ── Preflight / 预检 ──
+        "preflight.runtime_missing" => "Node.js runtime is not installed",
+        "memory.low" => "Available memory is low; n8n may run slowly or crash. Close other applications before launching",
+        "memory.ok" => "Enough free memory available",
+        "memory.unknown" => "Unable to read system memory; check skipped",
+        "preflight.runtime_arch_ok" => "Node.js runtime architecture matches the system",
+        "preflight.runtime_arch_mismatch" => "Node.js runtime architecture does not match the system; re-run runtime setup to download the correct build",
+        "preflight.runtime_arch_unknown" => "Unable to determine the Node.js runtime architecture",
 
         // ── n8n State / 状态 ──
         "n8n.state.process_manager_poisoned" => "Process manager lock poisoned",
+        "n8n.not_running" => "n8n is not running",
+        "n8n.cli.stop_first" => "Stop n8n before running this command to avoid locking the database",
+        "n8n.cli.source_not_found" => "Import source not found",
+        "n8n.cli.failed" => "n8n command failed",
+        "n8n.cli.invalid_workflow_id" => "Invalid workflow ID",
+        "n8n.startup.health_check_failed" => "n8n did not become ready in time; health checks attempted",
+        "n8n.startup.last_error" => "last startup error",
+        "n8n.startup.exited" => "n8n exited shortly after starting",
+        "n8n.startup.no_output" => "no error output was captured; set the output mode to piped to see it",
+        "setup.canceled" => "Setup was canceled",
+        "n8n.version.unknown" => "Unable to read the n8n version from the installed package",
+        "n8n.version.not_installed" => "This n8n version is not installed",
+        "n8n.version.invalid" => "Invalid n8n version name",
+        "runtime.offline_incompatible" => "The offline Node.js archive is not compatible with n8n",
+        "n8n.metrics.unavailable" => "Unable to read n8n process metrics",
+        "n8n.entrypoint_not_found" => "n8n entrypoint not found, searched",
+        "download.not_found" => "No active download with this id",
+        "download.none_active" => "No download is in progress",
+        "db.status.postgres_unsupported" => "Migration status is only available for SQLite",
+        "db.status.not_initialized" => "Database not created yet; migrations will run on first launch",
+        "db.status.pending" => "Pending database migrations (the next launch may take a while)",
+        "db.status.up_to_date" => "Database schema is up to date",
+        "db.status.unknown_pending" => "Unable to determine pending migrations",
+        "db.status.read_failed" => "Failed to read the migrations table",
+        "db.vacuum.not_found" => "Database file not found",
+        "db.vacuum.failed" => "Failed to vacuum the database",
+        "db.vacuum.postgres_unsupported" => "Use PostgreSQL's own maintenance tools for PostgreSQL databases",
+        "data_dir.not_absolute" => "The data directory must be an absolute path",
+        "data_dir.same_location" => "The new data directory is the same as the current one",
+        "data_dir.nested" => "The new data directory cannot be inside the current one (or contain it)",
+        "data_dir.target_not_empty" => "The target directory is not empty",
+        "data_dir.insufficient_space" => "Not enough free space on the target disk",
+        "data_dir.copy_mismatch" => "Copied data does not match the original",
+        "data_dir.install_dir" => "The data directory cannot be inside the runtime or n8n core directories",
+        "data_dir.not_n8n_data" => "The directory is not empty and does not contain n8n data (.n8n)",
+        "verify.archive_missing" => "Cached n8n core archive not found, archive check skipped",
+        "verify.archive_other_version" => "Cached core archive does not belong to the active version, archive check skipped",
+        "verify.archive_hash_ok" => "Core archive matches the SHA256 recorded at install",
+        "verify.archive_hash_mismatch" => "Core archive SHA256 does not match the digest recorded at install",
+        "verify.tree_ok" => "Installed files match the archive manifest",
+        "verify.tree_manifest_missing" => "Core binary present; archive manifest unavailable for a full comparison",
+        "verify.tree_mismatch" => "Missing or modified files",
+        "verify.runtime_ok" => "Node runtime works",
+        "verify.key_paths_ok" => "Node binary, n8n entry point and core packages are present",
+        "verify.key_paths_missing" => "Required files are missing",
+        "settings.invalid_n8n_path" => "n8n path must start and end with '/'",
+        "settings.invalid_user_agent" => "User-Agent must not contain control characters",
+        "settings.out_of_range" => "Value out of range",
+        "settings.required" => "This field is required",
+        "settings.invalid_port" => "Invalid port",
+        "settings.must_be_positive" => "Value must be greater than 0",
+        "settings.invalid_repo" => "Repository owner and name may only contain letters, digits, '-', '_' and '.'",
+        "settings.invalid_url" => "Must be a valid http(s) URL",
+        "settings.invalid_n8n_arg" => "n8n arguments must not contain shell metacharacters",
+        "settings.invalid_host" => "Invalid host name",
+        "settings.invalid_env_name" => "Environment variable names may only contain letters, digits and '_' and must not start with a digit",
+        "settings.locked_env" => "This environment variable is managed by the app and cannot be overridden",
+        "settings.invalid_env_value" => "Environment variable values must not contain NUL characters",
+        "privileges.elevated_warning" => "The app is running as root/administrator. n8n will run with full privileges, and data files it creates may not be accessible as a regular user later. Running as a regular user is recommended.",
+        "http.too_many_redirects" => "Too many redirects",
+        "http.redirect_host_not_allowed" => "Redirect target host is not allowed",
+        "n8n.kill_failed" => "Failed to stop the n8n process",
+        "n8n.restart.not_launched" => "n8n has not been launched in this session; nothing to restart",
+        "n8n.port_unavailable" => "No free port available for n8n (checked up to 5700)",
+        "n8n.tunnel.exposure_warning" => "n8n tunnel mode is enabled: your instance is publicly reachable through hooks.n8n.cloud. Only use it for testing webhooks.",
+        "window.invalid_label" => "Invalid window label",
         "n8n.state.no_check_run" => "No health check performed yet",
         "n8n.state.network_error" => "Network error",
         "n8n.state.http_status" => "HTTP status code",
         "n8n.state.healthy" => "healthy",
-        "n8n.state.cannot_read_body" => "Cannot read response body",
 
         // ── Filesystem / 文件系统 ──
         "fs.cannot_create_dir" => "Cannot create directory",
@@ -105,6 +188,7 @@ fn en(key: &str) -> Option<&'static str> {
         "download.zip_extract_failed" => "ZIP extraction failed",
         "download.tar_extract_failed" => "TAR.GZ extraction failed",
         "download.stream_error" => "Download stream error",
+        "download.no_data" => "No data received",
         "download.http_error" => "HTTP request failed",
         "download.http_status_error" => "Download failed with HTTP status",
         "download.create_client_failed" => "Failed to create HTTP client",
@@ -113,6 +197,7 @@ fn en(key: &str) -> Option<&'static str> {
 
         // ── N8n Process / 进程 ──
         "process.spawn_failed" => "Failed to spawn n8n process",
+        "process.stop_previous_failed" => "Failed to stop the previously started n8n process",
         "process.invalid_user_data_path" => "User data directory path contains invalid characters",
 
         // ── Cloudflared path ──
@@ -137,6 +222,12 @@ fn en(key: &str) -> Option<&'static str> {
         "fs.cannot_delete_existing_file" => "Cannot delete existing file",
         "fs.cannot_move_to" => "Cannot move file to",
         "fs.cannot_open_archive" => "Cannot open archive",
+        "archive.too_large" => "Archive exceeds the maximum uncompressed size",
+        "archive.invalid_zip" => "Invalid ZIP archive",
+        "archive.insufficient_memory" => "Not enough free memory to extract the archive",
+        "archive.path_too_long" => "File path exceeds the Windows length limit. Enable long path support (LongPathsEnabled) and try again",
+        "archive.worker_panicked" => "Extraction worker thread panicked",
+        "archive.malicious_entry" => "Archive entry points outside the extraction directory",
         "fs.cannot_read_archive_entry" => "Cannot read archive entry",
         "fs.cannot_get_entry_path" => "Cannot get entry path",
         "fs.cannot_create_target_file" => "Cannot create target file",
@@ -183,17 +274,100 @@ fn zh(key: &str) -> Option<&'static str> {
 
         // ── Node.js / Runtime ──
         "node.not_found" => "Node.js 未找到，请先执行 setup_runtime",
+        "node.no_working_binary" => "运行时目录中没有可正常运行的 Node.js，请重新安装运行时",
         "n8n.core_not_found" => "n8n 核心未找到，请先执行 setup_n8n",
         "n8n.binary_not_found" => "n8n 二进制文件未找到",
+        "n8n.encryption_key_pending" => "（首次启动时生成）",
         "runtime.unsupported_platform" => "不支持的平台架构",
+        "runtime.link_error" => "Node.js 缺少所需的共享库或版本过低，无法启动",
+
+        // ── Database / 数据库 ──
+        "db.postgres.missing_field" => "PostgreSQL 配置项不能为空",
+        "db.postgres.unreachable" => "无法连接到 PostgreSQL 服务器",
+        "queue.requires_postgres" => "队列模式需要使用 PostgreSQL 数据库",
+        "queue.redis_unreachable" => "无法连接到队列模式使用的 Redis 服务器",
+
+        // ── Preflight / 预检 ──
+        "preflight.runtime_missing" => "Node.js 运行时未安装",
+        "memory.low" => "可用内存不足，n8n 可能运行缓慢或崩溃，建议关闭其他应用后再启动",
+        "memory.ok" => "可用内存充足",
+        "memory.unknown" => "无法读取系统内存信息，已跳过检查",
+        "preflight.runtime_arch_ok" => "Node.js 运行时架构与系统一致",
+        "preflight.runtime_arch_mismatch" => "Node.js 运行时架构与系统不一致，请重新安装运行时以下载正确版本",
+        "preflight.runtime_arch_unknown" => "无法检测 Node.js 运行时架构",
 
         // ── n8n State / 状态 ──
         "n8n.state.process_manager_poisoned" => "PROCESS_MANAGER 锁已被毒化 (Poisoned)",
+        "n8n.not_running" => "n8n 未运行",
+        "n8n.cli.stop_first" => "请先停止 n8n 再执行此操作，以免数据库被锁定",
+        "n8n.cli.source_not_found" => "导入来源不存在",
+        "n8n.cli.failed" => "n8n 命令执行失败",
+        "n8n.cli.invalid_workflow_id" => "工作流 ID 不合法",
+        "n8n.startup.health_check_failed" => "n8n 未能在限定时间内就绪，健康检查次数",
+        "n8n.startup.last_error" => "最近的启动错误",
+        "n8n.startup.exited" => "n8n 启动后立即退出",
+        "n8n.startup.no_output" => "未捕获到错误输出，将输出模式设为 piped 后可查看",
+        "setup.canceled" => "安装已取消",
+        "n8n.version.unknown" => "无法从安装包中读取 n8n 版本号",
+        "n8n.version.not_installed" => "未安装该 n8n 版本",
+        "n8n.version.invalid" => "n8n 版本名不合法",
+        "runtime.offline_incompatible" => "离线 Node.js 资源包与 n8n 不兼容",
+        "n8n.metrics.unavailable" => "无法读取 n8n 进程资源占用",
+        "n8n.entrypoint_not_found" => "未找到 n8n 入口文件，已搜索",
+        "download.not_found" => "没有对应的进行中下载",
+        "download.none_active" => "当前没有进行中的下载",
+        "db.status.postgres_unsupported" => "仅支持查询 SQLite 数据库的迁移状态",
+        "db.status.not_initialized" => "数据库尚未创建，首次启动时将执行迁移",
+        "db.status.pending" => "存在待执行的数据库迁移（下次启动可能较慢）",
+        "db.status.up_to_date" => "数据库结构已是最新",
+        "db.status.unknown_pending" => "无法确定待执行的迁移",
+        "db.status.read_failed" => "读取迁移表失败",
+        "db.vacuum.not_found" => "数据库文件不存在",
+        "db.vacuum.failed" => "压缩数据库失败",
+        "db.vacuum.postgres_unsupported" => "PostgreSQL 数据库请使用数据库自带的维护工具",
+        "data_dir.not_absolute" => "数据目录必须是绝对路径",
+        "data_dir.same_location" => "新数据目录与当前目录相同",
+        "data_dir.nested" => "新数据目录不能位于当前目录内（或包含当前目录）",
+        "data_dir.target_not_empty" => "目标目录不为空",
+        "data_dir.insufficient_space" => "目标磁盘剩余空间不足",
+        "data_dir.copy_mismatch" => "复制后的数据与原数据不一致",
+        "data_dir.install_dir" => "数据目录不能位于运行时或 n8n 核心目录中",
+        "data_dir.not_n8n_data" => "目录不为空且不包含 n8n 数据（.n8n）",
+        "verify.archive_missing" => "未找到缓存的 n8n 核心包，已跳过核心包校验",
+        "verify.archive_other_version" => "缓存的核心包不属于当前启用的版本，已跳过核心包校验",
+        "verify.archive_hash_ok" => "核心包与安装时记录的 SHA256 一致",
+        "verify.archive_hash_mismatch" => "核心包 SHA256 与安装时记录的摘要不一致",
+        "verify.tree_ok" => "已安装文件与压缩包清单一致",
+        "verify.tree_manifest_missing" => "核心可执行文件存在，但缺少压缩包清单，无法完整比对",
+        "verify.tree_mismatch" => "缺失或被修改的文件",
+        "verify.runtime_ok" => "Node 运行时可用",
+        "verify.key_paths_ok" => "Node 可执行文件、n8n 入口与核心依赖包齐全",
+        "verify.key_paths_missing" => "缺少必要的文件",
+        "settings.invalid_n8n_path" => "n8n 部署路径必须以 '/' 开头和结尾",
+        "settings.invalid_user_agent" => "User-Agent 不能包含控制字符",
+        "settings.out_of_range" => "取值超出范围",
+        "settings.required" => "此项不能为空",
+        "settings.invalid_port" => "端口无效",
+        "settings.must_be_positive" => "取值必须大于 0",
+        "settings.invalid_repo" => "仓库所有者与名称只能包含字母、数字、'-'、'_' 和 '.'",
+        "settings.invalid_url" => "必须是有效的 http(s) 地址",
+        "settings.invalid_n8n_arg" => "n8n 参数不能包含 shell 元字符",
+        "settings.invalid_host" => "主机名不合法",
+        "settings.invalid_env_name" => "环境变量名只能包含字母、数字和 '_'，且不能以数字开头",
+        "settings.locked_env" => "该环境变量由应用管理，不能覆盖",
+        "settings.invalid_env_value" => "环境变量值不能包含 NUL 字符",
+        "privileges.elevated_warning" => "应用正以 root / 管理员身份运行，n8n 将拥有最高权限，且创建的数据文件之后可能无法以普通用户身份访问。建议以普通用户身份运行。",
+        "http.too_many_redirects" => "重定向次数超过上限",
+        "http.redirect_host_not_allowed" => "重定向目标主机不在允许列表中",
+        "n8n.kill_failed" => "终止 n8n 进程失败",
+        "n8n.restart.not_launched" => "本次会话尚未启动过 n8n，无法重启",
+        "n8n.port_unavailable" => "没有可供 n8n 使用的空闲端口（已检查至 5700）",
+        "n8n.tunnel.exposure_warning" => "已启用 n8n 隧道模式：实例可通过 hooks.n8n.cloud 被公网访问，请仅用于测试 webhook。",
+        "window.invalid_label" => "窗口标识无效",
         "n8n.state.no_check_run" => "未启动检查",
         "n8n.state.network_error" => "网络错误",
         "n8n.state.http_status" => "HTTP 状态码",
         "n8n.state.healthy" => "健康",
-        "n8n.state.cannot_read_body" => "无法读取Body",
 
         // ── Filesystem / 文件系统 ──
         "fs.cannot_create_dir" => "创建目录失败",
@@ -212,6 +386,7 @@ fn zh(key: &str) -> Option<&'static str> {
         "download.zip_extract_failed" => "ZIP 解压失败",
         "download.tar_extract_failed" => "TAR.GZ 解压失败",
         "download.stream_error" => "下载流错误",
+        "download.no_data" => "未收到任何数据",
         "download.http_error" => "HTTP 请求失败",
         "download.http_status_error" => "下载失败: HTTP",
         "download.create_client_failed" => "创建 HTTP 客户端失败",
@@ -220,6 +395,7 @@ fn zh(key: &str) -> Option<&'static str> {
 
         // ── N8n Process / 进程 ──
         "process.spawn_failed" => "启动 n8n 进程失败",
+        "process.stop_previous_failed" => "停止之前启动的 n8n 进程失败",
         "process.invalid_user_data_path" => "用户数据目录路径包含无效字符",
 
         // ── Cloudflared path ──
@@ -244,6 +420,12 @@ fn zh(key: &str) -> Option<&'static str> {
         "fs.cannot_delete_existing_file" => "删除现有文件失败",
         "fs.cannot_move_to" => "移动文件到失败",
         "fs.cannot_open_archive" => "无法打开压缩包",
+        "archive.too_large" => "压缩包解压后体积超出上限",
+        "archive.invalid_zip" => "ZIP 格式非法",
+        "archive.insufficient_memory" => "可用内存不足，无法解压资源包",
+        "archive.path_too_long" => "文件路径超出 Windows 长度限制，请在系统中启用长路径支持（LongPathsEnabled）后重试",
+        "archive.worker_panicked" => "解压线程异常退出",
+        "archive.malicious_entry" => "压缩包条目指向解压目录之外",
         "fs.cannot_read_archive_entry" => "读取压缩包条目失败",
         "fs.cannot_get_entry_path" => "获取条目路径失败",
         "fs.cannot_create_target_file" => "创建目标文件失败",
@@ -9,15 +9,74 @@ pub fn run() {
     let builder = tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_fs::init())
+        .setup(|app| {
+            // 启动时加载持久化的应用设置，失败时使用默认值
+            if let Err(error) = api::settings::load_settings(app.handle()) {
+                eprintln!("加载应用设置失败，使用默认设置: {error}");
+            }
+            // 应用自身的日志写入数据目录的 logs/app.log
+            if let Ok(dir) = api::n8n::base_dir(app.handle()) {
+                let level = api::settings::settings_lock().app_log_level;
+                services::logging::init(&dir.join(api::n8n::N8N_LOG_DIRNAME), level);
+            }
+            // n8n 进程状态变化统一通过 `n8n-state` 事件通知前端
+            api::n8n::init_state_events(app.handle());
+            // 以 root / 管理员身份运行时提醒用户
+            api::n8n::warn_elevated_privileges(app.handle());
+            // 开启自动启动时在主窗口就绪后启动 n8n
+            api::n8n::auto_launch(app.handle());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // n8n 核心功能
             api::commands::is_installed,
+            api::commands::get_pending_install,
+            api::commands::get_verification_status,
             api::commands::setup_runtime,
             api::commands::setup_n8n,
             api::commands::launch_n8n,
+            api::commands::launch_n8n_safe,
+            api::commands::restart_n8n,
+            api::commands::launch_n8n_and_wait,
+            api::commands::launch_n8n_diagnostic,
             api::commands::shutdown_n8n,
+            api::commands::fix_permissions,
+            api::commands::preflight,
+            api::commands::verify_installation,
+            api::commands::db_status,
+            api::commands::vacuum_database,
+            api::commands::migrate_data_dir,
+            api::commands::set_data_dir,
+            api::commands::get_data_dir,
+            api::commands::export_credentials,
+            api::commands::import_credentials,
+            api::commands::execute_workflow,
+            api::commands::get_log_path,
+            api::commands::get_logs_size,
+            api::commands::clear_logs,
+            api::commands::export_diagnostics,
+            api::commands::get_effective_env,
+            api::commands::get_binary_data_dir,
+            api::commands::platform_info,
+            api::commands::list_installed_versions,
+            api::commands::get_installed_n8n_version,
+            api::commands::activate_version,
+            api::commands::uninstall_n8n,
+            api::commands::pause_download,
+            api::commands::resume_download,
+            api::commands::cancel_setup,
+            api::commands::cancel_download,
+            api::commands::process_metrics,
+            api::commands::start_metrics_sampler,
+            api::commands::stop_metrics_sampler,
+            api::commands::test_connectivity,
+            api::commands::benchmark_mirrors,
             api::commands::proxy_health_check,
+            api::commands::get_n8n_status,
+            api::commands::open_editor_window,
+            api::commands::get_editor_url,
             api::commands::set_nodes_unlocked,
+            api::commands::set_log_level,
             api::commands::get_nodes_unlocked,
             // 隧道功能
             api::commands::start_tunnel,
@@ -34,6 +93,18 @@ pub fn run() {
             // cloudflared 管理
             api::commands::download_cloudflared,
             api::commands::check_cloudflared_version,
+            // 应用设置
+            api::commands::get_settings,
+            api::commands::update_settings,
+            api::commands::reset_settings,
+            api::commands::set_app_log_level,
+            api::commands::set_download_mirror,
+            api::commands::set_http_proxy,
+            api::commands::set_n8n_env,
+            api::commands::set_github_token,
+            api::commands::has_github_token,
+            api::commands::set_postgres_password,
+            api::commands::has_postgres_password,
             // 侧边栏管理
             api::commands::toggle_sidebar,
             // 国际化
@@ -59,8 +130,8 @@ pub fn run() {
 
 fn handle_app_run_event(event: tauri::RunEvent) {
     if let RunEvent::ExitRequested { .. } = event {
-        // 在应用退出前，直接调用 shutdown_n8n
-        api::commands::shutdown_n8n();
+        // 在应用退出前，直接调用 shutdown_n8n（同步等待 n8n 退出）
+        let _ = api::n8n::shutdown_n8n();
 
         // 关闭隧道
         #[cfg(unix)]
@@ -0,0 +1,424 @@
+//! 压缩包体积保护
+//!
+//! 资源包来自公共代理，需防止恶意构造的压缩包（zip bomb）解压后占满磁盘：
+//! ZIP 在解压前累加中央目录中声明的解压后大小，TAR.GZ 在解压过程中限制累计读取的字节数。
+//!
+//! 解压均从磁盘流式读取，逐条目以固定大小的缓冲区拷贝；开始解压前确认系统仍有足够的可用内存，
+//! 内存紧张时直接返回错误，而不是等系统因内存耗尽强制结束应用。
+//!
+//! n8n 的 `node_modules` 嵌套很深，在 Windows 上经常超过 260 字符的 MAX_PATH 限制：
+//! 解压目标使用 `\\?\` 扩展长度路径，仍然失败时返回带 `PATH_TOO_LONG` 错误码的提示。
+//!
+//! ZIP 条目路径含 `..`、绝对路径，或经目标目录中已有的符号链接指向目录之外时（zip slip），
+//! 拒绝整个压缩包并返回带 `MALICIOUS_ARCHIVE` 错误码的提示。
+
+use crate::i18n;
+use std::io::{self, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use sysinfo::System;
+use zip::read::ZipFile;
+use zip::result::ZipResult;
+use zip::ZipArchive;
+
+/// 单个压缩包解压后允许的最大体积（2GB）
+pub const MAX_ARCHIVE_UNCOMPRESSED_SIZE: u64 = 2 * 1024 * 1024 * 1024;
+
+/// 体积超限错误码
+pub const ARCHIVE_TOO_LARGE_CODE: &str = "ARCHIVE_TOO_LARGE";
+
+/// 生成体积超限的错误信息
+pub fn too_large_message(limit: u64) -> String {
+    format!(
+        "[{ARCHIVE_TOO_LARGE_CODE}] {} ({} MB)",
+        i18n::t("archive.too_large"),
+        limit / 1024 / 1024
+    )
+}
+
+/// 解压拷贝时使用的缓冲区大小（64KB）
+pub const EXTRACT_COPY_BUFFER_SIZE: usize = 64 * 1024;
+
+/// 自动选择时使用的最大解压线程数（避免机械硬盘上大量并发写入反而变慢）
+pub const MAX_AUTO_EXTRACTION_THREADS: usize = 4;
+
+/// 设置中允许的最大解压线程数
+pub const MAX_EXTRACTION_THREADS: usize = 16;
+
+/// 计算解压线程数（`configured` 为 0 时按 CPU 核数自动选择）
+pub fn extraction_threads(configured: usize) -> usize {
+    if configured > 0 {
+        return configured.min(MAX_EXTRACTION_THREADS);
+    }
+
+    std::thread::available_parallelism()
+        .map(|threads| threads.get())
+        .unwrap_or(1)
+        .min(MAX_AUTO_EXTRACTION_THREADS)
+}
+
+/// 开始解压前要求的最低可用内存（256MB）
+pub const MIN_AVAILABLE_MEMORY: u64 = 256 * 1024 * 1024;
+
+/// 内存不足错误码
+pub const INSUFFICIENT_MEMORY_CODE: &str = "INSUFFICIENT_MEMORY";
+
+/// 检查系统可用内存是否足以开始解压
+pub fn ensure_memory_headroom(min_available: u64) -> Result<(), String> {
+    let mut system = System::new();
+    system.refresh_memory();
+
+    let available = system.available_memory();
+    // 部分平台无法读取内存信息（返回 0），此时不做限制
+    if available == 0 || available >= min_available {
+        return Ok(());
+    }
+
+    Err(format!(
+        "[{INSUFFICIENT_MEMORY_CODE}] {} ({} MB < {} MB)",
+        i18n::t("archive.insufficient_memory"),
+        available / 1024 / 1024,
+        min_available / 1024 / 1024
+    ))
+}
+
+/// 以固定大小的缓冲区拷贝单个条目，避免大文件占用过多内存
+pub fn copy_bounded<R: Read + ?Sized, W: Write + ?Sized>(
+    reader: &mut R,
+    writer: &mut W,
+) -> io::Result<u64> {
+    let mut buffer = vec![0u8; EXTRACT_COPY_BUFFER_SIZE];
+    let mut copied: u64 = 0;
+
+    loop {
+        let n = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(copied),
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        writer.write_all(&buffer[..n])?;
+        copied += n as u64;
+    }
+}
+
+/// 解压目录守卫
+///
+/// 包裹一次解压过程：未调用 `commit` 就离开作用域（出错、取消或提前返回）时删除整个目录，
+/// 保证失败的安装不会留下半成品。
+pub struct ExtractionDirGuard {
+    path: PathBuf,
+    committed: bool,
+}
+
+impl ExtractionDirGuard {
+    /// 守护 `path` 目录
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            committed: false,
+        }
+    }
+
+    /// 解压成功，保留目录
+    pub fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl Drop for ExtractionDirGuard {
+    fn drop(&mut self) {
+        if !self.committed && self.path.exists() {
+            eprintln!("清理未完成的解压目录: {}", self.path.display());
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+}
+
+/// 路径过长错误码
+pub const PATH_TOO_LONG_CODE: &str = "PATH_TOO_LONG";
+
+/// Windows 传统路径长度上限（MAX_PATH）
+#[cfg(windows)]
+const WINDOWS_MAX_PATH: usize = 260;
+
+/// Windows 文件名或扩展名过长的系统错误码（ERROR_FILENAME_EXCED_RANGE）
+#[cfg(windows)]
+const ERROR_FILENAME_EXCED_RANGE: i32 = 206;
+
+/// 转换为 Windows 扩展长度路径（`\\?\C:\...` / `\\?\UNC\server\share\...`），其他平台原样返回
+///
+/// 扩展长度路径不做任何规范化，只对已是绝对路径的目录转换。
+pub fn extended_length_path(path: &Path) -> PathBuf {
+    #[cfg(windows)]
+    if let Some(raw) = path.to_str().filter(|_| path.is_absolute()) {
+        if raw.starts_with(r"\\?\") {
+            return path.to_path_buf();
+        }
+        let normalized = raw.replace('/', "\\");
+        return match normalized.strip_prefix(r"\\") {
+            Some(unc) => PathBuf::from(format!(r"\\?\UNC\{unc}")),
+            None => PathBuf::from(format!(r"\\?\{normalized}")),
+        };
+    }
+
+    path.to_path_buf()
+}
+
+/// 将压缩包条目的相对路径逐段拼接到目标目录（扩展长度路径不识别 `/` 分隔符）
+pub fn join_entry_path(root: &Path, relative: &Path) -> PathBuf {
+    let mut path = root.to_path_buf();
+    path.extend(relative.components());
+    path
+}
+
+/// 恶意条目错误码
+pub const MALICIOUS_ARCHIVE_CODE: &str = "MALICIOUS_ARCHIVE";
+
+/// 生成恶意条目的错误信息
+pub fn malicious_entry_message(name: &str) -> String {
+    format!("[{MALICIOUS_ARCHIVE_CODE}] {}: {name}", i18n::t("archive.malicious_entry"))
+}
+
+/// 计算 ZIP 条目在 `root` 下的输出路径，条目会逃逸出 `root` 时返回 `MALICIOUS_ARCHIVE` 错误
+pub fn zip_entry_outpath(root: &Path, entry: &ZipFile) -> Result<PathBuf, String> {
+    let relative = entry
+        .enclosed_name()
+        .ok_or_else(|| malicious_entry_message(entry.name()))?;
+    let outpath = join_entry_path(root, relative);
+    if !is_within_root(root, &outpath) {
+        return Err(malicious_entry_message(entry.name()));
+    }
+    Ok(outpath)
+}
+
+/// 输出路径解析符号链接后是否仍位于 `root` 内
+///
+/// 输出路径尚未创建，按其最深的已存在祖先规范化后做前缀比较；
+/// 悬空的符号链接无法规范化，写入时会跟随到未知位置，同样视为逃逸。
+fn is_within_root(root: &Path, outpath: &Path) -> bool {
+    let Ok(root) = root.canonicalize() else {
+        // 目标目录尚未创建时没有可被利用的符号链接
+        return !root.exists();
+    };
+    let Some(existing) = outpath
+        .ancestors()
+        .find(|path| path.symlink_metadata().is_ok())
+    else {
+        return false;
+    };
+
+    existing
+        .canonicalize()
+        .is_ok_and(|resolved| resolved.starts_with(&root))
+}
+
+/// IO 错误由路径过长引起时返回 `PATH_TOO_LONG` 提示
+pub fn path_too_long_message(path: &Path, error: &io::Error) -> Option<String> {
+    #[cfg(windows)]
+    {
+        let raw_len = path
+            .to_str()
+            .map(|raw| raw.trim_start_matches(r"\\?\").len())
+            .unwrap_or_default();
+        if error.raw_os_error() == Some(ERROR_FILENAME_EXCED_RANGE) || raw_len >= WINDOWS_MAX_PATH {
+            return Some(format!(
+                "[{PATH_TOO_LONG_CODE}] {}: {}",
+                i18n::t("archive.path_too_long"),
+                path.display()
+            ));
+        }
+    }
+
+    #[cfg(not(windows))]
+    let _ = (path, error);
+    None
+}
+
+/// 累加 ZIP 中央目录声明的解压后总大小
+pub fn zip_uncompressed_size<R: Read + Seek>(archive: &mut ZipArchive<R>) -> ZipResult<u64> {
+    let mut total: u64 = 0;
+    for i in 0..archive.len() {
+        total = total.saturating_add(archive.by_index_raw(i)?.size());
+    }
+    Ok(total)
+}
+
+/// 解压前检查 ZIP 声明的总大小是否超出上限
+pub fn ensure_zip_within_limit<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    limit: u64,
+) -> Result<(), String> {
+    let total = zip_uncompressed_size(archive).map_err(|e| format!("{}: {e}", i18n::t("archive.invalid_zip")))?;
+    if total > limit {
+        return Err(too_large_message(limit));
+    }
+    Ok(())
+}
+
+/// 限制累计读取字节数的读取器，用于流式解压（如 TAR.GZ）
+pub struct SizeCappedReader<R> {
+    inner: R,
+    read: u64,
+    limit: u64,
+}
+
+impl<R: Read> SizeCappedReader<R> {
+    /// 创建带上限的读取器
+    pub fn new(inner: R, limit: u64) -> Self {
+        Self {
+            inner,
+            read: 0,
+            limit,
+        }
+    }
+}
+
+impl<R: Read> Read for SizeCappedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read = self.read.saturating_add(n as u64);
+        if self.read > self.limit {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                too_large_message(self.limit),
+            ));
+        }
+        Ok(n)
+    }
+}
+
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Cursor;
+    use zip::write::FileOptions;
+    use zip::{CompressionMethod, ZipWriter};
+
+    /// 构造一个高压缩比的 ZIP（1MB 的零字节压缩后仅约 1KB）
+    fn zip_bomb_fixture() -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        for name in ["a.bin", "b.bin"] {
+            writer.start_file(name, options).expect("Failed to start zip entry");
+            writer
+                .write_all(&vec![0u8; 1024 * 1024])
+                .expect("Failed to write zip entry");
+        }
+        writer.finish().expect("Failed to finish zip").into_inner()
+    }
+
+    #[test]
+    fn test_zip_bomb_rejected_by_declared_size() {
+        let bytes = zip_bomb_fixture();
+        assert!(bytes.len() < 64 * 1024);
+
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).expect("Failed to open zip");
+        assert_eq!(
+            zip_uncompressed_size(&mut archive).expect("Failed to sum sizes"),
+            2 * 1024 * 1024
+        );
+
+        let result = ensure_zip_within_limit(&mut archive, 1024 * 1024);
+        assert!(result.is_err_and(|e| e.contains(ARCHIVE_TOO_LARGE_CODE)));
+        assert!(ensure_zip_within_limit(&mut archive, MAX_ARCHIVE_UNCOMPRESSED_SIZE).is_ok());
+    }
+
+    #[test]
+    fn test_gzip_stream_capped_while_reading() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        encoder
+            .write_all(&vec![0u8; 1024 * 1024])
+            .expect("Failed to write gzip");
+        let compressed = encoder.finish().expect("Failed to finish gzip");
+
+        let decoder = flate2::read::GzDecoder::new(Cursor::new(compressed));
+        let mut reader = SizeCappedReader::new(decoder, 64 * 1024);
+        let error = io::copy(&mut reader, &mut io::sink()).expect_err("cap should be enforced");
+        assert!(error.to_string().contains(ARCHIVE_TOO_LARGE_CODE));
+    }
+
+    /// 构造只含一个指定路径条目的 ZIP
+    fn single_entry_zip(name: &str) -> ZipArchive<Cursor<Vec<u8>>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer
+            .start_file(name, FileOptions::default())
+            .expect("Failed to start zip entry");
+        writer.write_all(b"evil").expect("Failed to write zip entry");
+        let bytes = writer.finish().expect("Failed to finish zip").into_inner();
+        ZipArchive::new(Cursor::new(bytes)).expect("Failed to open zip")
+    }
+
+    #[test]
+    fn test_zip_entry_outpath_rejects_traversal() {
+        let root = std::env::temp_dir().join("n8n_zip_slip_test");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("target")).expect("创建测试目录失败");
+        let target = root.join("target");
+
+        let mut archive = single_entry_zip("../../evil");
+        let entry = archive.by_index(0).expect("Failed to read zip entry");
+        let error = zip_entry_outpath(&target, &entry).expect_err("traversal should be rejected");
+        assert!(error.contains(MALICIOUS_ARCHIVE_CODE));
+        drop(entry);
+
+        let mut archive = single_entry_zip("node_modules/n8n/package.json");
+        let entry = archive.by_index(0).expect("Failed to read zip entry");
+        let outpath = zip_entry_outpath(&target, &entry).expect("normal entry should be allowed");
+        assert!(outpath.starts_with(&target));
+        drop(entry);
+
+        // 目标目录中已有指向外部的符号链接时，经由它的条目同样被拒绝
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&root, target.join("link")).expect("创建符号链接失败");
+            let mut archive = single_entry_zip("link/evil");
+            let entry = archive.by_index(0).expect("Failed to read zip entry");
+            let result = zip_entry_outpath(&target, &entry);
+            assert!(result.is_err_and(|e| e.contains(MALICIOUS_ARCHIVE_CODE)));
+        }
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_join_entry_path_appends_each_component() {
+        let root = extended_length_path(Path::new("/tmp/n8n-core"));
+        let joined = join_entry_path(&root, Path::new("node_modules/n8n/package.json"));
+        assert!(joined.starts_with(&root));
+        assert!(joined.ends_with(Path::new("n8n").join("package.json")));
+    }
+
+    #[test]
+    fn test_extraction_dir_guard_removes_uncommitted_dir() {
+        let dir = std::env::temp_dir().join("n8n_extraction_guard_test");
+        std::fs::create_dir_all(dir.join("node_modules")).expect("创建测试目录失败");
+        drop(ExtractionDirGuard::new(&dir));
+        assert!(!dir.exists());
+
+        std::fs::create_dir_all(&dir).expect("创建测试目录失败");
+        ExtractionDirGuard::new(&dir).commit();
+        assert!(dir.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_copy_bounded_copies_across_buffer_boundaries() {
+        let data = vec![7u8; EXTRACT_COPY_BUFFER_SIZE * 2 + 123];
+        let mut output = Vec::new();
+
+        let copied = copy_bounded(&mut Cursor::new(&data), &mut output).expect("copy should succeed");
+        assert_eq!(copied, data.len() as u64);
+        assert_eq!(output, data);
+    }
+
+    #[test]
+    fn test_extraction_threads_respects_limits() {
+        assert_eq!(extraction_threads(2), 2);
+        assert_eq!(extraction_threads(64), MAX_EXTRACTION_THREADS);
+        let auto = extraction_threads(0);
+        assert!((1..=MAX_AUTO_EXTRACTION_THREADS).contains(&auto));
+    }
+}
@@ -0,0 +1,208 @@
+//! 下载控制（暂停 / 继续 / 取消）
+//!
+//! 每个进行中的下载以其 `download_type`（如 `runtime`、`n8n-core`）作为操作标识注册一个控制句柄，
+//! 前端通过标识暂停或继续下载；下载循环据此停止读取数据流并在继续时发起 Range 请求。
+//!
+//! 同时维护按 `download_type` 区分的操作锁，防止同一资源的下载 / 解压被重复触发而相互覆盖；
+//! 操作锁附带取消标记，下载循环与解压的逐条目循环据此中止整个安装流程。
+
+use crate::i18n;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+use tokio::sync::watch;
+
+/// 进行中的下载控制句柄注册表
+static DOWNLOAD_CONTROLS: LazyLock<Mutex<HashMap<String, Arc<DownloadControl>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn controls_lock() -> MutexGuard<'static, HashMap<String, Arc<DownloadControl>>> {
+    DOWNLOAD_CONTROLS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// 取消错误码
+pub const CANCELED_CODE: &str = "CANCELED";
+
+/// 正在进行中的安装操作及其取消标记（按 `download_type` 区分）
+static ACTIVE_OPERATIONS: LazyLock<Mutex<HashMap<String, Arc<AtomicBool>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn operations_lock() -> MutexGuard<'static, HashMap<String, Arc<AtomicBool>>> {
+    ACTIVE_OPERATIONS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// 操作锁守卫，离开作用域（包括出错提前返回）时自动释放
+pub struct OperationGuard {
+    operation_id: String,
+}
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        operations_lock().remove(&self.operation_id);
+    }
+}
+
+/// 尝试占用操作锁，同一操作已在进行中时返回 `None`
+pub fn begin_operation(operation_id: &str) -> Option<OperationGuard> {
+    let mut operations = operations_lock();
+    if operations.contains_key(operation_id) {
+        return None;
+    }
+
+    operations.insert(operation_id.to_string(), Arc::new(AtomicBool::new(false)));
+    Some(OperationGuard {
+        operation_id: operation_id.to_string(),
+    })
+}
+
+/// 取消进行中的操作（等待数据或暂停中的下载会被唤醒以便尽快退出）
+pub fn cancel(operation_id: &str) -> Result<(), String> {
+    let canceled = operations_lock()
+        .get(operation_id)
+        .cloned()
+        .ok_or_else(|| format!("{}: {operation_id}", i18n::t("download.not_found")))?;
+    canceled.store(true, Ordering::SeqCst);
+
+    if let Ok(control) = find(operation_id) {
+        control.canceled.send_replace(true);
+        control.paused.send_replace(false);
+    }
+    Ok(())
+}
+
+/// 取消所有进行中的操作，返回被取消的操作标识
+pub fn cancel_all() -> Vec<String> {
+    let operation_ids: Vec<String> = operations_lock().keys().cloned().collect();
+    operation_ids
+        .into_iter()
+        .filter(|operation_id| cancel(operation_id).is_ok())
+        .collect()
+}
+
+/// 操作是否已被取消
+pub fn is_canceled(operation_id: &str) -> bool {
+    operations_lock()
+        .get(operation_id)
+        .is_some_and(|canceled| canceled.load(Ordering::SeqCst))
+}
+
+/// 生成取消错误信息
+pub fn canceled_error() -> String {
+    format!("[{CANCELED_CODE}] {}", i18n::t("setup.canceled"))
+}
+
+/// 单个下载的控制句柄
+pub struct DownloadControl {
+    paused: watch::Sender<bool>,
+    canceled: watch::Sender<bool>,
+}
+
+impl DownloadControl {
+    fn new() -> Self {
+        let (paused, _) = watch::channel(false);
+        let (canceled, _) = watch::channel(false);
+        Self { paused, canceled }
+    }
+
+    /// 是否处于暂停状态
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// 等待进入指定状态（`true` 为暂停，`false` 为继续）
+    pub async fn wait_until_paused(&self, paused: bool) {
+        let mut receiver = self.paused.subscribe();
+        let _ = receiver.wait_for(|value| *value == paused).await;
+    }
+
+    /// 等待下载被取消（用于在等待数据时立即中断连接）
+    pub async fn wait_until_canceled(&self) {
+        let mut receiver = self.canceled.subscribe();
+        let _ = receiver.wait_for(|canceled| *canceled).await;
+    }
+}
+
+/// 注册守卫，离开作用域时自动注销控制句柄
+pub struct DownloadControlGuard {
+    operation_id: String,
+    control: Arc<DownloadControl>,
+}
+
+impl DownloadControlGuard {
+    /// 获取控制句柄
+    pub fn control(&self) -> &DownloadControl {
+        &self.control
+    }
+}
+
+impl Drop for DownloadControlGuard {
+    fn drop(&mut self) {
+        let mut controls = controls_lock();
+        // 仅移除自己注册的句柄，避免误删同名的新下载
+        if controls
+            .get(&self.operation_id)
+            .is_some_and(|control| Arc::ptr_eq(control, &self.control))
+        {
+            controls.remove(&self.operation_id);
+        }
+    }
+}
+
+/// 为下载注册控制句柄
+pub fn register(operation_id: &str) -> DownloadControlGuard {
+    let control = Arc::new(DownloadControl::new());
+    controls_lock().insert(operation_id.to_string(), control.clone());
+    DownloadControlGuard {
+        operation_id: operation_id.to_string(),
+        control,
+    }
+}
+
+fn find(operation_id: &str) -> Result<Arc<DownloadControl>, String> {
+    controls_lock()
+        .get(operation_id)
+        .cloned()
+        .ok_or_else(|| format!("{}: {operation_id}", i18n::t("download.not_found")))
+}
+
+/// 暂停下载
+pub fn pause(operation_id: &str) -> Result<(), String> {
+    find(operation_id)?.paused.send_replace(true);
+    Ok(())
+}
+
+/// 继续下载
+pub fn resume(operation_id: &str) -> Result<(), String> {
+    find(operation_id)?.paused.send_replace(false);
+    Ok(())
+}
+
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_lock_is_released_on_drop() {
+        let guard = begin_operation("test-operation");
+        assert!(guard.is_some());
+        assert!(begin_operation("test-operation").is_none());
+
+        drop(guard);
+        assert!(begin_operation("test-operation").is_some());
+    }
+
+    #[test]
+    fn test_cancel_marks_active_operation() {
+        assert!(cancel("test-cancel").is_err());
+
+        let _guard = begin_operation("test-cancel").expect("operation should start");
+        assert!(!is_canceled("test-cancel"));
+        cancel("test-cancel").expect("cancel should succeed");
+        assert!(is_canceled("test-cancel"));
+    }
+}
@@ -1,8 +1,15 @@
+use crate::api::settings::settings_lock;
 use crate::i18n;
+use crate::services::download_control::{self, DownloadControl};
+use crate::services::{archive, http};
+use flate2::read::GzDecoder;
+use futures_util::future::try_join_all;
 use futures_util::StreamExt;
+use std::convert::Infallible;
 use std::fs;
-use std::io::Cursor;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 use tauri::{Emitter, Runtime, Window};
 
@@ -12,9 +19,6 @@ use std::os::unix::fs::PermissionsExt;
 
 // --- 常量定义 ---
 
-/// 用户代理字符串
-const USER_AGENT: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36";
-
 /// 进度更新最小增量（百分比）
 const PROGRESS_UPDATE_MIN_INCREMENT: f64 = 0.5;
 
@@ -28,12 +32,100 @@ const EXECUTABLE_PERMISSIONS_MODE: u32 = 0o755; // rwxr-xr-x
 /// 存档文件扩展名
 const ARCHIVE_EXTENSIONS: [&str; 3] = [".tar.gz", ".tgz", ".zip"];
 
+/// 下载临时文件后缀
+const PART_FILE_SUFFIX: &str = ".part";
+
+/// GZIP 魔数
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// 建立下载连接失败时的最大重试次数
+const DOWNLOAD_MAX_RETRIES: u32 = 3;
+
+/// 首次重试前的等待时间（之后每次翻倍：1s / 2s / 4s）
+const DOWNLOAD_RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// 无法计算下载百分比时 `download-progress` 事件中的进度值
+const INDETERMINATE_PROGRESS: f64 = -1.0;
+
+/// 下载速度指数平滑系数（越大越贴近瞬时速度）
+const SPEED_SMOOTHING_FACTOR: f64 = 0.3;
+
+/// 解压进度事件的发送粒度（每完成约 0.5% 的条目发送一次）
+const EXTRACTION_PROGRESS_STEPS: usize = 200;
+
+/// 存档下载时，下载阶段在整体进度中所占的百分比（其余为解压阶段）
+pub(crate) const DOWNLOAD_PHASE_WEIGHT: f64 = 70.0;
+
+/// 整体进度区间（起点，终点）
+pub type OverallRange = (f64, f64);
+
+/// 下载独占整条整体进度时的区间
+const FULL_OVERALL_RANGE: OverallRange = (0.0, 100.0);
+
 // --- 数据结构 ---
 
+/// `download-progress` 事件负载
+///
+/// 服务器未返回 `Content-Length` 时无法计算百分比，`progress` 为 [`INDETERMINATE_PROGRESS`]，
+/// 前端据此改为显示已下载字节数与加载动画。
 #[derive(Clone, serde::Serialize)]
 struct Progress {
     progress: f64,
     download_type: String,
+    /// 已下载字节数（下载完成事件中为 `None`）
+    bytes_downloaded: Option<u64>,
+    /// 文件总大小（未知时为 `None`）
+    total_bytes: Option<u64>,
+    /// 平滑后的下载速度（字节/秒，尚无采样时为 `None`）
+    speed_bps: Option<u64>,
+    /// 预计剩余秒数（总大小或速度未知时为 `None`）
+    eta_secs: Option<u64>,
+}
+
+/// 下载速度估算：每次发送进度时按两次发送之间传输的字节数采样，并做指数平滑
+struct SpeedMeter {
+    last_bytes: u64,
+    last_time: Instant,
+    smoothed: Option<f64>,
+}
+
+impl SpeedMeter {
+    fn new(downloaded: u64) -> Self {
+        Self {
+            last_bytes: downloaded,
+            last_time: Instant::now(),
+            smoothed: None,
+        }
+    }
+
+    /// 从 `downloaded` 字节处重新开始计时（暂停后继续或重新下载时，避免等待时间拉低速度）
+    fn reset(&mut self, downloaded: u64) {
+        self.last_bytes = downloaded;
+        self.last_time = Instant::now();
+    }
+
+    /// 记录一次采样，返回平滑后的速度（字节/秒）
+    fn sample(&mut self, downloaded: u64, now: Instant) -> Option<f64> {
+        let seconds = now.saturating_duration_since(self.last_time).as_secs_f64();
+        if seconds > 0.0 {
+            let instant_speed = downloaded.saturating_sub(self.last_bytes) as f64 / seconds;
+            self.smoothed = Some(match self.smoothed {
+                Some(previous) => {
+                    previous + SPEED_SMOOTHING_FACTOR * (instant_speed - previous)
+                }
+                None => instant_speed,
+            });
+            self.last_bytes = downloaded;
+            self.last_time = now;
+        }
+        self.smoothed
+    }
+}
+
+/// 按剩余字节数与速度估算剩余秒数
+fn estimate_eta_secs(downloaded: u64, total: u64, speed_bps: f64) -> Option<u64> {
+    (total > 0 && speed_bps > 0.0)
+        .then(|| (total.saturating_sub(downloaded) as f64 / speed_bps).ceil() as u64)
 }
 
 #[derive(Clone, serde::Serialize)]
@@ -41,13 +133,143 @@ pub struct ExtractionStart {
     pub download_type: String,
 }
 
+/// 下载开始事件负载（响应头到达后立即发送）
+#[derive(Clone, serde::Serialize)]
+pub struct DownloadStarted {
+    pub download_type: String,
+    /// 文件总大小（服务器未返回 `Content-Length` 时为 `None`，前端可改为不确定进度）
+    pub total_bytes: Option<u64>,
+    /// 下载源主机名
+    pub url_host: String,
+}
+
+/// 下载暂停 / 继续 / 取消事件负载
+#[derive(Clone, serde::Serialize)]
+pub struct DownloadControlEvent {
+    pub download_type: String,
+    /// 已写入临时文件的字节数（继续时的 Range 起点）
+    pub downloaded: u64,
+}
+
+/// `download-retry` 事件负载
+#[derive(Clone, serde::Serialize)]
+pub struct DownloadRetry {
+    pub download_type: String,
+    /// 第几次重试（从 1 开始）
+    pub attempt: u32,
+    pub max_attempts: u32,
+    /// 触发重试的网络错误或 HTTP 状态
+    pub reason: String,
+}
+
+/// `extraction-progress` 事件负载
+#[derive(Clone, serde::Serialize)]
+pub struct ExtractionProgressEvent {
+    pub download_type: String,
+    pub extracted_entries: usize,
+    pub total_entries: usize,
+    /// 百分比（0-100）
+    pub percent: f64,
+}
+
+/// 整体进度（下载与解压加权合并，单次操作内单调不减）
+#[derive(Clone, serde::Serialize)]
+pub struct OverallProgress {
+    pub progress: f64,
+    pub download_type: String,
+}
+
+/// 安装阶段（downloading / extracting / done），作为整体进度的阶段标签
+#[derive(Clone, serde::Serialize)]
+pub struct SetupStage {
+    pub stage: String,
+    pub download_type: String,
+}
+
+/// 整体进度追踪器
+///
+/// 将下载与解压两个阶段映射到同一条进度的 `range` 区间上（默认 0-100），并保证发送的值不会回退。
+/// 下载只是更大操作（如 n8n 核心包安装）中的一个阶段时，由调用方指定区间。
+struct OverallProgressTracker {
+    /// 下载阶段所占权重（无解压阶段时为 100）
+    download_weight: f64,
+    /// 本次下载在整体进度中占据的区间
+    range: OverallRange,
+    /// 已发送的最大进度
+    last_progress: Mutex<f64>,
+}
+
+impl OverallProgressTracker {
+    fn new(has_extraction: bool) -> Self {
+        Self {
+            download_weight: if has_extraction {
+                DOWNLOAD_PHASE_WEIGHT
+            } else {
+                100.0
+            },
+            range: FULL_OVERALL_RANGE,
+            last_progress: Mutex::new(0.0),
+        }
+    }
+
+    /// 将下载阶段进度映射为整体进度
+    fn download(&self, percent: f64) -> f64 {
+        self.scale(percent.clamp(0.0, 100.0) * self.download_weight / 100.0)
+    }
+
+    /// 将解压阶段进度映射为整体进度
+    fn extraction(&self, percent: f64) -> f64 {
+        self.scale(
+            self.download_weight + percent.clamp(0.0, 100.0) * (100.0 - self.download_weight) / 100.0,
+        )
+    }
+
+    /// 将 0-100 的进度映射到本次下载的区间
+    fn scale(&self, percent: f64) -> f64 {
+        let (start, end) = self.range;
+        start + percent * (end - start) / 100.0
+    }
+
+    /// 下载是否独占整条整体进度
+    fn is_full_range(&self) -> bool {
+        self.range == FULL_OVERALL_RANGE
+    }
+
+    /// 发送整体进度（小于已发送值时忽略）
+    fn emit<R: Runtime>(&self, window: &Window<R>, download_type: &str, progress: f64) {
+        let Ok(mut last_progress) = self.last_progress.lock() else {
+            return;
+        };
+        if progress < *last_progress {
+            return;
+        }
+        *last_progress = progress;
+        emit_overall_progress(window, download_type, progress);
+    }
+}
+
+/// 发送整体进度事件
+pub(crate) fn emit_overall_progress<R: Runtime>(window: &Window<R>, download_type: &str, progress: f64) {
+    let _ = window.emit(
+        "overall-progress",
+        OverallProgress {
+            progress,
+            download_type: download_type.to_string(),
+        },
+    );
+}
+
 /// 下载配置参数
 struct DownloadConfig {
     url: String,
     destination: PathBuf,
+    /// 下载过程中写入的临时文件（`<destination>.part`）
+    part_path: PathBuf,
     download_type: String,
     is_archive: bool,
     destination_is_file: bool,
+    /// 整体进度追踪
+    overall: OverallProgressTracker,
 }
 
 // --- 主下载函数 ---
@@ -60,9 +282,55 @@ pub async fn download_file<R: Runtime>(
     dest: PathBuf,
     download_type: String,
 ) -> Result<(), String> {
-    let config = analyze_download_config(&url, &dest, download_type);
+    download_file_in_range(window, url, dest, download_type, FULL_OVERALL_RANGE).await
+}
+
+/// 下载文件，整体进度映射到 `range` 区间
+///
+/// 用于下载只是更大操作中一个阶段的场景：完成时整体进度停在区间终点，
+/// 也不发送 `done` 阶段，由调用方在整个操作结束后发送。
+pub async fn download_file_in_range<R: Runtime>(
+    window: Window<R>,
+    url: String,
+    dest: PathBuf,
+    download_type: String,
+    range: OverallRange,
+) -> Result<(), String> {
+    let mut config = analyze_download_config(&url, &dest, download_type);
+    config.overall.range = range;
+    tracing::info!(
+        download_type = %config.download_type,
+        url = %config.url,
+        dest = %config.destination.display(),
+        "开始下载"
+    );
 
-    process_downloaded_content(&window, &config).await?;
+    if let Err(error) = process_downloaded_content(&window, &config).await {
+        tracing::error!(download_type = %config.download_type, url = %config.url, %error, "下载失败");
+        return Err(error);
+    }
+    finalize_download(&window, &config);
+    tracing::info!(download_type = %config.download_type, "下载完成");
+
+    Ok(())
+}
+
+/// 从本地存档安装（离线部署），跳过网络下载直接解压到目标目录
+///
+/// 源存档保持不变，进度与阶段事件与在线下载一致。
+pub fn install_from_local_archive<R: Runtime>(
+    window: Window<R>,
+    archive_path: PathBuf,
+    dest: PathBuf,
+    download_type: String,
+) -> Result<(), String> {
+    let mut config = analyze_download_config(&archive_path.to_string_lossy(), &dest, download_type);
+    if !config.is_archive || config.destination_is_file {
+        return Err(format!("不支持的离线资源包: {}", archive_path.display()));
+    }
+    config.part_path = archive_path;
+
+    handle_archive_download(&window, &config)?;
     finalize_download(&window, &config);
 
     Ok(())
@@ -79,69 +347,781 @@ fn analyze_download_config(url: &str, dest: &Path, download_type: String) -> Dow
     DownloadConfig {
         url: url.to_string(),
         destination: dest.to_path_buf(),
+        part_path: part_path_for(dest),
         download_type,
         is_archive,
         destination_is_file,
+        overall: OverallProgressTracker::new(is_archive && !destination_is_file),
     }
 }
 
-/// 执行带进度显示的下载
+/// 获取下载临时文件路径
+fn part_path_for(dest: &Path) -> PathBuf {
+    let mut part_path = dest.as_os_str().to_os_string();
+    part_path.push(PART_FILE_SUFFIX);
+    PathBuf::from(part_path)
+}
+
+/// 执行带进度显示的下载，数据流式写入临时文件
+///
+/// 写入经过 `BufWriter` 缓冲，缓冲区大小与刷盘间隔可通过设置调整。
+/// 上次下载中断留下临时文件时从其末尾续传（`Range: bytes=<offset>-`），服务器返回 200 时从头下载。
+/// 下载期间可通过 `download_control` 暂停：暂停时断开数据流并落盘，继续时从已写入的偏移发起 Range 请求。
+/// 被取消时立即断开连接并返回 `CANCELED` 错误。
+/// 设置的连接数大于 1 且服务器支持 Range 时，较大的文件从头下载时改为多连接分段下载（见 `SegmentedDownload`）；
+/// 分段下载中断后按其进度记录续传，服务器不再支持 Range 或文件已变化时丢弃临时文件从头下载。
+/// 已下载字节数与文件总大小一致时才算完成，否则保留临时文件供下次续传。
 async fn download_with_progress<R: Runtime>(
     window: &Window<R>,
     config: &DownloadConfig,
-) -> Result<Vec<u8>, String> {
+) -> Result<u64, String> {
+    let control_guard = download_control::register(&config.download_type);
+    let control = control_guard.control();
+    if download_control::is_canceled(&config.download_type) {
+        return Err(download_control::canceled_error());
+    }
+
+    let (buffer_size, flush_interval, connections) = {
+        let settings = settings_lock();
+        (
+            settings.effective_download_buffer_size(),
+            settings.download_flush_interval,
+            settings.effective_download_connections(),
+        )
+    };
+    ensure_parent_directory_exists(&config.part_path)?;
+    let mut offset = fs::metadata(&config.part_path).map(|m| m.len()).unwrap_or(0);
+    if offset > 0 {
+        tracing::info!(download_type = %config.download_type, offset, "发现未完成的下载，尝试续传");
+    }
+
     let client = create_http_client()?;
-    let response = fetch_http_response(&client, &config.url).await?;
-    validate_http_response(&response)?;
+    // 上次是分段下载时按进度记录续传；没有可续传的临时文件时，服务器支持 Range 请求则改为多连接分段下载
+    let saved_segments = SegmentCheckpoint::load(&config.part_path).filter(|_| offset > 0);
+    if saved_segments.is_some() || (connections > 1 && offset == 0) {
+        if let Some(total) = probe_range_support(&client, &config.url).await {
+            let checkpoint = saved_segments
+                .filter(|saved| saved.url == config.url && saved.total == total)
+                .unwrap_or_else(|| SegmentCheckpoint::new(&config.url, total, connections));
+            let segmented = SegmentedDownload {
+                window,
+                client: &client,
+                config,
+                control,
+                buffer_size,
+                flush_interval,
+            };
+            return segmented.run(checkpoint).await;
+        }
+        tracing::info!(
+            download_type = %config.download_type,
+            "服务器不支持分段下载或文件较小，使用单连接下载"
+        );
+        if saved_segments.is_some() {
+            // 分段写入的临时文件中间有空洞，无法按单连接续传
+            SegmentCheckpoint::remove(&config.part_path);
+            offset = 0;
+        }
+    }
+
+    let (mut response, mut writer, mut downloaded, total_bytes) =
+        open_download(window, &client, config, offset, buffer_size).await?;
 
-    let total_size = response.content_length().unwrap_or(0);
-    let mut stream = response.bytes_stream();
-    let mut buffer = Vec::new();
-    let mut downloaded = 0;
+    tracing::info!(download_type = %config.download_type, ?total_bytes, "已收到响应头");
+    notify_download_started(window, config, total_bytes);
+    let total_size = total_bytes.unwrap_or(0);
+    let mut unflushed = 0;
 
     let mut last_emit_time = Instant::now();
     let mut last_emit_progress = -1.0;
+    let mut speed = SpeedMeter::new(downloaded);
+
+    loop {
+        let mut stream = response.bytes_stream();
+        let mut paused = false;
+
+        loop {
+            let chunk_result = tokio::select! {
+                next = stream.next() => match next {
+                    Some(chunk_result) => chunk_result,
+                    None => break,
+                },
+                _ = control.wait_until_paused(true) => {
+                    paused = true;
+                    break;
+                }
+                _ = control.wait_until_canceled() => {
+                    return Err(download_control::canceled_error());
+                }
+            };
+
+            if download_control::is_canceled(&config.download_type) {
+                return Err(download_control::canceled_error());
+            }
 
-    while let Some(chunk_result) = stream.next().await {
-        let chunk = chunk_result.map_err(|e| format!("下载流错误: {e}"))?;
-        buffer.extend_from_slice(&chunk);
-        downloaded += chunk.len() as u64;
+            let chunk = chunk_result.map_err(|e| format!("下载流错误: {e}"))?;
+            writer
+                .write_all(&chunk)
+                .map_err(|e| format!("写入文件 '{}' 失败: {}", config.part_path.display(), e))?;
+            downloaded += chunk.len() as u64;
+            unflushed += chunk.len() as u64;
+
+            if flush_interval > 0 && unflushed >= flush_interval {
+                sync_part_writer(&mut writer)
+                    .map_err(|e| format!("写入文件 '{}' 失败: {}", config.part_path.display(), e))?;
+                unflushed = 0;
+            }
 
-        if total_size > 0 {
             update_progress_if_needed(
                 window,
                 downloaded,
                 total_size,
-                &config.download_type,
+                config,
                 &mut last_emit_time,
                 &mut last_emit_progress,
+                &mut speed,
             );
         }
+
+        if !paused {
+            break;
+        }
+
+        // 暂停：断开连接并将已下载的数据落盘
+        drop(stream);
+        writer
+            .flush()
+            .map_err(|e| format!("写入文件 '{}' 失败: {}", config.part_path.display(), e))?;
+        unflushed = 0;
+        notify_download_control_event(window, "download-paused", config, downloaded);
+
+        control.wait_until_paused(false).await;
+        if download_control::is_canceled(&config.download_type) {
+            return Err(download_control::canceled_error());
+        }
+        notify_download_control_event(window, "download-resumed", config, downloaded);
+
+        // 继续：从已写入的偏移发起 Range 请求；服务器不支持断点续传时从头下载
+        drop(writer);
+        (response, writer, downloaded, _) =
+            open_download(window, &client, config, downloaded, buffer_size).await?;
+        speed.reset(downloaded);
+    }
+
+    // 落盘后再重命名：避免断电后留下大小正确但内容损坏的"完整"文件
+    writer
+        .into_inner()
+        .map_err(|e| e.into_error())
+        .and_then(|file| file.sync_all())
+        .map_err(|e| format!("写入文件 '{}' 失败: {}", config.part_path.display(), e))?;
+
+    // 连接提前正常关闭时数据不完整，保留临时文件，下次从断点继续
+    if total_size > 0 && downloaded != total_size {
+        return Err(format!(
+            "下载不完整: 已下载 {downloaded} / {total_size} 字节，重试时将从断点继续"
+        ));
+    }
+
+    Ok(downloaded)
+}
+
+/// 发起下载请求，`offset` 大于 0 时从该偏移续传
+///
+/// 返回响应、临时文件写入器、临时文件中已有的字节数与文件总大小。服务器返回 206 且起点一致时
+/// 追加写入；返回 200（不支持 Range）时截断临时文件从头下载；其他响应（如 416，或 206 但
+/// `Content-Range` 缺失或起点不一致）无法确定内容对应的位置，重新发起不带 Range 的请求从头下载。
+async fn open_download<R: Runtime>(
+    window: &Window<R>,
+    client: &reqwest::Client,
+    config: &DownloadConfig,
+    offset: u64,
+    buffer_size: usize,
+) -> Result<(reqwest::Response, BufWriter<fs::File>, u64, Option<u64>), String> {
+    let mut response = fetch_with_retry(window, client, config, offset, None).await?;
+
+    if offset > 0 {
+        let content_range = response
+            .headers()
+            .get(reqwest::header::CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok());
+        match resume_action(response.status(), content_range, offset) {
+            ResumeAction::Append(total) => {
+                let total = total.or_else(|| response.content_length().map(|len| offset + len));
+                let writer = open_part_writer(&config.part_path, buffer_size, true)?;
+                return Ok((response, writer, offset, total));
+            }
+            ResumeAction::Restart => {
+                tracing::warn!(
+                    download_type = %config.download_type,
+                    url = %config.url,
+                    status = %response.status(),
+                    "服务器不支持断点续传，重新下载"
+                );
+            }
+            ResumeAction::Refetch => {
+                tracing::warn!(
+                    download_type = %config.download_type,
+                    url = %config.url,
+                    status = %response.status(),
+                    ?content_range,
+                    "续传响应与请求的范围不一致，重新请求完整文件"
+                );
+                response = fetch_with_retry(window, client, config, 0, None).await?;
+            }
+        }
+    }
+
+    validate_http_response(&response)?;
+    let total = response.content_length();
+    let writer = open_part_writer(&config.part_path, buffer_size, false)?;
+    Ok((response, writer, 0, total))
+}
+
+/// 续传请求的响应处理方式
+#[derive(Debug, PartialEq, Eq)]
+enum ResumeAction {
+    /// 206 且起点与已下载的字节数一致：追加写入，附文件总大小（`Content-Range` 中为 `*` 时为 `None`）
+    Append(Option<u64>),
+    /// 200：服务器忽略了 Range，响应体即完整文件，截断临时文件后写入
+    Restart,
+    /// 其他响应：内容与临时文件无法对应，需重新请求完整文件
+    Refetch,
+}
+
+/// 根据续传请求（从 `offset` 开始）的响应状态与 `Content-Range` 决定处理方式
+fn resume_action(status: reqwest::StatusCode, content_range: Option<&str>, offset: u64) -> ResumeAction {
+    if status == reqwest::StatusCode::OK {
+        return ResumeAction::Restart;
+    }
+    match content_range.and_then(parse_content_range) {
+        Some((start, total)) if status == reqwest::StatusCode::PARTIAL_CONTENT && start == offset => {
+            ResumeAction::Append(total)
+        }
+        _ => ResumeAction::Refetch,
+    }
+}
+
+/// 解析 `Content-Range: bytes <start>-<end>/<total>`，返回起点与总大小（总大小为 `*` 时为 `None`）
+fn parse_content_range(value: &str) -> Option<(u64, Option<u64>)> {
+    let (range, total) = value.trim().strip_prefix("bytes ")?.split_once('/')?;
+    let (start, _) = range.split_once('-')?;
+    Some((start.trim().parse().ok()?, total.trim().parse().ok()))
+}
+
+/// 打开临时文件（`append` 时追加，否则截断）并包装为带缓冲的写入器
+fn open_part_writer(
+    part_path: &Path,
+    buffer_size: usize,
+    append: bool,
+) -> Result<BufWriter<fs::File>, String> {
+    let part_file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(part_path)
+        .map_err(|e| format!("创建文件 '{}' 失败: {}", part_path.display(), e))?;
+    Ok(BufWriter::with_capacity(buffer_size, part_file))
+}
+
+/// 将缓冲区写入文件并同步到磁盘（仅 `flush` 时数据可能仍停留在系统页缓存中）
+fn sync_part_writer(writer: &mut BufWriter<fs::File>) -> std::io::Result<()> {
+    writer.flush()?;
+    writer.get_ref().sync_data()
+}
+
+// --- 多连接分段下载 ---
+
+/// 文件小于该大小时不分段（建立多个连接的开销大于并发带来的收益）
+const SEGMENTED_DOWNLOAD_MIN_SIZE: u64 = 8 * 1024 * 1024;
+
+/// 分段进度记录文件后缀（位于临时文件旁：`<destination>.part.segments`）
+const SEGMENTS_FILE_SUFFIX: &str = ".segments";
+
+/// 每个分段每写入该字节数落盘一次并更新进度记录（中断后最多重新下载这部分数据）
+const SEGMENT_CHECKPOINT_BYTES: u64 = 4 * 1024 * 1024;
+
+/// 探测服务器是否支持分段下载（`Accept-Ranges: bytes` 且返回了 `Content-Length`），支持时返回文件总大小
+async fn probe_range_support(client: &reqwest::Client, url: &str) -> Option<u64> {
+    let response = client.head(url).send().await.ok()?.error_for_status().ok()?;
+    let headers = response.headers();
+    let accepts_ranges = headers
+        .get(reqwest::header::ACCEPT_RANGES)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.trim().eq_ignore_ascii_case("bytes"));
+    // HEAD 响应没有响应体，`content_length()` 恒为 0，需直接读取响应头
+    let total = headers
+        .get(reqwest::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())?;
+
+    (accepts_ranges && total >= SEGMENTED_DOWNLOAD_MIN_SIZE).then_some(total)
+}
+
+/// 将 `[0, total)` 均分为最多 `count` 段，返回各段的起止字节（均包含）
+fn split_segments(total: u64, count: usize) -> Vec<(u64, u64)> {
+    let segment_size = total.div_ceil(count.max(1) as u64).max(1);
+    let mut segments = Vec::new();
+    let mut start = 0;
+    while start < total {
+        let end = (start + segment_size).min(total) - 1;
+        segments.push((start, end));
+        start = end + 1;
+    }
+    segments
+}
+
+/// 单个分段的起止字节（均包含）与已落盘的位置
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct SegmentState {
+    start: u64,
+    end: u64,
+    position: u64,
+}
+
+/// 分段下载的进度记录，下载中断后各分段据此从已落盘的位置续传
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct SegmentCheckpoint {
+    url: String,
+    total: u64,
+    segments: Vec<SegmentState>,
+}
+
+impl SegmentCheckpoint {
+    /// 从头开始的分段下载
+    fn new(url: &str, total: u64, connections: usize) -> Self {
+        let segments = split_segments(total, connections)
+            .into_iter()
+            .map(|(start, end)| SegmentState { start, end, position: start })
+            .collect();
+        Self { url: url.to_string(), total, segments }
+    }
+
+    /// 进度记录文件路径
+    fn path_for(part_path: &Path) -> PathBuf {
+        let mut path = part_path.as_os_str().to_os_string();
+        path.push(SEGMENTS_FILE_SUFFIX);
+        PathBuf::from(path)
+    }
+
+    /// 读取临时文件对应的进度记录（不存在或内容无效时为 `None`）
+    fn load(part_path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(Self::path_for(part_path)).ok()?;
+        let checkpoint: Self = serde_json::from_str(&content).ok()?;
+        checkpoint.is_consistent().then_some(checkpoint)
+    }
+
+    fn save(&self, part_path: &Path) -> std::io::Result<()> {
+        let content = serde_json::to_string(self).map_err(std::io::Error::other)?;
+        fs::write(Self::path_for(part_path), content)
+    }
+
+    fn remove(part_path: &Path) {
+        let path = Self::path_for(part_path);
+        if let Err(error) = fs::remove_file(&path) {
+            if error.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!(path = %path.display(), %error, "删除分段进度记录失败");
+            }
+        }
+    }
+
+    /// 各分段已落盘的字节数之和
+    fn downloaded(&self) -> u64 {
+        self.segments.iter().map(|segment| segment.position - segment.start).sum()
     }
 
-    Ok(buffer)
+    /// 各分段首尾相接覆盖整个文件，且位置都在分段范围内
+    fn is_consistent(&self) -> bool {
+        let mut expected_start = 0;
+        for segment in &self.segments {
+            if segment.start != expected_start
+                || segment.end < segment.start
+                || !(segment.start..=segment.end + 1).contains(&segment.position)
+            {
+                return false;
+            }
+            expected_start = segment.end + 1;
+        }
+        !self.segments.is_empty() && expected_start == self.total
+    }
 }
 
-/// 创建 HTTP 客户端
+/// 分段下载的共享进度（各分段写入后累加，沿用单连接下载的节流规则发送进度事件）
+struct SegmentedProgress {
+    downloaded: u64,
+    last_emit_time: Instant,
+    last_emit_progress: f64,
+    speed: SpeedMeter,
+    checkpoint: SegmentCheckpoint,
+}
+
+/// 多连接分段下载
+///
+/// 按总大小分配临时文件，各分段以 `Range: bytes=<start>-<end>` 并发请求并写入文件中对应的偏移。
+/// 暂停时各分段断开连接，继续时从各自已写入的位置重新请求。任一分段失败时整个下载失败；
+/// 各分段已落盘的位置记录在 `SegmentCheckpoint` 中，重试时从记录的位置续传。
+struct SegmentedDownload<'a, R: Runtime> {
+    window: &'a Window<R>,
+    client: &'a reqwest::Client,
+    config: &'a DownloadConfig,
+    control: &'a DownloadControl,
+    buffer_size: usize,
+    flush_interval: u64,
+}
+
+impl<R: Runtime> SegmentedDownload<'_, R> {
+    /// 按进度记录下载所有分段，返回文件总大小
+    async fn run(&self, checkpoint: SegmentCheckpoint) -> Result<u64, String> {
+        let part_path = &self.config.part_path;
+        let write_failed = |e: std::io::Error| format!("写入文件 '{}' 失败: {}", part_path.display(), e);
+        let total = checkpoint.total;
+        let downloaded = checkpoint.downloaded();
+        // 续传时保留已下载的数据
+        fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(downloaded == 0)
+            .open(part_path)
+            .and_then(|file| file.set_len(total))
+            .and_then(|()| checkpoint.save(part_path))
+            .map_err(write_failed)?;
+
+        tracing::info!(
+            download_type = %self.config.download_type,
+            total,
+            downloaded,
+            segments = checkpoint.segments.len(),
+            "使用多连接分段下载"
+        );
+        notify_download_started(self.window, self.config, Some(total));
+
+        let segment_count = checkpoint.segments.len();
+        let progress = Mutex::new(SegmentedProgress {
+            downloaded,
+            last_emit_time: Instant::now(),
+            last_emit_progress: -1.0,
+            speed: SpeedMeter::new(downloaded),
+            checkpoint,
+        });
+        let downloads =
+            try_join_all((0..segment_count).map(|index| self.download_segment(index, total, &progress)));
+        tokio::select! {
+            result = downloads => {
+                result?;
+            }
+            never = self.notify_pause_events(&progress) => match never {},
+        }
+        if download_control::is_canceled(&self.config.download_type) {
+            return Err(download_control::canceled_error());
+        }
+
+        // 落盘后再重命名：避免断电后留下大小正确但内容损坏的"完整"文件
+        fs::OpenOptions::new()
+            .write(true)
+            .open(part_path)
+            .and_then(|file| file.sync_all())
+            .map_err(write_failed)?;
+        SegmentCheckpoint::remove(part_path);
+        Ok(total)
+    }
+
+    /// 下载第 `index` 个分段，从记录的位置开始，暂停后从已写入的位置继续
+    async fn download_segment(
+        &self,
+        index: usize,
+        total: u64,
+        progress: &Mutex<SegmentedProgress>,
+    ) -> Result<(), String> {
+        let config = self.config;
+        let write_failed =
+            |e: std::io::Error| format!("写入文件 '{}' 失败: {}", config.part_path.display(), e);
+        let SegmentState { start, end, mut position } = progress
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .checkpoint
+            .segments[index]
+            .clone();
+        let file = fs::OpenOptions::new()
+            .write(true)
+            .open(&config.part_path)
+            .map_err(write_failed)?;
+        let mut writer = BufWriter::with_capacity(self.buffer_size, file);
+        let mut unsaved = 0;
+        let mut unflushed = 0;
+
+        while position <= end {
+            let response =
+                fetch_with_retry(self.window, self.client, config, position, Some(end)).await?;
+            let range_start = response
+                .headers()
+                .get(reqwest::header::CONTENT_RANGE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_content_range)
+                .map(|(range_start, _)| range_start);
+            if response.status() != reqwest::StatusCode::PARTIAL_CONTENT || range_start != Some(position) {
+                return Err(format!(
+                    "分段下载失败: 服务器未返回请求的数据范围 {position}-{end} (HTTP {})",
+                    response.status()
+                ));
+            }
+            writer.seek(SeekFrom::Start(position)).map_err(write_failed)?;
+
+            let mut stream = response.bytes_stream();
+            let mut paused = false;
+            while position <= end {
+                let chunk_result = tokio::select! {
+                    next = stream.next() => match next {
+                        Some(chunk_result) => chunk_result,
+                        None => break,
+                    },
+                    _ = self.control.wait_until_paused(true) => {
+                        paused = true;
+                        break;
+                    }
+                    _ = self.control.wait_until_canceled() => {
+                        return Err(download_control::canceled_error());
+                    }
+                };
+                if download_control::is_canceled(&config.download_type) {
+                    return Err(download_control::canceled_error());
+                }
+
+                let chunk = match chunk_result {
+                    Ok(chunk) => chunk,
+                    Err(error) => {
+                        self.save_checkpoint(&mut writer, progress, index, position)?;
+                        return Err(format!("下载流错误: {error}"));
+                    }
+                };
+                // 服务器多返回的数据不能写入相邻分段的位置
+                let remaining = usize::try_from(end + 1 - position).unwrap_or(usize::MAX);
+                let chunk = &chunk[..chunk.len().min(remaining)];
+                writer.write_all(chunk).map_err(write_failed)?;
+                position += chunk.len() as u64;
+                unsaved += chunk.len() as u64;
+                unflushed += chunk.len() as u64;
+                self.record_progress(progress, chunk.len() as u64, total);
+
+                if self.flush_interval > 0 && unflushed >= self.flush_interval {
+                    sync_part_writer(&mut writer).map_err(write_failed)?;
+                    unflushed = 0;
+                }
+
+                if unsaved >= SEGMENT_CHECKPOINT_BYTES {
+                    self.save_checkpoint(&mut writer, progress, index, position)?;
+                    unsaved = 0;
+                }
+            }
+
+            // 暂停或连接中断：将已下载的数据落盘并记录位置
+            self.save_checkpoint(&mut writer, progress, index, position)?;
+            unsaved = 0;
+            if paused {
+                // 断开连接，继续后从 position 重新请求
+                drop(stream);
+                self.control.wait_until_paused(false).await;
+                if download_control::is_canceled(&config.download_type) {
+                    return Err(download_control::canceled_error());
+                }
+            } else if position <= end {
+                return Err(format!(
+                    "分段下载不完整: 分段 {start}-{end} 在 {position} 处连接提前关闭"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 将分段已写入的数据落盘，再更新进度记录（记录的位置不会超过已落盘的数据）
+    fn save_checkpoint(
+        &self,
+        writer: &mut BufWriter<fs::File>,
+        progress: &Mutex<SegmentedProgress>,
+        index: usize,
+        position: u64,
+    ) -> Result<(), String> {
+        let part_path = &self.config.part_path;
+        writer
+            .flush()
+            .map_err(|e| format!("写入文件 '{}' 失败: {}", part_path.display(), e))?;
+        let mut state = progress.lock().unwrap_or_else(|p| p.into_inner());
+        state.checkpoint.segments[index].position = position;
+        if let Err(error) = state.checkpoint.save(part_path) {
+            tracing::warn!(path = %part_path.display(), %error, "保存分段进度记录失败");
+        }
+        Ok(())
+    }
+
+    /// 累加已下载字节数并按需发送进度
+    fn record_progress(&self, progress: &Mutex<SegmentedProgress>, bytes: u64, total: u64) {
+        let mut guard = progress.lock().unwrap_or_else(|p| p.into_inner());
+        let state = &mut *guard;
+        state.downloaded += bytes;
+        update_progress_if_needed(
+            self.window,
+            state.downloaded,
+            total,
+            self.config,
+            &mut state.last_emit_time,
+            &mut state.last_emit_progress,
+            &mut state.speed,
+        );
+    }
+
+    /// 各分段各自处理暂停，这里只负责发送一次暂停 / 继续事件
+    ///
+    /// 永不结束，只随分段下载结束而被取消：暂停中被取消时若提前返回，`select!` 会把未完成的下载当作成功。
+    async fn notify_pause_events(&self, progress: &Mutex<SegmentedProgress>) -> Infallible {
+        let downloaded = || progress.lock().unwrap_or_else(|p| p.into_inner()).downloaded;
+        loop {
+            self.control.wait_until_paused(true).await;
+            notify_download_control_event(self.window, "download-paused", self.config, downloaded());
+            self.control.wait_until_paused(false).await;
+            if download_control::is_canceled(&self.config.download_type) {
+                // 各分段会各自返回取消错误，这里等待被取消
+                std::future::pending::<()>().await;
+            }
+            let resumed_at = downloaded();
+            progress
+                .lock()
+                .unwrap_or_else(|p| p.into_inner())
+                .speed
+                .reset(resumed_at);
+            notify_download_control_event(self.window, "download-resumed", self.config, resumed_at);
+        }
+    }
+}
+
+/// 发送下载开始事件
+fn notify_download_started<R: Runtime>(window: &Window<R>, config: &DownloadConfig, total_bytes: Option<u64>) {
+    let url_host = reqwest::Url::parse(&config.url)
+        .ok()
+        .and_then(|url| url.host_str().map(ToString::to_string))
+        .unwrap_or_default();
+
+    let _ = window.emit(
+        "download-started",
+        DownloadStarted {
+            download_type: config.download_type.clone(),
+            total_bytes,
+            url_host,
+        },
+    );
+}
+
+/// 发送暂停 / 继续 / 取消事件
+fn notify_download_control_event<R: Runtime>(
+    window: &Window<R>,
+    event: &str,
+    config: &DownloadConfig,
+    downloaded: u64,
+) {
+    let _ = window.emit(
+        event,
+        DownloadControlEvent {
+            download_type: config.download_type.clone(),
+            downloaded,
+        },
+    );
+}
+
+/// 下载被取消：删除临时文件（取消后不再续传）并发送 `download-cancelled` 事件
+fn discard_canceled_download<R: Runtime>(window: &Window<R>, config: &DownloadConfig) {
+    let downloaded = fs::metadata(&config.part_path).map(|m| m.len()).unwrap_or(0);
+    if let Err(error) = fs::remove_file(&config.part_path) {
+        tracing::warn!(path = %config.part_path.display(), %error, "删除下载临时文件失败");
+    }
+    SegmentCheckpoint::remove(&config.part_path);
+    tracing::info!(download_type = %config.download_type, downloaded, "下载已取消");
+    notify_download_control_event(window, "download-cancelled", config, downloaded);
+}
+
+/// 获取共享的 HTTP 客户端
 fn create_http_client() -> Result<reqwest::Client, String> {
-    reqwest::Client::builder()
-        .user_agent(USER_AGENT)
-        .build()
-        .map_err(|e| format!("创建 HTTP 客户端失败: {e}"))
+    http::client().map_err(|e| format!("创建 HTTP 客户端失败: {e}"))
 }
 
-/// 获取 HTTP 响应
+/// 获取 HTTP 响应（`offset` 大于 0 时请求从该偏移开始的数据，指定 `end` 时只请求到该字节为止）
 async fn fetch_http_response(
     client: &reqwest::Client,
     url: &str,
+    offset: u64,
+    end: Option<u64>,
 ) -> Result<reqwest::Response, String> {
-    client
-        .get(url)
+    let mut request = client.get(url);
+    match end {
+        Some(end) => request = request.header(reqwest::header::RANGE, format!("bytes={offset}-{end}")),
+        None if offset > 0 => {
+            request = request.header(reqwest::header::RANGE, format!("bytes={offset}-"))
+        }
+        None => {}
+    }
+
+    request
         .send()
         .await
         .map_err(|e| format!("HTTP 请求失败 '{url}': {e}"))
 }
 
+/// 发起下载请求，网络错误与可重试的 HTTP 状态（408 / 429 / 5xx）按指数退避重试
+///
+/// 其他状态（如 404）立即返回，由调用方校验；重试次数用尽时返回最后一次的结果。
+/// 每次重试前发送 `download-retry` 事件。
+async fn fetch_with_retry<R: Runtime>(
+    window: &Window<R>,
+    client: &reqwest::Client,
+    config: &DownloadConfig,
+    offset: u64,
+    end: Option<u64>,
+) -> Result<reqwest::Response, String> {
+    let mut attempt = 0;
+    loop {
+        let result = fetch_http_response(client, &config.url, offset, end).await;
+        let reason = match &result {
+            Ok(response) if is_retryable_status(response.status()) => {
+                format!("HTTP {}", response.status())
+            }
+            Ok(_) => return result,
+            Err(error) => error.clone(),
+        };
+        if attempt >= DOWNLOAD_MAX_RETRIES {
+            return result;
+        }
+
+        attempt += 1;
+        let delay = DOWNLOAD_RETRY_BASE_DELAY * 2u32.pow(attempt - 1);
+        tracing::warn!(
+            download_type = %config.download_type,
+            attempt,
+            %reason,
+            ?delay,
+            "下载请求失败，稍后重试"
+        );
+        let _ = window.emit(
+            "download-retry",
+            DownloadRetry {
+                download_type: config.download_type.clone(),
+                attempt,
+                max_attempts: DOWNLOAD_MAX_RETRIES,
+                reason,
+            },
+        );
+
+        tokio::time::sleep(delay).await;
+        if download_control::is_canceled(&config.download_type) {
+            return Err(download_control::canceled_error());
+        }
+    }
+}
+
+/// 是否为可重试的 HTTP 状态（请求超时、限流与服务端错误）
+fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::REQUEST_TIMEOUT
+        || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        || status.is_server_error()
+}
+
 /// 验证 HTTP 响应状态
 fn validate_http_response(response: &reqwest::Response) -> Result<(), String> {
     if !response.status().is_success() {
@@ -151,29 +1131,49 @@ fn validate_http_response(response: &reqwest::Response) -> Result<(), String> {
 }
 
 /// 根据需要更新进度显示
+///
+/// `total` 为 0（服务器未返回 `Content-Length`）时按时间间隔发送不确定进度，整体进度保持不变。
+/// 速度只在发送时采样，发送频率受节流限制，采样间隔不会过短。
 fn update_progress_if_needed<R: Runtime>(
     window: &Window<R>,
     downloaded: u64,
     total: u64,
-    download_type: &str,
+    config: &DownloadConfig,
     last_emit_time: &mut Instant,
     last_emit_progress: &mut f64,
+    speed: &mut SpeedMeter,
 ) {
-    let downloaded_u32 = u32::try_from(downloaded).unwrap_or(u32::MAX);
-    let total_u32 = u32::try_from(total).unwrap_or(u32::MAX);
-    let progress = (f64::from(downloaded_u32) / f64::from(total_u32)) * 100.0;
     let time_elapsed =
         last_emit_time.elapsed() >= Duration::from_millis(PROGRESS_UPDATE_MIN_INTERVAL_MS);
-    let progress_increased = progress - *last_emit_progress >= PROGRESS_UPDATE_MIN_INCREMENT;
+
+    let progress = if total > 0 {
+        let downloaded_u32 = u32::try_from(downloaded).unwrap_or(u32::MAX);
+        let total_u32 = u32::try_from(total).unwrap_or(u32::MAX);
+        (f64::from(downloaded_u32) / f64::from(total_u32)) * 100.0
+    } else {
+        INDETERMINATE_PROGRESS
+    };
+    let progress_increased =
+        total > 0 && progress - *last_emit_progress >= PROGRESS_UPDATE_MIN_INCREMENT;
 
     if time_elapsed || progress_increased {
+        let speed_bps = speed.sample(downloaded, Instant::now());
         let _ = window.emit(
             "download-progress",
             Progress {
                 progress,
-                download_type: download_type.to_string(),
+                download_type: config.download_type.clone(),
+                bytes_downloaded: Some(downloaded),
+                total_bytes: (total > 0).then_some(total),
+                speed_bps: speed_bps.map(|bps| bps.round() as u64),
+                eta_secs: speed_bps.and_then(|bps| estimate_eta_secs(downloaded, total, bps)),
             },
         );
+        if total > 0 {
+            config
+                .overall
+                .emit(window, &config.download_type, config.overall.download(progress));
+        }
 
         *last_emit_progress = progress;
         *last_emit_time = Instant::now();
@@ -185,12 +1185,21 @@ async fn process_downloaded_content<R: Runtime>(
     window: &Window<R>,
     config: &DownloadConfig,
 ) -> Result<(), String> {
-    let buffer = download_with_progress(window, config).await?;
+    notify_setup_stage(window, "downloading", &config.download_type);
+    if let Err(error) = download_with_progress(window, config).await {
+        if download_control::is_canceled(&config.download_type) {
+            discard_canceled_download(window, config);
+        }
+        return Err(error);
+    }
 
     if config.is_archive && !config.destination_is_file {
-        handle_archive_download(window, config, &buffer)
+        let result = handle_archive_download(window, config);
+        // 解压完成（或失败）后临时存档不再需要
+        let _ = fs::remove_file(&config.part_path);
+        result
     } else {
-        handle_file_download(config, &buffer)
+        handle_file_download(config)
     }
 }
 
@@ -198,22 +1207,35 @@ async fn process_downloaded_content<R: Runtime>(
 fn handle_archive_download<R: Runtime>(
     window: &Window<R>,
     config: &DownloadConfig,
-    buffer: &[u8],
 ) -> Result<(), String> {
     prepare_destination_directory(&config.destination)?;
     notify_extraction_start(window, &config.download_type);
+    notify_setup_stage(window, "extracting", &config.download_type);
+    config
+        .overall
+        .emit(window, &config.download_type, config.overall.extraction(0.0));
 
-    extract_archive(buffer, &config.destination)?;
+    // 解压、展平或修复权限中途失败或被取消时，守卫会清理半成品目录
+    let guard = archive::ExtractionDirGuard::new(&config.destination);
+    let is_canceled = || download_control::is_canceled(&config.download_type);
+    let on_progress = |extracted: usize, total: usize| {
+        let percent = emit_extraction_progress(window, &config.download_type, extracted, total);
+        config
+            .overall
+            .emit(window, &config.download_type, config.overall.extraction(percent));
+    };
+    extract_archive(&config.part_path, &config.destination, &is_canceled, &on_progress)?;
     flatten_single_directory(&config.destination)?;
     fix_permissions_if_needed(&config.destination)?;
+    guard.commit();
 
     Ok(())
 }
 
-/// 处理普通文件下载
-fn handle_file_download(config: &DownloadConfig, buffer: &[u8]) -> Result<(), String> {
+/// 处理普通文件下载：将临时文件移动到最终位置
+fn handle_file_download(config: &DownloadConfig) -> Result<(), String> {
     ensure_parent_directory_exists(&config.destination)?;
-    write_file_content(&config.destination, buffer)
+    move_part_file(&config.part_path, &config.destination)
 }
 
 /// 准备目标目录
@@ -236,6 +1258,42 @@ fn notify_extraction_start<R: Runtime>(window: &Window<R>, download_type: &str)
     );
 }
 
+/// 每解压多少个条目发送一次进度
+pub(crate) fn extraction_progress_step(total: usize) -> usize {
+    (total / EXTRACTION_PROGRESS_STEPS).max(1)
+}
+
+/// 发送解压进度事件，返回百分比
+pub(crate) fn emit_extraction_progress<R: Runtime>(
+    window: &Window<R>,
+    download_type: &str,
+    extracted: usize,
+    total: usize,
+) -> f64 {
+    let percent = extracted as f64 * 100.0 / total.max(1) as f64;
+    let _ = window.emit(
+        "extraction-progress",
+        ExtractionProgressEvent {
+            download_type: download_type.to_string(),
+            extracted_entries: extracted,
+            total_entries: total,
+            percent,
+        },
+    );
+    percent
+}
+
+/// 通知安装阶段变化
+pub(crate) fn notify_setup_stage<R: Runtime>(window: &Window<R>, stage: &str, download_type: &str) {
+    let _ = window.emit(
+        "setup-stage",
+        SetupStage {
+            stage: stage.to_string(),
+            download_type: download_type.to_string(),
+        },
+    );
+}
+
 /// 确保父目录存在
 fn ensure_parent_directory_exists(file_path: &Path) -> Result<(), String> {
     if let Some(parent) = file_path.parent() {
@@ -245,76 +1303,215 @@ fn ensure_parent_directory_exists(file_path: &Path) -> Result<(), String> {
     Ok(())
 }
 
-/// 写入文件内容
-fn write_file_content(file_path: &Path, content: &[u8]) -> Result<(), String> {
-    fs::write(file_path, content)
-        .map_err(|e| format!("写入文件 '{}' 失败: {}", file_path.display(), e))
+/// 将下载完成的临时文件移动到最终位置
+fn move_part_file(part_path: &Path, file_path: &Path) -> Result<(), String> {
+    if file_path.exists() {
+        fs::remove_file(file_path)
+            .map_err(|e| format!("删除文件 '{}' 失败: {}", file_path.display(), e))?;
+    }
+
+    fs::rename(part_path, file_path).map_err(|e| {
+        format!(
+            "移动文件 '{}' 到 '{}' 失败: {}",
+            part_path.display(),
+            file_path.display(),
+            e
+        )
+    })?;
+
+    // 确保重命名本身（目录项）也已持久化
+    if let Some(parent) = file_path.parent() {
+        let _ = sync_dir(parent);
+    }
+    Ok(())
 }
 
-/// 解压存档文件
-fn extract_archive(buffer: &[u8], dest: &Path) -> Result<(), String> {
-    if is_tar_gz_archive(buffer) {
-        extract_tar_gz(buffer, dest)
+/// 打开存档文件
+fn open_archive(archive_path: &Path) -> Result<fs::File, String> {
+    fs::File::open(archive_path)
+        .map_err(|e| format!("打开文件 '{}' 失败: {}", archive_path.display(), e))
+}
+
+/// 解压进度回调（已解压条目数，条目总数）
+type ExtractionProgress<'a> = &'a dyn Fn(usize, usize);
+
+/// 解压存档文件（逐条目检查 `is_canceled`，被取消时返回 `CANCELED` 错误）
+///
+/// 每完成约 0.5% 的条目回调一次 `on_progress`，最后一个条目完成时必定回调。
+fn extract_archive(
+    archive_path: &Path,
+    dest: &Path,
+    is_canceled: &dyn Fn() -> bool,
+    on_progress: ExtractionProgress,
+) -> Result<(), String> {
+    archive::ensure_memory_headroom(archive::MIN_AVAILABLE_MEMORY)?;
+
+    if is_tar_gz_archive(archive_path)? {
+        extract_tar_gz(archive_path, dest, is_canceled, on_progress)
     } else {
-        extract_zip(buffer, dest)
+        extract_zip(archive_path, dest, is_canceled, on_progress)
+    }
+}
+
+/// 报告解压进度（按粒度节流）
+fn report_extraction_progress(on_progress: ExtractionProgress, done: usize, total: usize) {
+    if done.is_multiple_of(extraction_progress_step(total)) || done == total {
+        on_progress(done, total);
     }
 }
 
-/// 检查是否为 tar.gz 格式
-fn is_tar_gz_archive(buffer: &[u8]) -> bool {
-    buffer.starts_with(&[0x1f, 0x8b]) // GZIP 魔数
+/// 检查是否为 tar.gz 格式（读取文件头的 GZIP 魔数）
+fn is_tar_gz_archive(archive_path: &Path) -> Result<bool, String> {
+    let mut magic = [0u8; 2];
+    let bytes_read = open_archive(archive_path)?
+        .read(&mut magic)
+        .map_err(|e| format!("读取文件 '{}' 失败: {}", archive_path.display(), e))?;
+
+    Ok(bytes_read == magic.len() && magic == GZIP_MAGIC)
 }
 
 /// 解压 ZIP 文件
-fn extract_zip(buffer: &[u8], dest: &Path) -> Result<(), String> {
-    let mut archive =
-        zip::ZipArchive::new(Cursor::new(buffer)).map_err(|e| format!("ZIP 格式非法: {e}"))?;
+fn extract_zip(
+    archive_path: &Path,
+    dest: &Path,
+    is_canceled: &dyn Fn() -> bool,
+    on_progress: ExtractionProgress,
+) -> Result<(), String> {
+    let mut archive = zip::ZipArchive::new(BufReader::new(open_archive(archive_path)?))
+        .map_err(|e| format!("ZIP 格式非法: {e}"))?;
+    archive::ensure_zip_within_limit(&mut archive, archive::MAX_ARCHIVE_UNCOMPRESSED_SIZE)?;
+
+    let extract_failed = |e: &dyn std::fmt::Display| format!("ZIP 解压失败: {e}");
+    let io_failed = |path: &Path, e: std::io::Error| {
+        archive::path_too_long_message(path, &e).unwrap_or_else(|| extract_failed(&e))
+    };
+    // 使用扩展长度路径，避免深层目录超出 Windows MAX_PATH
+    let dest_root = archive::extended_length_path(dest);
+    let total = archive.len();
+    for i in 0..total {
+        if is_canceled() {
+            return Err(download_control::canceled_error());
+        }
+
+        let mut entry = archive.by_index(i).map_err(|e| extract_failed(&e))?;
+        let outpath = archive::zip_entry_outpath(&dest_root, &entry)?;
 
-    archive
-        .extract(dest)
-        .map_err(|e| format!("ZIP 解压失败: {e}"))
+        if entry.is_dir() {
+            fs::create_dir_all(&outpath).map_err(|e| io_failed(&outpath, e))?;
+            report_extraction_progress(on_progress, i + 1, total);
+            continue;
+        }
+
+        if let Some(parent) = outpath.parent() {
+            fs::create_dir_all(parent).map_err(|e| io_failed(parent, e))?;
+        }
+        let mut outfile = fs::File::create(&outpath).map_err(|e| io_failed(&outpath, e))?;
+        archive::copy_bounded(&mut entry, &mut outfile).map_err(|e| extract_failed(&e))?;
+
+        #[cfg(unix)]
+        if let Some(mode) = entry.unix_mode() {
+            fs::set_permissions(&outpath, fs::Permissions::from_mode(mode))
+                .map_err(|e| extract_failed(&e))?;
+        }
+        report_extraction_progress(on_progress, i + 1, total);
+    }
+
+    Ok(())
+}
+
+/// 解压中的 TAR.GZ 存档
+type TarGzArchive = tar::Archive<archive::SizeCappedReader<GzDecoder<BufReader<fs::File>>>>;
+
+/// 打开 TAR.GZ 文件（解压后的总大小受 `MAX_ARCHIVE_UNCOMPRESSED_SIZE` 限制）
+fn open_tar_gz(archive_path: &Path) -> Result<TarGzArchive, String> {
+    let tar_gz = GzDecoder::new(BufReader::new(open_archive(archive_path)?));
+    Ok(tar::Archive::new(archive::SizeCappedReader::new(
+        tar_gz,
+        archive::MAX_ARCHIVE_UNCOMPRESSED_SIZE,
+    )))
 }
 
 /// 解压 TAR.GZ 文件
-fn extract_tar_gz(buffer: &[u8], dest: &Path) -> Result<(), String> {
-    use flate2::read::GzDecoder;
-    use tar::Archive;
+///
+/// TAR 没有中央目录，先完整读一遍统计条目数作为进度分母，再逐条解压。
+fn extract_tar_gz(
+    archive_path: &Path,
+    dest: &Path,
+    is_canceled: &dyn Fn() -> bool,
+    on_progress: ExtractionProgress,
+) -> Result<(), String> {
+    let extract_failed = |e: std::io::Error| format!("TAR.GZ 解压失败: {e}");
 
-    let tar_gz = GzDecoder::new(Cursor::new(buffer));
-    let mut archive = Archive::new(tar_gz);
+    let mut total = 0;
+    for entry in open_tar_gz(archive_path)?.entries().map_err(extract_failed)? {
+        if is_canceled() {
+            return Err(download_control::canceled_error());
+        }
+        entry.map_err(extract_failed)?;
+        total += 1;
+    }
+
+    let mut archive = open_tar_gz(archive_path)?;
+    for (index, entry) in archive.entries().map_err(extract_failed)?.enumerate() {
+        if is_canceled() {
+            return Err(download_control::canceled_error());
+        }
+
+        // unpack_in 会拒绝逃逸出目标目录的路径
+        entry.map_err(extract_failed)?
+            .unpack_in(dest)
+            .map_err(extract_failed)?;
+        report_extraction_progress(on_progress, index + 1, total);
+    }
+
+    Ok(())
+}
+
+/// 展平时临时目录名前缀（避免子目录中存在与其同名的条目时发生冲突）
+const FLATTEN_STAGING_PREFIX: &str = ".flatten-";
 
-    archive
-        .unpack(dest)
-        .map_err(|e| format!("TAR.GZ 解压失败: {e}"))
+/// 判断路径是否为隐藏条目（以 `.` 开头，如 `.DS_Store`）
+fn is_hidden_entry(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with('.'))
 }
 
 /// 展平单层目录结构
+///
+/// 仅当解压结果中唯一的非隐藏条目是一个目录时（如 `node-v22.../`）才展平，
+/// 根目录已有文件或存在多个目录时保持原样。
 fn flatten_single_directory(dest: &Path) -> Result<(), String> {
-    let entries: Vec<_> = fs::read_dir(dest)
+    let visible_entries: Vec<PathBuf> = fs::read_dir(dest)
         .map_err(|e| format!("读取目录 '{}' 失败: {}", dest.display(), e))?
         .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| !is_hidden_entry(path))
         .collect();
 
-    let directories: Vec<_> = entries
-        .iter()
-        .filter(|entry| {
-            let path = entry.path();
-            let is_dir = path.is_dir();
-            let is_hidden = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .is_some_and(|n| n.starts_with('.'));
-            is_dir && !is_hidden
-        })
-        .collect();
+    let sub_dir = match visible_entries.as_slice() {
+        [only] if only.is_dir() => only,
+        _ => return Ok(()),
+    };
 
-    // 如果只有一个非隐藏目录，展平它
-    if directories.len() == 1 {
-        let sub_dir = &directories[0].path();
-        flatten_directory_contents(sub_dir, dest)?;
-        fs::remove_dir(sub_dir)
-            .map_err(|e| format!("删除目录 '{}' 失败: {}", sub_dir.display(), e))?;
-    }
+    // 先将子目录改名为临时目录，防止其内部存在同名条目时移动失败
+    let dir_name = sub_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let staging_dir = dest.join(format!("{FLATTEN_STAGING_PREFIX}{dir_name}"));
+    fs::rename(sub_dir, &staging_dir).map_err(|e| {
+        format!(
+            "移动目录 '{}' 到 '{}' 失败: {}",
+            sub_dir.display(),
+            staging_dir.display(),
+            e
+        )
+    })?;
+
+    flatten_directory_contents(&staging_dir, dest)?;
+    fs::remove_dir(&staging_dir)
+        .map_err(|e| format!("删除目录 '{}' 失败: {}", staging_dir.display(), e))?;
 
     Ok(())
 }
@@ -330,6 +1527,11 @@ fn flatten_directory_contents(source_dir: &Path, target_dir: &Path) -> Result<()
         let from = entry.path();
         let to = target_dir.join(entry.file_name());
 
+        // 目标已存在（如与根目录的隐藏条目同名）时不覆盖，直接报错
+        if to.exists() {
+            return Err(format!("展平目录失败: '{}' 已存在", to.display()));
+        }
+
         fs::rename(&from, &to).map_err(|e| {
             format!(
                 "移动文件 '{}' 到 '{}' 失败: {}",
@@ -357,21 +1559,67 @@ fn fix_permissions_if_needed(dest: &Path) -> Result<(), String> {
 }
 
 /// 递归修复权限（仅 Unix）
+///
+/// 跳过符号链接（Linux 版 Node.js 的 `bin/npm` 等指向 `lib/node_modules`），
+/// 其目标文件会在遍历到时单独处理，悬空链接也不会导致安装失败。
 #[cfg(unix)]
 fn fix_recursive_permissions(path: &Path) -> std::io::Result<()> {
-    if path.is_dir() {
+    let file_type = fs::symlink_metadata(path)?.file_type();
+    if file_type.is_symlink() {
+        return Ok(());
+    }
+    if file_type.is_dir() {
         for entry in fs::read_dir(path)? {
             let entry = entry?;
             fix_recursive_permissions(&entry.path())?;
         }
     } else {
+        make_executable(path)?;
+    }
+    Ok(())
+}
+
+/// 将文件内容刷写到磁盘
+pub fn sync_file(path: &Path) -> std::io::Result<()> {
+    fs::File::open(path)?.sync_all()
+}
+
+/// 将目录项（新建、重命名）刷写到磁盘（仅 Unix 系统，Windows 不支持打开目录句柄）
+pub fn sync_dir(path: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        fs::File::open(path)?.sync_all()
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        Ok(())
+    }
+}
+
+/// 为单个文件设置可执行权限（仅 Unix 系统，其他平台不做处理）
+pub fn make_executable(path: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
         let mut permissions = fs::metadata(path)?.permissions();
         permissions.set_mode(EXECUTABLE_PERMISSIONS_MODE);
         fs::set_permissions(path, permissions)?;
     }
+    #[cfg(not(unix))]
+    let _ = path;
+
     Ok(())
 }
 
+/// 递归移除隔离属性（仅 macOS，其他平台不做处理）
+pub fn clear_quarantine(path: &Path) {
+    #[cfg(target_os = "macos")]
+    remove_macos_quarantine_attribute(path);
+    #[cfg(not(target_os = "macos"))]
+    let _ = path;
+}
+
 /// 移除 macOS 隔离属性
 #[cfg(target_os = "macos")]
 fn remove_macos_quarantine_attribute(path: &Path) {
@@ -390,6 +1638,190 @@ fn finalize_download<R: Runtime>(window: &Window<R>, config: &DownloadConfig) {
         Progress {
             progress: 100.0,
             download_type: config.download_type.clone(),
+            bytes_downloaded: None,
+            total_bytes: None,
+            speed_bps: None,
+            eta_secs: Some(0),
         },
     );
+    config
+        .overall
+        .emit(window, &config.download_type, config.overall.scale(100.0));
+    if config.overall.is_full_range() {
+        notify_setup_stage(window, "done", &config.download_type);
+    }
+}
+
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+
+    /// 创建独立的测试目录
+    fn fixture_dir(name: &str) -> PathBuf {
+        let dir = temp_dir().join(format!("n8n-desktop-flatten-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("Failed to create fixture dir");
+        dir
+    }
+
+    fn touch(path: &Path) {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent dir");
+        }
+        fs::write(path, b"test").expect("Failed to write fixture file");
+    }
+
+    #[test]
+    fn test_split_segments_covers_whole_file() {
+        assert_eq!(split_segments(10, 3), vec![(0, 3), (4, 7), (8, 9)]);
+        assert_eq!(split_segments(2, 4), vec![(0, 0), (1, 1)]);
+        assert_eq!(split_segments(100, 1), vec![(0, 99)]);
+    }
+
+    #[test]
+    fn test_resume_action_requires_matching_range() {
+        use reqwest::StatusCode;
+
+        assert_eq!(
+            resume_action(StatusCode::PARTIAL_CONTENT, Some("bytes 100-199/200"), 100),
+            ResumeAction::Append(Some(200))
+        );
+        assert_eq!(
+            resume_action(StatusCode::PARTIAL_CONTENT, Some("bytes 100-199/*"), 100),
+            ResumeAction::Append(None)
+        );
+        assert_eq!(resume_action(StatusCode::OK, None, 100), ResumeAction::Restart);
+        // 206 但缺少 Content-Range 或起点不一致时，响应体不能当作完整文件或续传数据
+        assert_eq!(resume_action(StatusCode::PARTIAL_CONTENT, None, 100), ResumeAction::Refetch);
+        assert_eq!(
+            resume_action(StatusCode::PARTIAL_CONTENT, Some("bytes 0-99/200"), 100),
+            ResumeAction::Refetch
+        );
+        assert_eq!(
+            resume_action(StatusCode::RANGE_NOT_SATISFIABLE, Some("bytes */200"), 100),
+            ResumeAction::Refetch
+        );
+    }
+
+    #[test]
+    fn test_segment_checkpoint_round_trip() {
+        let dir = fixture_dir("segments");
+        let part_path = dir.join("n8n-core.zip.part");
+        assert_eq!(SegmentCheckpoint::load(&part_path), None);
+
+        let mut checkpoint = SegmentCheckpoint::new("https://example.com/core.zip", 10, 3);
+        checkpoint.segments[0].position = 2;
+        checkpoint.segments[2].position = 10;
+        checkpoint.save(&part_path).expect("save failed");
+        assert_eq!(SegmentCheckpoint::load(&part_path).as_ref(), Some(&checkpoint));
+        assert_eq!(checkpoint.downloaded(), 4);
+
+        // 位置越过分段终点的记录视为无效
+        checkpoint.segments[1].position = 9;
+        checkpoint.save(&part_path).expect("save failed");
+        assert_eq!(SegmentCheckpoint::load(&part_path), None);
+
+        SegmentCheckpoint::remove(&part_path);
+        assert!(!SegmentCheckpoint::path_for(&part_path).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_flatten_single_top_level_directory() {
+        let dest = fixture_dir("single");
+        touch(&dest.join("node-v22.22.0-darwin-arm64/bin/node"));
+        touch(&dest.join("node-v22.22.0-darwin-arm64/README.md"));
+
+        flatten_single_directory(&dest).expect("flatten failed");
+
+        assert!(dest.join("bin/node").exists());
+        assert!(dest.join("README.md").exists());
+        assert!(!dest.join("node-v22.22.0-darwin-arm64").exists());
+        let _ = fs::remove_dir_all(&dest);
+    }
+
+    #[test]
+    fn test_flatten_noop_when_files_at_root() {
+        let dest = fixture_dir("root-files");
+        touch(&dest.join("README.md"));
+        touch(&dest.join("lib/index.js"));
+
+        flatten_single_directory(&dest).expect("flatten failed");
+
+        assert!(dest.join("README.md").exists());
+        assert!(dest.join("lib/index.js").exists());
+        assert!(!dest.join("index.js").exists());
+        let _ = fs::remove_dir_all(&dest);
+    }
+
+    #[test]
+    fn test_flatten_noop_with_multiple_directories() {
+        let dest = fixture_dir("multi");
+        touch(&dest.join("bin/node"));
+        touch(&dest.join("lib/index.js"));
+
+        flatten_single_directory(&dest).expect("flatten failed");
+
+        assert!(dest.join("bin/node").exists());
+        assert!(dest.join("lib/index.js").exists());
+        let _ = fs::remove_dir_all(&dest);
+    }
+
+    #[test]
+    fn test_flatten_ignores_hidden_directory() {
+        let dest = fixture_dir("hidden");
+        touch(&dest.join(".hidden/marker"));
+        touch(&dest.join("package/bin/n8n"));
+
+        flatten_single_directory(&dest).expect("flatten failed");
+
+        assert!(dest.join("bin/n8n").exists());
+        assert!(dest.join(".hidden/marker").exists());
+        assert!(!dest.join("package").exists());
+        let _ = fs::remove_dir_all(&dest);
+    }
+
+    #[test]
+    fn test_flatten_handles_entry_named_like_parent() {
+        let dest = fixture_dir("collision");
+        touch(&dest.join("package/package/index.js"));
+        touch(&dest.join("package/other.js"));
+
+        flatten_single_directory(&dest).expect("flatten failed");
+
+        assert!(dest.join("package/index.js").exists());
+        assert!(dest.join("other.js").exists());
+        let _ = fs::remove_dir_all(&dest);
+    }
+
+    #[test]
+    fn test_parse_content_range() {
+        assert_eq!(parse_content_range("bytes 100-999/1000"), Some((100, Some(1000))));
+        assert_eq!(parse_content_range("bytes 100-999/*"), Some((100, None)));
+        assert_eq!(parse_content_range("items 0-1/2"), None);
+    }
+
+    #[test]
+    fn test_is_retryable_status() {
+        assert!(is_retryable_status(reqwest::StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable_status(reqwest::StatusCode::BAD_GATEWAY));
+        assert!(!is_retryable_status(reqwest::StatusCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn test_speed_meter_smooths_and_estimates_eta() {
+        let mut speed = SpeedMeter::new(0);
+        let start = speed.last_time;
+
+        let first = speed.sample(1000, start + Duration::from_secs(1)).expect("应有速度");
+        assert!((first - 1000.0).abs() < f64::EPSILON);
+        // 瞬时速度 2000 B/s，平滑后为 1000 + 0.3 * 1000
+        let second = speed.sample(3000, start + Duration::from_secs(2)).expect("应有速度");
+        assert!((second - 1300.0).abs() < 1e-9);
+
+        assert_eq!(estimate_eta_secs(3000, 16_000, second), Some(10));
+        assert_eq!(estimate_eta_secs(3000, 0, second), None);
+    }
 }
@@ -0,0 +1,126 @@
+use crate::api::settings::settings_lock;
+use crate::i18n;
+use reqwest::redirect::{Attempt, Policy};
+use std::env;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+/// 建立连接的超时时间（整体超时由各请求按需设置，避免限制大文件下载）
+const HTTP_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 单个请求允许跟随的最大重定向次数
+const MAX_REDIRECTS: usize = 5;
+
+/// 共享的 HTTP 客户端（复用连接池与 TLS 会话）
+static SHARED_CLIENT: LazyLock<Mutex<Option<reqwest::Client>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// 默认的 User-Agent：`n8n-desktop/<version> (<os>; <arch>)`
+pub fn default_user_agent() -> String {
+    format!(
+        "n8n-desktop/{} ({}; {})",
+        env!("CARGO_PKG_VERSION"),
+        env::consts::OS,
+        env::consts::ARCH
+    )
+}
+
+/// 获取当前生效的 User-Agent（设置中的自定义值优先）
+pub fn user_agent() -> String {
+    settings_lock()
+        .user_agent
+        .as_deref()
+        .map(str::trim)
+        .filter(|ua| !ua.is_empty())
+        .map(ToString::to_string)
+        .unwrap_or_else(default_user_agent)
+}
+
+/// 获取共享的 HTTP 客户端，首次调用时按当前设置（User-Agent、重定向允许列表、代理）创建
+///
+/// `reqwest::Client` 内部使用 `Arc`，克隆开销很小且共享同一个连接池。
+pub fn client() -> reqwest::Result<reqwest::Client> {
+    let mut guard = SHARED_CLIENT
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    if let Some(client) = guard.as_ref() {
+        return Ok(client.clone());
+    }
+
+    let (redirect_allowed_hosts, proxy) = {
+        let settings = settings_lock();
+        (settings.redirect_allowed_hosts.clone(), settings.effective_http_proxy())
+    };
+    let mut builder = reqwest::Client::builder()
+        .user_agent(user_agent())
+        .connect_timeout(HTTP_CONNECT_TIMEOUT)
+        .redirect(redirect_policy(redirect_allowed_hosts));
+    // 未配置代理时 reqwest 自动读取 HTTP_PROXY / HTTPS_PROXY 等环境变量；显式代理优先于环境变量
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+    }
+    let client = builder.build()?;
+    *guard = Some(client.clone());
+    Ok(client)
+}
+
+/// 丢弃共享客户端，下次使用时按最新设置（如 User-Agent）重新创建
+pub fn invalidate_client() {
+    *SHARED_CLIENT
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+}
+
+/// 重定向策略：限制次数、校验目标主机，并记录每次跳转
+///
+/// 下载经过第三方代理和镜像，跳转到允许列表之外的主机时直接失败，而不是静默跟随。
+fn redirect_policy(allowed_hosts: Vec<String>) -> Policy {
+    Policy::custom(move |attempt: Attempt| {
+        let from = attempt.previous().last().map(ToString::to_string).unwrap_or_default();
+        tracing::debug!(%from, to = %attempt.url(), "HTTP 重定向");
+
+        if attempt.previous().len() > MAX_REDIRECTS {
+            let error = format!("{} ({MAX_REDIRECTS})", i18n::t("http.too_many_redirects"));
+            return attempt.error(error);
+        }
+
+        let host = attempt.url().host_str().unwrap_or_default().to_string();
+        if !is_host_allowed(&host, &allowed_hosts) {
+            tracing::warn!(%host, "拒绝重定向到未允许的主机");
+            return attempt.error(format!("{}: {host}", i18n::t("http.redirect_host_not_allowed")));
+        }
+
+        attempt.follow()
+    })
+}
+
+/// 主机是否在允许列表中（列表为空时不限制；条目同时匹配其子域名）
+fn is_host_allowed(host: &str, allowed_hosts: &[String]) -> bool {
+    if allowed_hosts.is_empty() {
+        return true;
+    }
+
+    let host = host.to_ascii_lowercase();
+    allowed_hosts.iter().any(|allowed| {
+        let allowed = allowed.trim().trim_start_matches("*.").to_ascii_lowercase();
+        host == allowed || host.ends_with(&format!(".{allowed}"))
+    })
+}
+
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_host_allowed_matches_subdomains() {
+        let allowed = vec!["github.com".to_string(), "*.npmmirror.com".to_string()];
+
+        assert!(is_host_allowed("github.com", &[]));
+        assert!(is_host_allowed("objects.github.com", &allowed));
+        assert!(is_host_allowed("cdn.npmmirror.com", &allowed));
+        assert!(!is_host_allowed("evilgithub.com", &allowed));
+        assert!(!is_host_allowed("example.org", &allowed));
+    }
+}
@@ -0,0 +1,89 @@
+//! 系统钥匙串
+//!
+//! GitHub 令牌、PostgreSQL 密码等敏感凭据保存在系统钥匙串（macOS Keychain / Windows 凭据管理器 / Linux Secret Service），
+//! 不写入 `config.json`，也不输出到日志。读取结果在进程内缓存，避免每次使用都访问钥匙串。
+
+use std::sync::{LazyLock, Mutex};
+
+/// 钥匙串中的服务名
+const KEYCHAIN_SERVICE: &str = "n8n-desktop";
+
+/// GitHub 令牌在钥匙串中的账户名
+const GITHUB_TOKEN_ACCOUNT: &str = "github-token";
+
+/// PostgreSQL 密码在钥匙串中的账户名
+const POSTGRES_PASSWORD_ACCOUNT: &str = "postgres-password";
+
+/// 已读取的凭据（外层 `None` 表示尚未读取钥匙串）
+type SecretCache = LazyLock<Mutex<Option<Option<String>>>>;
+
+static GITHUB_TOKEN: SecretCache = LazyLock::new(|| Mutex::new(None));
+static POSTGRES_PASSWORD: SecretCache = LazyLock::new(|| Mutex::new(None));
+
+/// 获取 GitHub 令牌（未设置或钥匙串不可用时为 `None`）
+pub fn github_token() -> Option<String> {
+    cached_secret(&GITHUB_TOKEN, GITHUB_TOKEN_ACCOUNT)
+}
+
+/// 保存 GitHub 令牌；为空时删除
+pub fn set_github_token(token: Option<&str>) -> Result<(), String> {
+    let token = token.map(str::trim).filter(|token| !token.is_empty());
+    store_secret(&GITHUB_TOKEN, GITHUB_TOKEN_ACCOUNT, token)?;
+    tracing::info!(configured = token.is_some(), "已更新 GitHub 令牌");
+    Ok(())
+}
+
+/// 获取 PostgreSQL 密码（未设置或钥匙串不可用时为 `None`）
+pub fn postgres_password() -> Option<String> {
+    cached_secret(&POSTGRES_PASSWORD, POSTGRES_PASSWORD_ACCOUNT)
+}
+
+/// 保存 PostgreSQL 密码；为空时删除（密码可能以空格开头或结尾，不做修剪）
+pub fn set_postgres_password(password: Option<&str>) -> Result<(), String> {
+    let password = password.filter(|password| !password.is_empty());
+    store_secret(&POSTGRES_PASSWORD, POSTGRES_PASSWORD_ACCOUNT, password)?;
+    tracing::info!(configured = password.is_some(), "已更新 PostgreSQL 密码");
+    Ok(())
+}
+
+fn cached_secret(cache: &SecretCache, account: &str) -> Option<String> {
+    cache
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .get_or_insert_with(|| read_secret(account))
+        .clone()
+}
+
+fn store_secret(cache: &SecretCache, account: &str, secret: Option<&str>) -> Result<(), String> {
+    let entry = entry(account)?;
+    match secret {
+        Some(secret) => entry.set_password(secret).map_err(|e| e.to_string())?,
+        None => match entry.delete_password() {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(error) => return Err(error.to_string()),
+        },
+    }
+
+    *cache.lock().unwrap_or_else(|p| p.into_inner()) = Some(secret.map(str::to_string));
+    Ok(())
+}
+
+fn entry(account: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, account).map_err(|e| e.to_string())
+}
+
+/// 读取钥匙串中的凭据（读取失败只记录错误类型，不影响匿名访问）
+fn read_secret(account: &str) -> Option<String> {
+    match entry(account).map(|entry| entry.get_password()) {
+        Ok(Ok(secret)) => Some(secret),
+        Ok(Err(keyring::Error::NoEntry)) => None,
+        Ok(Err(error)) => {
+            tracing::warn!(account, %error, "读取钥匙串失败");
+            None
+        }
+        Err(error) => {
+            tracing::warn!(account, %error, "打开钥匙串失败");
+            None
+        }
+    }
+}
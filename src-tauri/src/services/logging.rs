@@ -0,0 +1,230 @@
+//! 应用自身的结构化日志
+//!
+//! 打包后的 GUI 应用没有控制台，`println!` 的输出无处可看。这里初始化 `tracing`：
+//! 事件写入数据目录的 `logs/app.log`（与 n8n 自身的 `n8n.log` 分开），调试构建同时输出到控制台。
+//! 日志级别来自设置，修改后立即生效，无需重启。
+//!
+//! 另提供按大小轮转的日志文件 [`RotatingLogFile`]，用于保存 n8n 进程的输出。
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::reload;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, Registry};
+
+/// 应用日志文件名
+pub const APP_LOG_FILENAME: &str = "app.log";
+
+/// n8n 日志单个文件的大小上限（5MB），超过后轮转
+pub const N8N_LOG_MAX_BYTES: u64 = 5 * 1024 * 1024;
+
+/// n8n 日志保留的轮转文件数（`n8n.log.1` ~ `n8n.log.3`）
+pub const N8N_LOG_MAX_ROTATED: usize = 3;
+
+/// 应用日志级别
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AppLogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl AppLogLevel {
+    fn filter(self) -> LevelFilter {
+        match self {
+            AppLogLevel::Error => LevelFilter::ERROR,
+            AppLogLevel::Warn => LevelFilter::WARN,
+            AppLogLevel::Info => LevelFilter::INFO,
+            AppLogLevel::Debug => LevelFilter::DEBUG,
+            AppLogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// 运行时调整日志级别的句柄（初始化后才存在）
+static LEVEL_HANDLE: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+/// 初始化日志：写入 `log_dir/app.log`，调试构建同时输出到控制台（重复调用时忽略）
+pub fn init(log_dir: &Path, level: AppLogLevel) {
+    if LEVEL_HANDLE.get().is_some() {
+        return;
+    }
+
+    let (level_layer, handle) = reload::Layer::new(level.filter());
+    let file_layer = open_log_file(log_dir)
+        .map_err(|error| eprintln!("[logging] 无法打开应用日志文件: {error}"))
+        .ok()
+        .map(|file| {
+            fmt::layer()
+                .with_ansi(false)
+                .with_writer(move || SharedFile(file.clone()))
+        });
+    let console_layer = cfg!(debug_assertions).then(fmt::layer);
+
+    let result = tracing_subscriber::registry()
+        .with(level_layer)
+        .with(file_layer)
+        .with(console_layer)
+        .try_init();
+    if result.is_ok() {
+        let _ = LEVEL_HANDLE.set(handle);
+    }
+}
+
+/// 修改日志级别（立即生效）
+pub fn set_level(level: AppLogLevel) {
+    if let Some(handle) = LEVEL_HANDLE.get() {
+        if let Err(error) = handle.reload(level.filter()) {
+            eprintln!("[logging] 修改日志级别失败: {error}");
+        }
+    }
+}
+
+/// 以追加方式打开应用日志文件
+fn open_log_file(log_dir: &Path) -> std::io::Result<Arc<Mutex<File>>> {
+    fs::create_dir_all(log_dir)?;
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_dir.join(APP_LOG_FILENAME))?;
+    Ok(Arc::new(Mutex::new(file)))
+}
+
+/// 多个线程共用的日志文件写入器
+struct SharedFile(Arc<Mutex<File>>);
+
+impl std::io::Write for SharedFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap_or_else(|p| p.into_inner()).write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.lock().unwrap_or_else(|p| p.into_inner()).flush()
+    }
+}
+
+/// 按大小轮转的日志文件
+///
+/// 当前文件写满 `max_bytes` 后依次改名为 `<文件名>.1` ~ `<文件名>.<max_rotated>`（序号越大越旧），
+/// 超出数量的最旧文件被删除。不做内部加锁，多个线程共用时由调用方放在同一把锁内写入与轮转。
+pub struct RotatingLogFile {
+    path: PathBuf,
+    /// 当前文件句柄（轮转期间先关闭，Windows 上无法重命名打开中的文件）
+    file: Option<File>,
+    size: u64,
+    max_bytes: u64,
+    max_rotated: usize,
+}
+
+impl RotatingLogFile {
+    /// 以追加方式打开日志文件（目录不存在时创建）
+    pub fn open(path: &Path, max_bytes: u64, max_rotated: usize) -> std::io::Result<Self> {
+        let file = open_append(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Some(file),
+            size,
+            max_bytes,
+            max_rotated,
+        })
+    }
+
+    /// 写入一行，写入后超过大小上限时先轮转
+    pub fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        let len = line.len() as u64 + 1;
+        if self.size > 0 && self.size + len > self.max_bytes {
+            self.rotate()?;
+        }
+
+        let file = match &mut self.file {
+            Some(file) => file,
+            file => file.insert(open_append(&self.path)?),
+        };
+        writeln!(file, "{line}")?;
+        self.size += len;
+        Ok(())
+    }
+
+    /// 清空当前文件（以追加方式打开，截断后的写入从文件开头继续）
+    pub fn truncate(&mut self) -> std::io::Result<()> {
+        if let Some(file) = &self.file {
+            file.set_len(0)?;
+        } else if self.path.exists() {
+            OpenOptions::new().write(true).open(&self.path)?.set_len(0)?;
+        }
+        self.size = 0;
+        Ok(())
+    }
+
+    /// 轮转：`.N-1` → `.N`、…、当前文件 → `.1`，然后重新创建当前文件
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file = None;
+        let rotated = |index: usize| {
+            let mut name = self.path.clone().into_os_string();
+            name.push(format!(".{index}"));
+            PathBuf::from(name)
+        };
+
+        let _ = fs::remove_file(rotated(self.max_rotated));
+        for index in (1..self.max_rotated).rev() {
+            let from = rotated(index);
+            if from.exists() {
+                fs::rename(&from, rotated(index + 1))?;
+            }
+        }
+        if self.max_rotated > 0 {
+            fs::rename(&self.path, rotated(1))?;
+        } else {
+            fs::remove_file(&self.path)?;
+        }
+
+        self.file = Some(open_append(&self.path)?);
+        self.size = 0;
+        Ok(())
+    }
+}
+
+/// 以追加方式打开文件（父目录不存在时创建）
+fn open_append(path: &Path) -> std::io::Result<File> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+// --- 测试模块 ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+
+    #[test]
+    fn test_rotating_log_file_keeps_limited_history() {
+        let dir = temp_dir().join("n8n_rotating_log_test");
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("n8n.log");
+
+        let mut log = RotatingLogFile::open(&path, 10, 2).expect("打开日志失败");
+        for line in ["first", "second", "third", "fourth"] {
+            log.write_line(line).expect("写入日志失败");
+        }
+
+        let read = |name: &str| fs::read_to_string(dir.join(name)).expect("读取日志失败");
+        assert_eq!(read("n8n.log"), "fourth\n");
+        assert_eq!(read("n8n.log.1"), "third\n");
+        assert_eq!(read("n8n.log.2"), "second\n");
+        assert!(!dir.join("n8n.log.3").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,10 +1,18 @@
+use crate::api::settings::{settings_lock, DownloadMirror, ProcessStdio};
 use crate::i18n;
+use crate::services::logging::{RotatingLogFile, N8N_LOG_MAX_BYTES, N8N_LOG_MAX_ROTATED};
+use crate::services::power;
 use once_cell::sync::Lazy;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
+use std::fs::{self, OpenOptions};
 use std::path::{Path, PathBuf};
-use std::process::{Child, Command, Stdio};
-use std::sync::Mutex;
+use std::io::{BufRead, BufReader, Read};
+use std::net::TcpListener;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 // --- 常量定义 ---
 
@@ -17,16 +25,27 @@ const MINIMUM_N8N_NODE_VERSION: &str = "v22.22.0";
 /// Node.js 官方下载地址
 const NODEJS_BASE_URL: &str = "https://nodejs.org/dist/";
 
-/// Node.js 华为云镜像地址（备用）
-#[allow(dead_code)]
+/// Node.js 华为云镜像地址（国内速度快，海外经常无法访问）
 const NODEJS_HUAWEI_MIRROR_URL: &str = "https://mirrors.huaweicloud.com/nodejs";
 
+/// Node.js 清华大学 TUNA 镜像地址（国内备用）
+const NODEJS_TUNA_MIRROR_URL: &str = "https://mirrors.tuna.tsinghua.edu.cn/nodejs-release";
+
 /// n8n 服务端口
-const N8N_SERVICE_PORT: &str = "5678";
+pub const N8N_SERVICE_PORT: u16 = 5678;
+
+/// 端口被占用时自动选择端口的搜索上限（含）
+const N8N_PORT_SEARCH_END: u16 = 5700;
 
 /// n8n 服务主机
 const N8N_SERVICE_HOST: &str = "127.0.0.1";
 
+/// n8n 主进程子命令
+const N8N_MAIN_SUBCOMMAND: &str = "start";
+
+/// n8n 队列模式 worker 子命令
+const N8N_WORKER_SUBCOMMAND: &str = "worker";
+
 /// Windows 进程创建标志（CREATE_NO_WINDOW）
 #[cfg(windows)]
 const WINDOWS_CREATE_NO_WINDOW_FLAG: u32 = 0x08000000;
@@ -50,32 +69,240 @@ const ENV_N8N_HOST: &str = "N8N_HOST";
 pub static PROCESS_MANAGER: Lazy<Mutex<ProcessManager>> =
     Lazy::new(|| Mutex::new(ProcessManager::new()));
 
+/// 多个输出转发线程共用的日志文件句柄（写入与轮转都在同一把锁内完成）
+type SharedLogFile = Arc<Mutex<RotatingLogFile>>;
+
+/// 当前 n8n 输出写入的日志文件（路径与共享句柄）
+static ACTIVE_LOG_FILE: Lazy<Mutex<Option<(PathBuf, SharedLogFile)>>> =
+    Lazy::new(|| Mutex::new(None));
+
+/// 检查 n8n 进程是否意外退出的间隔
+const PROCESS_EXIT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 请求正常退出后轮询进程是否已退出的间隔
+const GRACEFUL_EXIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 记录本应用启动的 n8n 进程 ID 的文件名（位于数据基础目录，用于清理上次会话遗留的进程）
+pub const N8N_PID_FILENAME: &str = "n8n.pid";
+
+/// n8n 进程生命周期状态
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum N8nProcessState {
+    Stopped,
+    Starting,
+    Running,
+    Stopping,
+    Crashed,
+}
+
+/// 进程状态变化事件负载
+#[derive(Clone, Debug, Serialize)]
+pub struct N8nStateEvent {
+    pub state: N8nProcessState,
+    pub pid: Option<u32>,
+    pub port: Option<u16>,
+    /// 补充说明（如崩溃时的退出状态）
+    pub detail: Option<String>,
+}
+
+/// 进程状态变化监听器
+pub type StateListener = Arc<dyn Fn(&N8nStateEvent) + Send + Sync>;
+
 /// 进程管理器结构体
+///
+/// `child` 为 n8n 主进程；队列模式下的 worker 进程保存在 `workers` 中，随主进程一同终止。
+/// 所有状态变化都在这里完成，并通知注册的监听器，前端据此获得唯一可信的生命周期状态。
 pub struct ProcessManager {
     child: Option<Child>,
+    workers: Vec<Child>,
+    port: Option<u16>,
+    state: N8nProcessState,
+    listener: Option<StateListener>,
+    /// 最近一次主进程退出的状态说明
+    last_exit: Option<String>,
+    /// 最近一次主进程的退出码（被信号终止时为 None）
+    last_exit_code: Option<i32>,
+    /// 最近一次自行退出（非 `kill_child` 终止）的主进程 ID
+    exited_pid: Option<u32>,
 }
 
 impl ProcessManager {
     /// 创建新的进程管理器实例
     pub fn new() -> Self {
-        ProcessManager { child: None }
+        ProcessManager {
+            child: None,
+            workers: Vec::new(),
+            port: None,
+            state: N8nProcessState::Stopped,
+            listener: None,
+            last_exit: None,
+            last_exit_code: None,
+            exited_pid: None,
+        }
+    }
+
+    /// 注册进程状态变化监听器
+    pub fn set_state_listener(&mut self, listener: StateListener) {
+        self.listener = Some(listener);
+    }
+
+    /// 当前生命周期状态
+    pub fn state(&self) -> N8nProcessState {
+        self.state
     }
 
-    /// 设置子进程
+    /// 最近一次主进程退出的状态说明（如 `exit status: 1`）
+    pub fn last_exit(&self) -> Option<String> {
+        self.last_exit.clone()
+    }
+
+    /// 最近一次主进程的退出码（被信号终止或从未退出时为 None）
+    pub fn last_exit_code(&self) -> Option<i32> {
+        self.last_exit_code
+    }
+
+    /// 最近一次自行退出的主进程 ID（用于区分崩溃与用户主动停止）
+    pub fn exited_pid(&self) -> Option<u32> {
+        self.exited_pid
+    }
+
+    /// 切换状态并通知监听器
+    fn transition(&mut self, state: N8nProcessState, detail: Option<String>) {
+        self.state = state;
+        tracing::info!(?state, pid = ?self.pid(), port = ?self.port, ?detail, "n8n 进程状态变化");
+        if let Some(listener) = &self.listener {
+            listener(&N8nStateEvent {
+                state,
+                pid: self.pid(),
+                port: self.port,
+                detail,
+            });
+        }
+    }
+
+    /// 设置子进程（进入 `starting` 状态，健康检查通过后由 `mark_running` 切换为 `running`）
     pub fn set_child(&mut self, child: Child) {
         self.child = Some(child);
+        self.transition(N8nProcessState::Starting, None);
     }
 
-    /// 终止子进程
-    pub fn kill_child(&mut self) {
-        if let Some(mut child) = self.child.take() {
-            // 首先尝试优雅地杀死进程
-            if let Err(error) = child.kill() {
-                eprintln!("终止进程失败: {error}");
+    /// 健康检查通过后标记为运行中
+    pub fn mark_running(&mut self) {
+        if self.child.is_some() && self.state == N8nProcessState::Starting {
+            self.transition(N8nProcessState::Running, None);
+        }
+    }
+
+    /// 检查主进程是否已自行退出；退出时清理句柄并切换为 `crashed`（正常退出码为 `stopped`）
+    pub fn check_exit(&mut self) -> bool {
+        let Some(child) = self.child.as_mut() else {
+            return false;
+        };
+        let Ok(Some(status)) = child.try_wait() else {
+            return false;
+        };
+        let pid = child.id();
+
+        self.kill_workers();
+        power::release_idle();
+        let state = if status.success() {
+            N8nProcessState::Stopped
+        } else {
+            N8nProcessState::Crashed
+        };
+        self.last_exit = Some(status.to_string());
+        self.last_exit_code = status.code();
+        self.exited_pid = Some(pid);
+        self.transition(state, Some(status.to_string()));
+        self.child = None;
+        self.port = None;
+        true
+    }
+
+    /// 添加队列模式 worker 进程
+    pub fn add_worker(&mut self, worker: Child) {
+        self.workers.push(worker);
+    }
+
+    /// 正在管理的 worker 进程数量
+    pub fn worker_count(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// 终止所有 worker 进程（worker 失败不影响主进程的终止结果，仅记录日志）
+    fn kill_workers(&mut self) {
+        for mut worker in self.workers.drain(..) {
+            if let Ok(Some(_)) = worker.try_wait() {
+                continue;
             }
+            if let Err(error) = worker.kill() {
+                tracing::warn!(%error, "终止 worker 进程失败");
+                continue;
+            }
+            let _ = worker.wait();
+        }
+    }
+
+    /// 记录 n8n 实际监听的端口
+    pub fn set_port(&mut self, port: u16) {
+        self.port = Some(port);
+    }
+
+    /// 获取子进程 ID
+    pub fn pid(&self) -> Option<u32> {
+        self.child.as_ref().map(Child::id)
+    }
+
+    /// 获取正在运行的 n8n 服务端口
+    pub fn port(&self) -> Option<u16> {
+        self.child.as_ref().and(self.port)
+    }
+
+    /// 开始终止子进程：终止 worker 进程，取出主进程句柄并切换为 `stopping`
+    ///
+    /// 没有可终止的进程（未启动或已自行退出）时返回 None。取出的进程由 `StoppingChild::terminate`
+    /// 终止，结果交给 `finish_stop` 记录；等待退出期间不必持有管理器的锁（见 `stop_child`）。
+    pub fn begin_stop(&mut self) -> Option<StoppingChild> {
+        self.kill_workers();
+
+        // 进程已自行退出，无需终止
+        if self.child.is_none() || self.check_exit() {
+            self.port = None;
+            return None;
+        }
 
-            // 等待进程完全退出，确保资源释放
-            let _ = child.wait();
+        let previous_state = self.state;
+        self.transition(N8nProcessState::Stopping, None);
+        let child = self.child.take()?;
+        Some(StoppingChild { child, previous_state })
+    }
+
+    /// 记录终止结果：成功时切换为 `stopped`，失败时放回子进程句柄并恢复之前的状态
+    ///
+    /// 等待期间已启动了新的进程时只记录退出状态，不改变新进程的状态。
+    pub fn finish_stop(&mut self, result: StopResult) -> std::io::Result<bool> {
+        let restarted = self.child.is_some();
+        match result {
+            Ok(status) => {
+                self.last_exit = status.map(|status| status.to_string());
+                self.last_exit_code = status.and_then(|status| status.code());
+                if !restarted {
+                    power::release_idle();
+                    self.transition(N8nProcessState::Stopped, None);
+                    self.port = None;
+                }
+                Ok(true)
+            }
+            Err((error, stopping)) => {
+                if restarted {
+                    tracing::error!(pid = stopping.child.id(), "已启动新的 n8n 进程，放弃管理未能终止的旧进程");
+                } else {
+                    self.child = Some(stopping.child);
+                    self.transition(stopping.previous_state, Some(error.to_string()));
+                }
+                Err(error)
+            }
         }
     }
 
@@ -85,6 +312,57 @@ impl ProcessManager {
     }
 }
 
+/// 终止结果：进程的退出状态（强制终止后无法获取时为 None），或终止失败的错误与未能终止的进程
+pub type StopResult = Result<Option<ExitStatus>, (std::io::Error, StoppingChild)>;
+
+/// 已从管理器中取出、正在终止的主进程
+pub struct StoppingChild {
+    child: Child,
+    previous_state: N8nProcessState,
+}
+
+impl StoppingChild {
+    /// 终止进程（阻塞，最长 `shutdown_timeout_secs`；异步代码中须在 `spawn_blocking` 中调用）
+    ///
+    /// 先请求正常退出（Unix 发送 SIGTERM，Windows 执行不带 `/F` 的 taskkill），让 n8n 写完
+    /// SQLite 数据库、结束进行中的执行；超过设置的 `shutdown_timeout_secs` 仍未退出时才强制终止。
+    pub fn terminate(mut self) -> StopResult {
+        let timeout = Duration::from_secs(settings_lock().shutdown_timeout_secs);
+        if !timeout.is_zero() && request_graceful_exit(self.child.id()) {
+            if let Some(status) = wait_for_exit(&mut self.child, timeout) {
+                return Ok(Some(status));
+            }
+            tracing::warn!(timeout_secs = timeout.as_secs(), "n8n 未在超时内退出，强制终止");
+        }
+
+        if let Err(error) = self.child.kill() {
+            tracing::error!(%error, "终止进程失败");
+            return Err((error, self));
+        }
+        // 等待进程完全退出，确保资源释放
+        Ok(self.child.wait().ok())
+    }
+}
+
+/// 终止管理器中的 n8n 子进程，队列模式下的 worker 进程总是一并终止
+///
+/// 等待进程正常退出（最长 `shutdown_timeout_secs`）期间不持有管理器的锁，
+/// 状态查询、退出监视等操作不会被阻塞。
+///
+/// 该函数会阻塞调用线程，异步代码中须通过 `tokio::task::spawn_blocking` 调用。误在 tokio 工作线程上
+/// 调用时，等待经由 `block_in_place` 执行，避免占住工作线程；在单线程运行时中调用会直接 panic。
+///
+/// 返回 `Ok(true)` 表示终止了一个仍在运行的进程，`Ok(false)` 表示没有可终止的进程
+/// （未启动或已自行退出）；终止失败时保留子进程句柄并返回错误。
+pub fn stop_child(manager: &Mutex<ProcessManager>) -> std::io::Result<bool> {
+    let lock = || manager.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let Some(stopping) = lock().begin_stop() else {
+        return Ok(false);
+    };
+    let result = tokio::task::block_in_place(|| stopping.terminate());
+    lock().finish_stop(result)
+}
+
 // --- Node.js 下载 URL 生成 ---
 
 /// 获取当前平台的 Node.js 下载 URL
@@ -129,49 +407,118 @@ pub fn get_node_download_urls() -> Result<Vec<String>, String> {
     let version = get_compatible_node_version();
 
     let extension = if platform == "windows" { "zip" } else { "tar.gz" };
-    let platform_arch = match (platform, architecture) {
-        ("macos", "aarch64") => "darwin-arm64",
-        ("macos", "x86_64") => "darwin-x64",
-        ("windows", _) => "win-x64",
-        _ => {
-            return Err(format!("{}: {platform} {architecture}", i18n::t("runtime.unsupported_platform")))
-        }
+    let Some(platform_arch) = node_platform_arch(platform, architecture) else {
+        return Err(format!("{}: {platform} {architecture}", i18n::t("runtime.unsupported_platform")));
     };
 
-    let mut urls = vec![format_nodejs_url_for_base(
-        NODEJS_BASE_URL,
-        platform_arch,
-        &version,
-        extension,
-    )];
+    let mirror = settings_lock().download_mirror.clone();
+    let prefer_china_mirrors = i18n::current_lang() == i18n::Lang::Zh;
+    let urls = node_mirror_bases(&mirror, prefer_china_mirrors)
+        .iter()
+        .map(|base| format_nodejs_url_for_base(base, platform_arch, &version, extension))
+        .collect();
+
+    Ok(urls)
+}
 
-    if !NODEJS_HUAWEI_MIRROR_URL.is_empty() {
-        urls.push(format_nodejs_url_for_base(
-            NODEJS_HUAWEI_MIRROR_URL,
-            platform_arch,
-            &version,
-            extension,
-        ));
+/// Node.js 发布包的平台架构标识（如 `win-arm64`），不支持的平台返回 None
+///
+/// Windows on ARM 必须使用 `win-arm64` 构建：x64 构建在模拟层下运行缓慢，且与 arm64 原生模块不兼容。
+fn node_platform_arch(platform: &str, architecture: &str) -> Option<&'static str> {
+    match (platform, architecture) {
+        ("macos", "aarch64") => Some("darwin-arm64"),
+        ("macos", "x86_64") => Some("darwin-x64"),
+        ("linux", "x86_64") => Some("linux-x64"),
+        ("linux", "aarch64") => Some("linux-arm64"),
+        ("windows", "x86_64") => Some("win-x64"),
+        ("windows", "aarch64") => Some("win-arm64"),
+        _ => None,
     }
+}
 
-    Ok(urls)
+/// 按下载源设置排列 Node.js 下载源，其余内置源作为备用
+///
+/// 未指定 Node.js 镜像时按地区偏好排列（中文环境优先国内镜像，其余优先官方地址）。
+fn node_mirror_bases(mirror: &DownloadMirror, prefer_china_mirrors: bool) -> Vec<String> {
+    let builtin = if prefer_china_mirrors {
+        [NODEJS_HUAWEI_MIRROR_URL, NODEJS_TUNA_MIRROR_URL, NODEJS_BASE_URL]
+    } else {
+        [NODEJS_BASE_URL, NODEJS_HUAWEI_MIRROR_URL, NODEJS_TUNA_MIRROR_URL]
+    };
+    let preferred = match mirror {
+        DownloadMirror::Huawei => Some(NODEJS_HUAWEI_MIRROR_URL),
+        DownloadMirror::Official => Some(NODEJS_BASE_URL),
+        DownloadMirror::Custom(url) => Some(url.trim()),
+        DownloadMirror::Auto | DownloadMirror::GhProxy => None,
+    };
+
+    let mut bases: Vec<String> = preferred.into_iter().map(str::to_string).collect();
+    bases.extend(
+        builtin
+            .into_iter()
+            .filter(|base| Some(*base) != preferred)
+            .map(str::to_string),
+    );
+    bases
 }
 
 /// 检查当前运行时目录中的 Node 是否兼容 n8n
 pub fn is_runtime_compatible(runtime_dir: &PathBuf) -> bool {
-    let node_path = get_node_binary_path(runtime_dir.clone());
+    let version_supported = get_runtime_version(runtime_dir)
+        .is_some_and(|version| is_supported_node_version(&version));
+
+    // 架构不一致（如 Apple Silicon 上的 x64 Node）同样视为不兼容，触发重新下载
+    version_supported && !is_runtime_arch_mismatched(runtime_dir)
+}
+
+/// 读取运行时 Node 的版本号（`node --version`），无法执行时返回 None
+pub fn get_runtime_version(runtime_dir: &Path) -> Option<String> {
+    let node_path = get_node_binary_path(runtime_dir.to_path_buf());
     if !node_path.exists() {
-        return false;
+        return None;
     }
 
-    let output = Command::new(&node_path).arg("--version").output();
-    match output {
-        Ok(output) if output.status.success() => {
-            let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            is_supported_node_version(&version)
-        }
-        _ => false,
+    let output = Command::new(&node_path).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
     }
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// 获取当前系统对应的 Node.js 架构标识（与 `process.arch` 取值一致）
+pub fn expected_node_arch() -> &'static str {
+    match env::consts::ARCH {
+        "aarch64" => "arm64",
+        "x86_64" => "x64",
+        "x86" => "ia32",
+        other => other,
+    }
+}
+
+/// 读取运行时 Node 报告的架构（`node -p process.arch`）
+pub fn get_runtime_arch(runtime_dir: &Path) -> Option<String> {
+    let node_path = get_node_binary_path(runtime_dir.to_path_buf());
+    if !node_path.exists() {
+        return None;
+    }
+
+    let output = Command::new(&node_path)
+        .args(["-p", "process.arch"])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let arch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!arch.is_empty()).then_some(arch)
+}
+
+/// 检查运行时架构是否与系统不一致（无法检测时视为一致）
+pub fn is_runtime_arch_mismatched(runtime_dir: &Path) -> bool {
+    get_runtime_arch(runtime_dir).is_some_and(|arch| arch != expected_node_arch())
 }
 
 /// 格式化 Node.js 下载 URL
@@ -191,69 +538,107 @@ fn format_nodejs_url_for_base(base_url: &str, platform_arch: &str, version: &str
 
 // --- Node.js 二进制路径查找 ---
 
-/// 获取 Node.js 二进制文件路径
+/// 获取 Node.js 二进制文件路径（仅按文件位置判断，不验证能否运行）
 pub fn get_node_binary_path(runtime_dir: PathBuf) -> PathBuf {
-    if cfg!(target_os = "windows") {
-        find_windows_node_binary(&runtime_dir)
-    } else {
-        find_unix_node_binary(&runtime_dir)
-    }
-}
-
-/// 在 Windows 上查找 Node.js 二进制文件
-fn find_windows_node_binary(runtime_dir: &PathBuf) -> PathBuf {
-    let direct_path = runtime_dir.join("node.exe");
-
+    let direct_path = direct_node_binary_path(&runtime_dir);
     if direct_path.exists() {
         return direct_path;
     }
 
-    search_node_binary(runtime_dir, "node.exe").unwrap_or(direct_path)
+    ranked_node_candidates(&runtime_dir)
+        .into_iter()
+        .next()
+        .unwrap_or(direct_path)
 }
 
-/// 在 Unix 系统上查找 Node.js 二进制文件
-fn find_unix_node_binary(runtime_dir: &PathBuf) -> PathBuf {
-    let direct_path = runtime_dir.join("bin/node");
+/// 获取经过验证、可以正常运行的 Node.js 二进制文件路径
+///
+/// 依次尝试直接路径与搜索到的候选文件，以 `--version` 验证；
+/// 避免误用压缩包中 `node_modules/.bin` 下的 shim 导致启动失败。
+pub fn resolve_node_binary(runtime_dir: &Path) -> Result<PathBuf, String> {
+    let direct_path = direct_node_binary_path(runtime_dir);
+    let candidates = std::iter::once(direct_path)
+        .filter(|path| path.exists())
+        .chain(ranked_node_candidates(runtime_dir));
+
+    for candidate in candidates {
+        if let Some(version) = node_binary_version(&candidate) {
+            tracing::info!(%version, path = %candidate.display(), "使用 Node.js");
+            return Ok(candidate);
+        }
+        tracing::warn!(path = %candidate.display(), "跳过无法运行的 node");
+    }
 
-    if direct_path.exists() {
-        return direct_path;
+    Err(format!(
+        "{}: {}",
+        i18n::t("node.no_working_binary"),
+        runtime_dir.display()
+    ))
+}
+
+/// 运行时目录下 Node.js 的标准位置（Windows 为 `node.exe`，其他系统为 `bin/node`）
+fn direct_node_binary_path(runtime_dir: &Path) -> PathBuf {
+    if cfg!(target_os = "windows") {
+        runtime_dir.join("node.exe")
+    } else {
+        runtime_dir.join("bin/node")
     }
+}
+
+/// 执行 `node --version`，成功时返回版本号
+fn node_binary_version(node_path: &Path) -> Option<String> {
+    let mut command = Command::new(node_path);
+    command.arg("--version").stdin(Stdio::null());
+    apply_platform_specific_config(&mut command);
 
-    search_node_binary(runtime_dir, "bin/node").unwrap_or(direct_path)
+    let output = command.output().ok().filter(|output| output.status.success())?;
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    version.starts_with('v').then_some(version)
 }
 
-/// 递归搜索 Node.js 二进制文件
-fn search_node_binary(directory: &PathBuf, target_name: &str) -> Option<PathBuf> {
-    use std::fs;
+/// 搜索运行时目录中的 Node.js 候选文件并排序
+///
+/// 与 `npm` / `npx` 位于同一目录的优先（真正的运行时），其余按目录深度由浅到深。
+fn ranked_node_candidates(runtime_dir: &Path) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    collect_node_candidates(runtime_dir, &mut candidates);
 
-    // 首先尝试直接路径
-    let candidate = directory.join(target_name);
-    if candidate.exists() {
-        return Some(candidate);
-    }
+    candidates.sort_by_key(|path| (!is_adjacent_to_npm(path), path.components().count()));
+    candidates
+}
 
-    // 递归搜索目录
-    if let Ok(entries) = fs::read_dir(directory) {
-        for entry in entries.flatten() {
-            let path = entry.path();
+/// 递归收集名为 `node` / `node.exe` 的文件（跳过 `node_modules`，其中的 `.bin/node` 可能只是 shim）
+fn collect_node_candidates(directory: &Path, candidates: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
 
-            if path.is_dir() {
-                // 递归搜索子目录
-                if let Some(found) = search_node_binary(&path, target_name) {
-                    return Some(found);
-                }
-            } else if is_node_binary_file(&path) {
-                // 找到 node 或 node.exe 文件
-                return Some(path);
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            let skipped = path
+                .file_name()
+                .is_some_and(|name| name == "node_modules" || name == ".bin");
+            if !skipped {
+                collect_node_candidates(&path, candidates);
             }
+        } else if is_node_binary_file(&path) {
+            candidates.push(path);
         }
     }
+}
 
-    None
+/// 检查候选文件旁边是否有 `npm` / `npx`（Windows 为 `.cmd`）
+fn is_adjacent_to_npm(path: &Path) -> bool {
+    path.parent().is_some_and(|dir| {
+        ["npm", "npm.cmd", "npx", "npx.cmd"]
+            .iter()
+            .any(|name| dir.join(name).exists())
+    })
 }
 
 /// 检查文件是否为 Node.js 二进制文件
-fn is_node_binary_file(path: &PathBuf) -> bool {
+fn is_node_binary_file(path: &Path) -> bool {
     path.file_name()
         .and_then(|name| name.to_str())
         .is_some_and(|name| name == "node" || name == "node.exe")
@@ -261,12 +646,35 @@ fn is_node_binary_file(path: &PathBuf) -> bool {
 
 // --- n8n 进程启动 ---
 
+/// n8n 输出流
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+/// n8n 日志行处理器（逐行接收 n8n 的 stdout/stderr 输出及其来源）
+pub type LogLineHandler = Arc<dyn Fn(LogStream, &str) + Send + Sync>;
+
 /// n8n 启动配置
 pub struct N8nStartConfig {
     pub node_path: PathBuf,
     pub n8n_binary: PathBuf,
     pub user_data_dir: PathBuf,
     pub additional_envs: HashMap<String, String>,
+    /// 追加在 `start` 之后的命令行参数（如 `--tunnel`）
+    pub extra_args: Vec<String>,
+    /// 日志行处理器，设置后 n8n 的输出将被转发并逐行交给处理器
+    pub log_handler: Option<LogLineHandler>,
+    /// 标准输出的处理方式
+    pub stdio: ProcessStdio,
+    /// `piped` 模式下输出写入的日志文件
+    pub log_file: Option<PathBuf>,
+    /// 队列模式下随主进程启动的 worker 数量（0 表示不启动）
+    pub worker_count: usize,
+    /// 记录主进程与 worker 进程 ID 的文件（启动前据此终止上次会话遗留的 n8n）
+    pub pid_file: Option<PathBuf>,
 }
 
 /// 启动 n8n 进程
@@ -276,76 +684,436 @@ pub fn start_node(
     user_data: PathBuf,
     additional_envs: HashMap<String, String>,
 ) -> Result<(), String> {
-    let config = N8nStartConfig {
+    start_node_with_config(N8nStartConfig {
         node_path,
         n8n_binary: n8n_bin,
         user_data_dir: user_data,
         additional_envs,
-    };
+        extra_args: Vec::new(),
+        log_handler: None,
+        stdio: ProcessStdio::default(),
+        log_file: None,
+        worker_count: 0,
+        pid_file: None,
+    })
+}
+
+/// 按完整配置启动 n8n 进程
+pub fn start_node_with_config(config: N8nStartConfig) -> Result<(), String> {
+    // 额外环境变量可覆盖默认端口，以实际生效的端口为准
+    let port = config
+        .additional_envs
+        .get(ENV_N8N_PORT)
+        .and_then(|value| value.parse::<u16>().ok())
+        .unwrap_or(N8N_SERVICE_PORT);
+
+    stop_previous_instances(config.pid_file.as_deref())?;
+    let child = create_and_start_n8n_process(&config, N8N_MAIN_SUBCOMMAND)?;
+    let mut pids = vec![child.id()];
+    register_process_with_manager(child, port);
+
+    for _ in 0..config.worker_count {
+        match create_and_start_n8n_process(&config, N8N_WORKER_SUBCOMMAND) {
+            Ok(worker) => {
+                pids.push(worker.id());
+                if let Ok(mut manager) = PROCESS_MANAGER.lock() {
+                    manager.add_worker(worker);
+                }
+            }
+            Err(error) => {
+                // worker 启动失败时整体回滚，避免留下无法执行任务的主进程
+                let _ = stop_child(&PROCESS_MANAGER);
+                return Err(error);
+            }
+        }
+    }
+
+    if let Some(pid_file) = &config.pid_file {
+        let content: Vec<String> = pids.iter().map(u32::to_string).collect();
+        if let Err(error) = fs::write(pid_file, content.join("\n")) {
+            tracing::warn!(%error, path = %pid_file.display(), "写入 PID 文件失败");
+        }
+    }
+    Ok(())
+}
 
-    terminate_existing_node_processes();
-    let child = create_and_start_n8n_process(&config)?;
-    register_process_with_manager(child);
+/// 停止之前启动的 n8n：本次会话管理的子进程正常终止，上次会话遗留的进程按 PID 文件终止
+///
+/// 只处理本应用启动的进程，不影响用户的其他 Node.js 进程（开发服务器、编辑器插件等）。
+/// 选择端口前应先调用，避免把旧实例占用的端口当作被其他程序占用。
+pub fn stop_previous_instances(pid_file: Option<&Path>) -> Result<(), String> {
+    stop_child(&PROCESS_MANAGER).map_err(|error| format!("{}: {error}", i18n::t("process.stop_previous_failed")))?;
 
+    if let Some(pid_file) = pid_file {
+        terminate_stale_processes(pid_file);
+    }
     Ok(())
 }
 
-/// 终止现有的 node 进程（仅 Unix 系统）
-fn terminate_existing_node_processes() {
+/// 终止 PID 文件中记录的、上次会话遗留的 n8n 进程（应用崩溃或被强制退出时未能停止）
+///
+/// PID 可能已被系统复用：只终止命令行中包含 PID 文件所在目录（n8n 入口位于其中）的进程。
+/// 先请求正常退出，超过设置的 `shutdown_timeout_secs` 仍未退出时强制终止。
+fn terminate_stale_processes(pid_file: &Path) {
+    let Ok(content) = fs::read_to_string(pid_file) else {
+        return;
+    };
+    let Some(marker) = pid_file.parent() else {
+        return;
+    };
+
+    let timeout = Duration::from_secs(settings_lock().shutdown_timeout_secs);
+    for pid in content.lines().filter_map(|line| line.trim().parse::<u32>().ok()) {
+        if !is_n8n_process(pid, marker) {
+            continue;
+        }
+        tracing::warn!(pid, "终止上次会话遗留的 n8n 进程");
+        let exited = !timeout.is_zero()
+            && request_graceful_exit(pid)
+            && wait_for_pid_exit(pid, marker, timeout);
+        if !exited {
+            force_kill_pid(pid);
+        }
+    }
+    let _ = fs::remove_file(pid_file);
+}
+
+/// 进程 `pid` 是否存在且命令行中包含 `marker` 路径
+fn is_n8n_process(pid: u32, marker: &Path) -> bool {
     #[cfg(unix)]
-    {
-        let _ = Command::new("pkill").arg("-9").arg("node").output();
+    let mut command = {
+        let mut command = Command::new("ps");
+        command.args(["-o", "command=", "-p", &pid.to_string()]);
+        command
+    };
+
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = Command::new("powershell");
+        command.args([
+            "-NoProfile",
+            "-Command",
+            &format!("(Get-CimInstance Win32_Process -Filter 'ProcessId={pid}').CommandLine"),
+        ]);
+        command
+    };
+
+    apply_platform_specific_config(&mut command);
+    let marker = marker.to_string_lossy();
+    command.stderr(Stdio::null()).output().is_ok_and(|output| {
+        output.status.success() && String::from_utf8_lossy(&output.stdout).contains(marker.as_ref())
+    })
+}
+
+/// 在 `timeout` 内等待进程 `pid` 退出，返回是否已退出
+fn wait_for_pid_exit(pid: u32, marker: &Path, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if !is_n8n_process(pid, marker) {
+            return true;
+        }
+        std::thread::sleep(GRACEFUL_EXIT_POLL_INTERVAL);
     }
+    !is_n8n_process(pid, marker)
 }
 
-/// 创建并启动 n8n 进程
-fn create_and_start_n8n_process(config: &N8nStartConfig) -> Result<Child, String> {
-    let mut command = build_n8n_command(config)?;
-    configure_process_stdio(&mut command);
+/// 强制终止进程 `pid`
+fn force_kill_pid(pid: u32) {
+    let pid = pid.to_string();
+
+    #[cfg(unix)]
+    let mut command = {
+        let mut command = Command::new("kill");
+        command.args(["-KILL", &pid]);
+        command
+    };
+
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = Command::new("taskkill");
+        command.args(["/PID", &pid, "/T", "/F"]);
+        command
+    };
+
     apply_platform_specific_config(&mut command);
+    if let Err(error) = command.stdout(Stdio::null()).stderr(Stdio::null()).status() {
+        tracing::error!(%error, pid, "强制终止进程失败");
+    }
+}
 
-    command
+/// 创建并启动 n8n 进程（`subcommand` 为 `start` 或 `worker`）
+fn create_and_start_n8n_process(config: &N8nStartConfig, subcommand: &str) -> Result<Child, String> {
+    let mut command = build_n8n_command(config, subcommand)?;
+    // 需要扫描日志（如隧道地址）时，无论哪种模式都必须通过管道读取输出
+    let capture_output = config.stdio == ProcessStdio::Piped || config.log_handler.is_some();
+    configure_process_stdio(&mut command, config.stdio, capture_output);
+    apply_platform_specific_config(&mut command);
+
+    let mut child = command
         .spawn()
-        .map_err(|error| format!("{}: {error}", i18n::t("process.spawn_failed")))
+        .map_err(|error| format!("{}: {error}", i18n::t("process.spawn_failed")))?;
+
+    #[cfg(target_os = "linux")]
+    check_runtime_link(&mut child, &config.node_path)?;
+
+    if capture_output {
+        let sink = OutputSink::new(config);
+        if let Some(stdout) = child.stdout.take() {
+            forward_output_lines(stdout, sink.clone(), LogStream::Stdout);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_output_lines(stderr, sink, LogStream::Stderr);
+        }
+    }
+
+    Ok(child)
 }
 
-/// 构建 n8n 命令
-fn build_n8n_command(config: &N8nStartConfig) -> Result<Command, String> {
-    let user_data_str = config
-        .user_data_dir
-        .to_str()
-        .ok_or(i18n::t("process.invalid_user_data_path"))?;
+/// 启动后等待进程因动态链接失败退出的时间
+#[cfg(target_os = "linux")]
+const RUNTIME_LINK_CHECK_DELAY: Duration = Duration::from_millis(200);
+
+/// 检查 Node.js 是否因缺少共享库（glibc / libstdc++ 等）而在启动后立即退出
+///
+/// 精简 Linux 发行版上 `spawn` 本身会成功，动态链接器的报错写入继承的 stderr 后丢失；
+/// 进程已退出时重新执行 `node --version` 取回报错，返回列出缺失库的 `RUNTIME_LINK_ERROR`。
+#[cfg(target_os = "linux")]
+fn check_runtime_link(child: &mut Child, node_path: &Path) -> Result<(), String> {
+    std::thread::sleep(RUNTIME_LINK_CHECK_DELAY);
+    if !matches!(child.try_wait(), Ok(Some(_))) {
+        return Ok(());
+    }
 
-    let mut command = Command::new(&config.node_path);
+    // Node.js 本身能运行时，退出原因交给进程退出监控处理
+    let Ok(output) = Command::new(node_path)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+    else {
+        return Ok(());
+    };
+    if output.status.success() {
+        return Ok(());
+    }
 
-    command
-        .arg(&config.n8n_binary)
-        .arg("start")
-        .env(ENV_N8N_USER_FOLDER, user_data_str)
-        .env(ENV_N8N_DISABLE_INTERACTIVE_REPL, "true")
-        .env(ENV_N8N_BLOCK_IFRAME_EMBEDS, "false")
-        .env(ENV_N8N_USE_SAMESITE_COOKIE_STRICT, "false")
-        .env(ENV_N8N_CORS_ALLOWED_ORIGINS, "*")
-        .env(ENV_N8N_SECURE_COOKIE, "false")
-        .env(ENV_N8N_USER_MANAGEMENT_DISABLED, "true")
-        .env(ENV_SKIP_SETUP, "true")
-        .env(ENV_N8N_PORT, N8N_SERVICE_PORT)
-        .env(ENV_N8N_HOST, N8N_SERVICE_HOST);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let libraries = missing_shared_libraries(&stderr);
+    let detail = if libraries.is_empty() {
+        stderr.lines().next().unwrap_or_default().trim().to_string()
+    } else {
+        libraries.join(", ")
+    };
+    tracing::error!(node = %node_path.display(), %stderr, "Node.js 动态链接失败");
+    Err(format!("[RUNTIME_LINK_ERROR] {}: {detail}", i18n::t("runtime.link_error")))
+}
 
-    // 添加额外的环境变量
-    for (key, value) in &config.additional_envs {
-        command.env(key, value);
+/// 从动态链接器的报错中提取缺失（或版本过低）的共享库
+///
+/// 支持 `error while loading shared libraries: <lib>: ...` 与
+/// `<path>/<lib>: version `<ver>' not found` 两种格式，后者记为 `<lib> (<ver>)`。
+#[cfg(any(target_os = "linux", test))]
+fn missing_shared_libraries(stderr: &str) -> Vec<String> {
+    const MISSING_MARKER: &str = "error while loading shared libraries: ";
+    const VERSION_MARKER: &str = ": version `";
+
+    let mut libraries: Vec<String> = Vec::new();
+    for line in stderr.lines() {
+        let library = if let Some((_, rest)) = line.split_once(MISSING_MARKER) {
+            rest.split(':').next().unwrap_or_default().trim().to_string()
+        } else if let Some((path, rest)) = line.split_once(VERSION_MARKER) {
+            let path = path.rsplit(": ").next().unwrap_or_default();
+            let name = path.rsplit('/').next().unwrap_or_default().trim();
+            let version = rest.split('\'').next().unwrap_or_default();
+            format!("{name} ({version})")
+        } else {
+            continue;
+        };
+        if !library.is_empty() && !libraries.contains(&library) {
+            libraries.push(library);
+        }
+    }
+    libraries
+}
+
+/// 管道读取到的输出去向
+#[derive(Clone)]
+struct OutputSink {
+    /// 是否原样转发到控制台（`inherit` 模式）
+    echo: bool,
+    /// 日志文件（`piped` 模式）
+    log_file: Option<SharedLogFile>,
+    handler: Option<LogLineHandler>,
+}
+
+impl OutputSink {
+    fn new(config: &N8nStartConfig) -> Self {
+        let log_file = match (&config.stdio, &config.log_file) {
+            (ProcessStdio::Piped, Some(path)) => shared_log_file(path)
+                .map_err(|error| {
+                    tracing::error!(path = %path.display(), %error, "无法打开 n8n 日志文件")
+                })
+                .ok(),
+            _ => None,
+        };
+
+        Self {
+            echo: config.stdio == ProcessStdio::Inherit,
+            log_file,
+            handler: config.log_handler.clone(),
+        }
+    }
+}
+
+/// 获取日志文件的共享句柄（主进程与 worker 共用同一个句柄，清理日志时可统一加锁）
+fn shared_log_file(path: &Path) -> std::io::Result<SharedLogFile> {
+    let mut active = ACTIVE_LOG_FILE.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some((active_path, file)) = active.as_ref() {
+        if active_path == path && path.exists() {
+            return Ok(file.clone());
+        }
+    }
+
+    let file = Arc::new(Mutex::new(RotatingLogFile::open(
+        path,
+        N8N_LOG_MAX_BYTES,
+        N8N_LOG_MAX_ROTATED,
+    )?));
+    *active = Some((path.to_path_buf(), file.clone()));
+    Ok(file)
+}
+
+/// 清空日志文件
+///
+/// 正在写入的日志文件会先获取写入锁再截断，不会与输出转发线程并发写入或轮转；
+/// 句柄以追加方式打开，截断后的写入从文件开头继续。
+pub fn truncate_log_file(path: &Path) -> std::io::Result<()> {
+    let active = ACTIVE_LOG_FILE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .as_ref()
+        .filter(|(active_path, _)| active_path == path)
+        .map(|(_, file)| file.clone());
+
+    match active {
+        Some(file) => file
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .truncate(),
+        None if path.exists() => OpenOptions::new().write(true).open(path)?.set_len(0),
+        None => Ok(()),
+    }
+}
+
+/// 在后台线程中逐行读取进程输出，按配置转发到控制台、日志文件和处理器
+fn forward_output_lines<S: Read + Send + 'static>(stream: S, sink: OutputSink, source: LogStream) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            if sink.echo {
+                match source {
+                    LogStream::Stdout => tracing::info!(target: "n8n", stream = "stdout", "{line}"),
+                    LogStream::Stderr => tracing::info!(target: "n8n", stream = "stderr", "{line}"),
+                }
+            }
+            if let Some(file) = &sink.log_file {
+                let mut file = file.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                let _ = file.write_line(&line);
+            }
+            if let Some(handler) = &sink.handler {
+                handler(source, &line);
+            }
+        }
+    });
+}
+
+/// 构建 n8n 命令（附加参数如 `--tunnel` 仅作用于主进程）
+fn build_n8n_command(config: &N8nStartConfig, subcommand: &str) -> Result<Command, String> {
+    let envs = n8n_process_envs(&config.user_data_dir, &config.additional_envs)?;
+
+    let mut command = Command::new(&config.node_path);
+
+    command.arg(&config.n8n_binary).arg(subcommand).envs(envs);
+    if subcommand == N8N_MAIN_SUBCOMMAND {
+        command.args(&config.extra_args);
     }
 
     Ok(command)
 }
 
-/// 配置进程的标准输入/输出
-fn configure_process_stdio(command: &mut Command) {
+/// 构建一次性执行的 n8n CLI 命令（如 `export:credentials`），环境变量与服务进程一致
+///
+/// 使用相同的 `N8N_USER_FOLDER` 才能读取到同一个数据库与加密密钥。
+pub fn build_n8n_cli_command(
+    node_path: &Path,
+    n8n_binary: &Path,
+    user_data_dir: &Path,
+    additional_envs: &HashMap<String, String>,
+    args: &[String],
+) -> Result<Command, String> {
+    let envs = n8n_process_envs(user_data_dir, additional_envs)?;
+
+    let mut command = Command::new(node_path);
     command
+        .arg(n8n_binary)
+        .args(args)
+        .envs(envs)
         .stdin(Stdio::null())
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit());
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    apply_platform_specific_config(&mut command);
+
+    Ok(command)
+}
+
+/// 计算传给 n8n 进程的环境变量（默认值 + 额外环境变量，后者可覆盖前者）
+///
+/// 启动进程与 `get_effective_env` 共用此函数，保证展示的内容与实际生效的一致。
+pub fn n8n_process_envs(
+    user_data_dir: &Path,
+    additional_envs: &HashMap<String, String>,
+) -> Result<BTreeMap<String, String>, String> {
+    let user_data_str = user_data_dir
+        .to_str()
+        .ok_or(i18n::t("process.invalid_user_data_path"))?;
+
+    let mut envs = BTreeMap::from([
+        (ENV_N8N_USER_FOLDER.to_string(), user_data_str.to_string()),
+        (ENV_N8N_DISABLE_INTERACTIVE_REPL.to_string(), "true".to_string()),
+        (ENV_N8N_BLOCK_IFRAME_EMBEDS.to_string(), "false".to_string()),
+        (ENV_N8N_USE_SAMESITE_COOKIE_STRICT.to_string(), "false".to_string()),
+        (ENV_N8N_CORS_ALLOWED_ORIGINS.to_string(), "*".to_string()),
+        (ENV_N8N_SECURE_COOKIE.to_string(), "false".to_string()),
+        (ENV_N8N_USER_MANAGEMENT_DISABLED.to_string(), "true".to_string()),
+        (ENV_SKIP_SETUP.to_string(), "true".to_string()),
+        (ENV_N8N_PORT.to_string(), N8N_SERVICE_PORT.to_string()),
+        (ENV_N8N_HOST.to_string(), N8N_SERVICE_HOST.to_string()),
+    ]);
+
+    // 添加额外的环境变量
+    envs.extend(
+        additional_envs
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone())),
+    );
+
+    Ok(envs)
+}
+
+/// 配置进程的标准输入/输出（需要扫描日志时改为管道）
+fn configure_process_stdio(command: &mut Command, stdio: ProcessStdio, capture_output: bool) {
+    let output = || {
+        if capture_output {
+            return Stdio::piped();
+        }
+        match stdio {
+            ProcessStdio::Inherit => Stdio::inherit(),
+            ProcessStdio::Piped => Stdio::piped(),
+            ProcessStdio::Null => Stdio::null(),
+        }
+    };
+
+    command.stdin(Stdio::null()).stdout(output()).stderr(output());
 }
 
 /// 应用平台特定的配置
@@ -357,11 +1125,97 @@ fn apply_platform_specific_config(command: &mut Command) {
     }
 }
 
-/// 将进程注册到全局管理器
-fn register_process_with_manager(child: Child) {
+/// 请求进程正常退出（Unix 发送 SIGTERM，Windows 执行不带 `/F` 的 taskkill），返回请求是否发送成功
+fn request_graceful_exit(pid: u32) -> bool {
+    let pid = pid.to_string();
+
+    #[cfg(unix)]
+    let mut command = {
+        let mut command = Command::new("kill");
+        command.args(["-TERM", &pid]);
+        command
+    };
+
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = Command::new("taskkill");
+        command.args(["/PID", &pid, "/T"]);
+        command
+    };
+
+    apply_platform_specific_config(&mut command);
+    command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// 在 `timeout` 内等待进程退出，超时返回 None
+fn wait_for_exit(child: &mut Child, timeout: Duration) -> Option<ExitStatus> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return Some(status),
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(GRACEFUL_EXIT_POLL_INTERVAL),
+            _ => return None,
+        }
+    }
+}
+
+/// 将进程注册到全局管理器，并在后台监视其是否意外退出
+fn register_process_with_manager(child: Child, port: u16) {
+    let pid = child.id();
     if let Ok(mut manager) = PROCESS_MANAGER.lock() {
+        manager.set_port(port);
         manager.set_child(child);
     }
+    if settings_lock().prevent_idle {
+        power::prevent_idle(pid);
+    }
+    watch_process_exit(pid);
+}
+
+/// 定期检查主进程是否退出（进程被替换或已清理时结束监视）
+///
+/// 处于 `starting` 状态时同时探测端口，端口开始监听即标记为 `running`。
+fn watch_process_exit(pid: u32) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(PROCESS_EXIT_POLL_INTERVAL);
+        let starting_port = {
+            let mut manager = PROCESS_MANAGER.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if manager.pid() != Some(pid) || manager.check_exit() {
+                break;
+            }
+            manager.port().filter(|_| manager.state() == N8nProcessState::Starting)
+        };
+
+        if starting_port.is_some_and(is_port_listening) {
+            let mut manager = PROCESS_MANAGER.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if manager.pid() == Some(pid) {
+                manager.mark_running();
+            }
+        }
+    });
+}
+
+/// 检查本机端口是否已开始监听
+fn is_port_listening(port: u16) -> bool {
+    let address = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+    std::net::TcpStream::connect_timeout(&address, PROCESS_EXIT_POLL_INTERVAL).is_ok()
+}
+
+/// 获取 n8n 服务的访问地址
+pub fn n8n_base_url(port: u16) -> String {
+    format!("http://{N8N_SERVICE_HOST}:{port}")
+}
+
+/// 从 `preferred` 开始查找本机可绑定的端口（不超过 5700），全部被占用时返回 None
+///
+/// 通过实际绑定 `127.0.0.1` 检测占用，绑定后立即释放。
+pub fn find_available_port(preferred: u16) -> Option<u16> {
+    (preferred..=N8N_PORT_SEARCH_END.max(preferred))
+        .find(|port| TcpListener::bind((N8N_SERVICE_HOST, *port)).is_ok())
 }
 
 // --- 测试模块 ---
@@ -378,7 +1232,7 @@ mod tests {
 
     #[test]
     fn test_process_manager_child_management() {
-        let mut manager = ProcessManager::new();
+        let manager = Mutex::new(ProcessManager::new());
 
         // 模拟一个子进程（使用 sleep 命令）
         #[cfg(unix)]
@@ -394,13 +1248,14 @@ mod tests {
             .spawn()
             .expect("Failed to spawn timeout process");
 
-        manager.set_child(child);
-        assert!(manager.has_child());
+        manager.lock().expect("lock poisoned").set_child(child);
+        assert!(manager.lock().expect("lock poisoned").has_child());
 
-        manager.kill_child();
-        // 注意：kill_child 后 has_child 应该返回 false
-        // 但实际实现中，take() 会取出 child，所以 has_child 为 false
+        assert!(stop_child(&manager).expect("Failed to kill child"));
+        // 终止后子进程句柄已被取出
+        let manager = manager.lock().expect("lock poisoned");
         assert!(!manager.has_child());
+        assert_eq!(manager.state(), N8nProcessState::Stopped);
     }
 
     #[test]
@@ -417,15 +1272,40 @@ mod tests {
             assert!(result.is_ok());
             let url = result.expect("Failed to get node URL");
             assert!(url.contains("darwin-x64"));
-        } else if cfg!(target_os = "windows") {
-            assert!(result.is_ok());
+        } else if cfg!(target_os = "linux") && cfg!(target_arch = "x86_64") {
+            let url = result.expect("Failed to get node URL");
+            assert!(url.contains("linux-x64"));
+            assert!(url.ends_with(".tar.gz"));
+        } else if cfg!(target_os = "windows") && cfg!(target_arch = "x86_64") {
             let url = result.expect("Failed to get node URL");
             assert!(url.contains("win-x64"));
             assert!(url.ends_with(".zip"));
+        } else if cfg!(target_os = "windows") && cfg!(target_arch = "aarch64") {
+            let url = result.expect("Failed to get node URL");
+            assert!(url.contains("win-arm64"));
         }
         // 其他平台可能返回错误，这是预期的
     }
 
+    #[test]
+    fn test_windows_arm64_runtime() {
+        assert_eq!(node_platform_arch("windows", "aarch64"), Some("win-arm64"));
+        assert_eq!(node_platform_arch("windows", "x86_64"), Some("win-x64"));
+        assert_eq!(node_platform_arch("windows", "x86"), None);
+
+        // win-arm64 压缩包与 win-x64 布局相同：node.exe 与 npm.cmd 位于顶层目录中
+        let runtime_dir = temp_dir().join("n8n_win_arm64_runtime_test");
+        let _ = fs::remove_dir_all(&runtime_dir);
+        let package_dir = runtime_dir.join("node-v22.22.0-win-arm64");
+        fs::create_dir_all(package_dir.join("node_modules/npm/bin")).expect("创建运行时目录失败");
+        fs::write(package_dir.join("node.exe"), "").expect("写入 node.exe 失败");
+        fs::write(package_dir.join("npm.cmd"), "").expect("写入 npm.cmd 失败");
+        fs::write(package_dir.join("node_modules/npm/bin/node.exe"), "").expect("写入 shim 失败");
+
+        assert_eq!(get_node_binary_path(runtime_dir.clone()), package_dir.join("node.exe"));
+        let _ = fs::remove_dir_all(&runtime_dir);
+    }
+
     #[test]
     fn test_is_node_binary_file() {
         let _node_path = PathBuf::from("/usr/bin/node");
@@ -439,6 +1319,19 @@ mod tests {
         assert!(!is_node_binary_file(&PathBuf::from("python")));
     }
 
+    #[test]
+    fn test_missing_shared_libraries() {
+        let stderr = "node: error while loading shared libraries: libstdc++.so.6: cannot open shared object file: No such file or directory\n\
+            node: /lib/x86_64-linux-gnu/libc.so.6: version `GLIBC_2.28' not found (required by node)\n\
+            node: /lib/x86_64-linux-gnu/libc.so.6: version `GLIBC_2.28' not found (required by node)";
+
+        assert_eq!(
+            missing_shared_libraries(stderr),
+            vec!["libstdc++.so.6".to_string(), "libc.so.6 (GLIBC_2.28)".to_string()]
+        );
+        assert!(missing_shared_libraries("Segmentation fault").is_empty());
+    }
+
     #[test]
     fn test_format_nodejs_url() {
         let url = format_nodejs_url("darwin-arm64", "tar.gz");
@@ -461,7 +1354,59 @@ mod tests {
     fn test_node_download_urls_include_mirror() {
         let urls = get_node_download_urls().expect("should build download urls");
         assert!(!urls.is_empty());
-        assert!(urls[0].contains("nodejs.org/dist"));
+        assert!(urls.iter().any(|url| url.contains("nodejs.org/dist")));
+    }
+
+    #[test]
+    fn test_find_available_port_skips_bound_port() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).expect("绑定端口失败");
+        let port = listener.local_addr().expect("读取端口失败").port();
+        assert_eq!(find_available_port(port), None);
+
+        drop(listener);
+        assert_eq!(find_available_port(port), Some(port));
+    }
+
+    #[test]
+    fn test_node_mirror_order_follows_region() {
+        assert_eq!(node_mirror_bases(&DownloadMirror::Auto, false)[0], NODEJS_BASE_URL);
+        assert_eq!(node_mirror_bases(&DownloadMirror::Auto, true)[0], NODEJS_HUAWEI_MIRROR_URL);
+        assert!(node_mirror_bases(&DownloadMirror::Auto, true).contains(&NODEJS_BASE_URL.to_string()));
+
+        let official = node_mirror_bases(&DownloadMirror::Official, true);
+        assert_eq!(official[0], NODEJS_BASE_URL);
+        assert_eq!(official.len(), 3);
+
+        let custom = node_mirror_bases(&DownloadMirror::Custom("https://npmmirror.com/mirrors/node".into()), false);
+        assert_eq!(custom[0], "https://npmmirror.com/mirrors/node");
+        assert_eq!(custom.len(), 4);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_terminate_stale_processes_only_kills_own_instances() {
+        let base = temp_dir().join("n8n_stale_process_test");
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).expect("创建测试目录失败");
+
+        // 命令行包含数据基础目录的视为本应用启动的 n8n，其余进程不受影响
+        let mut own = Command::new("sh")
+            .args(["-c", &format!("sleep 30; : {}", base.display())])
+            .spawn()
+            .expect("启动测试进程失败");
+        let mut unrelated = Command::new("sleep").arg("30").spawn().expect("启动测试进程失败");
+        let pid_file = base.join(N8N_PID_FILENAME);
+        fs::write(&pid_file, format!("{}\n{}", own.id(), unrelated.id())).expect("写入 PID 文件失败");
+
+        terminate_stale_processes(&pid_file);
+
+        assert!(own.wait().is_ok());
+        assert!(matches!(unrelated.try_wait(), Ok(None)));
+        assert!(!pid_file.exists());
+
+        let _ = unrelated.kill();
+        let _ = unrelated.wait();
+        let _ = fs::remove_dir_all(&base);
     }
 
     #[test]
@@ -472,9 +1417,15 @@ mod tests {
             n8n_binary: PathBuf::from("/app/n8n"),
             user_data_dir: temp_dir.clone(),
             additional_envs: HashMap::from([("TEST_KEY".to_string(), "TEST_VALUE".to_string())]),
+            extra_args: vec!["--tunnel".to_string()],
+            log_handler: None,
+            stdio: ProcessStdio::Piped,
+            log_file: None,
+            worker_count: 0,
+            pid_file: None,
         };
 
-        let command_result = build_n8n_command(&config);
+        let command_result = build_n8n_command(&config, N8N_MAIN_SUBCOMMAND);
         assert!(command_result.is_ok());
 
         // 命令应该包含必要的参数和环境变量
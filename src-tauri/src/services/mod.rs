@@ -1,2 +1,9 @@
+pub mod archive;
+pub mod download_control;
 pub mod downloader;
-pub mod manager;
\ No newline at end of file
+pub mod http;
+pub mod keychain;
+pub mod logging;
+pub mod manager;
+pub mod power;
+pub mod privileges;
@@ -0,0 +1,42 @@
+//! 空闲 / App Nap 防护
+//!
+//! macOS 在应用窗口隐藏后可能对应用执行 App Nap 节流，导致托管的 n8n 进程定时触发器延迟。
+//! n8n 运行期间启动 `caffeinate -i -w <pid>` 阻止系统空闲休眠，n8n 退出时 caffeinate 随之退出，
+//! 关闭 n8n 时也会主动结束。其他平台暂不处理。
+
+use std::process::Child;
+use std::sync::{LazyLock, Mutex};
+
+/// 当前持有的空闲防护进程
+static IDLE_BLOCKER: LazyLock<Mutex<Option<Child>>> = LazyLock::new(|| Mutex::new(None));
+
+/// 在 `pid` 对应的进程运行期间阻止系统空闲（重复调用时替换旧的防护）
+pub fn prevent_idle(pid: u32) {
+    release_idle();
+
+    #[cfg(target_os = "macos")]
+    match std::process::Command::new("caffeinate")
+        .args(["-i", "-w"])
+        .arg(pid.to_string())
+        .spawn()
+    {
+        Ok(child) => {
+            println!("[power] 已启用空闲防护 (caffeinate pid {})", child.id());
+            *IDLE_BLOCKER.lock().unwrap_or_else(|p| p.into_inner()) = Some(child);
+        }
+        Err(error) => eprintln!("[power] 启动 caffeinate 失败: {error}"),
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = pid;
+}
+
+/// 释放空闲防护
+pub fn release_idle() {
+    let blocker = IDLE_BLOCKER.lock().unwrap_or_else(|p| p.into_inner()).take();
+    if let Some(mut child) = blocker {
+        let _ = child.kill();
+        let _ = child.wait();
+        println!("[power] 已释放空闲防护");
+    }
+}
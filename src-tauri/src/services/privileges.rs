@@ -0,0 +1,49 @@
+//! 提权运行检测
+//!
+//! 以 root / 管理员身份运行时，n8n 及其 Node 子进程同样拥有最高权限，`n8n-data` 中创建的文件
+//! 之后以普通用户运行时无法写入。这里只做检测，由调用方提醒用户，不阻止运行。
+
+use std::process::{Command, Stdio};
+
+/// Windows 高完整性级别（管理员提权）的 SID
+#[cfg(windows)]
+const HIGH_MANDATORY_LEVEL_SID: &str = "S-1-16-12288";
+
+/// 当前进程是否以 root / 管理员身份运行（无法检测时视为否）
+pub fn is_elevated() -> bool {
+    #[cfg(unix)]
+    {
+        command_stdout("id", &["-u"]).is_some_and(|uid| uid.trim() == "0")
+    }
+
+    #[cfg(windows)]
+    {
+        // 提权后的令牌带有高完整性级别标签（UAC 下的普通管理员账户不带）
+        command_stdout("whoami", &["/groups"])
+            .is_some_and(|groups| groups.contains(HIGH_MANDATORY_LEVEL_SID))
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        false
+    }
+}
+
+/// 执行命令并返回标准输出（执行失败时返回 None）
+fn command_stdout(program: &str, args: &[&str]) -> Option<String> {
+    let mut command = Command::new(program);
+    command.args(args).stdin(Stdio::null()).stderr(Stdio::null());
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW：不弹出控制台窗口
+        command.creation_flags(0x08000000);
+    }
+
+    let output = command.output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}